@@ -45,7 +45,8 @@ fn main() {
     )
     .expect("interpret failed");
 
-    // Bug where clearing an empty list leaks memory.
+    // Clearing an empty list frees a null pointer, which must not
+    // be recorded as an allocation.
     vm.interpret(
         "main",
         r#"
@@ -0,0 +1,69 @@
+//! Compares copying a large numeric list out of Wren by setting the list
+//! handle on every element, against setting it once before the loop.
+//!
+//! Run in release mode for meaningful numbers:
+//!
+//! ```sh
+//! cargo run --release --example list_bench
+//! ```
+use rust_wren::{bindings, handle::WrenHandle, prelude::*, value::FromWren, WrenContext, WrenResult};
+use std::time::{Duration, Instant};
+
+const SIZE: usize = 100_000;
+const RUNS: u32 = 20;
+
+/// Copy loop as it was, setting the list handle into slot 0 for every element.
+fn copy_per_element(ctx: &mut WrenContext, handle: &WrenHandle, size: usize) -> WrenResult<Vec<f64>> {
+    let mut result = Vec::with_capacity(size);
+    ctx.ensure_slots(2);
+
+    for index in 0..size {
+        ToWren::put(handle, ctx, 0);
+        unsafe {
+            bindings::wrenGetListElement(ctx.vm_ptr(), 0, index as i32, 1);
+        }
+        result.push(<f64 as FromWren>::get_slot(ctx, 1)?);
+    }
+
+    Ok(result)
+}
+
+fn time<F: FnMut() -> WrenResult<Vec<f64>>>(mut func: F) -> WrenResult<Duration> {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let result = func()?;
+        assert_eq!(result.len(), SIZE);
+    }
+    Ok(start.elapsed() / RUNS)
+}
+
+fn main() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "list_bench",
+        &format!(
+            r#"
+    var numbers = []
+    for (i in 0...{}) numbers.add(i)
+    "#,
+            SIZE
+        ),
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        let handle = ctx.get_var("list_bench", "numbers")?.leak()?;
+        let list = ctx.get_list("list_bench", "numbers")?;
+
+        let per_element = time(|| copy_per_element(ctx, &handle, SIZE))?;
+        let hoisted = time(|| list.to_vec::<f64>(ctx))?;
+
+        println!("{} elements, average of {} runs", SIZE, RUNS);
+        println!("  handle set per element: {:?}", per_element);
+        println!("  handle set once:        {:?}", hoisted);
+
+        Ok(())
+    })
+    .expect("Context block failed");
+}
@@ -0,0 +1,57 @@
+//! Compares summing a large numeric list by copying it into a `Vec`,
+//! against visiting each element with `for_each_f64`.
+//!
+//! Run in release mode for meaningful numbers:
+//!
+//! ```sh
+//! cargo run --release --example list_sum_bench
+//! ```
+use rust_wren::{prelude::*, WrenResult};
+use std::time::{Duration, Instant};
+
+const SIZE: usize = 100_000;
+const RUNS: u32 = 20;
+
+fn time<F: FnMut() -> WrenResult<f64>>(mut func: F) -> WrenResult<Duration> {
+    let expected = (0..SIZE).sum::<usize>() as f64;
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        assert_eq!(func()?, expected);
+    }
+    Ok(start.elapsed() / RUNS)
+}
+
+fn main() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "list_sum_bench",
+        &format!(
+            r#"
+    var numbers = []
+    for (i in 0...{}) numbers.add(i)
+    "#,
+            SIZE
+        ),
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        let list = ctx.get_list("list_sum_bench", "numbers")?;
+
+        let copied = time(|| list.to_vec::<f64>(ctx).map(|numbers| numbers.iter().sum()))?;
+        let visited = time(|| {
+            let mut sum = 0.0;
+            list.for_each_f64(ctx, |n| sum += n)?;
+            Ok(sum)
+        })?;
+
+        println!("{} elements, average of {} runs", SIZE, RUNS);
+        println!("  to_vec then sum: {:?}", copied);
+        println!("  for_each_f64:    {:?}", visited);
+
+        Ok(())
+    })
+    .expect("Context block failed");
+}
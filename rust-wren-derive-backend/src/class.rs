@@ -36,7 +36,7 @@ pub fn gen_to_wren_impl(class: Ident) -> TokenStream {
         /// The foreign class binding must be registered for the type to be
         /// moved into Wren.
         ///
-        /// This implementation is effectively the same as the generated `__wren_allocate` method.
+        /// See `WrenContext::new_foreign` to also keep a handle to the instance.
         ///
         /// # Safety
         ///
@@ -44,60 +44,20 @@ pub fn gen_to_wren_impl(class: Ident) -> TokenStream {
         ///
         /// # Errors
         ///
-        /// Returns an error if the foreign class binding cannot be found.
+        /// Aborts the current fiber with a foreign error if the foreign class binding
+        /// cannot be found, or the class hasn't been declared in Wren.
         ///
         /// # Implementation
         ///
         /// Allocates space in Wren's heap to contain the value.
         impl rust_wren::value::ToWren for #class {
             fn put(self, ctx: &mut rust_wren::WrenContext, slot: i32) {
-                use rust_wren::{prelude::*, bindings, value::ToWren};
                 assert!((slot as usize) < ctx.slot_count());
 
-                // To allocate a new foreign object, we must first lookup its class.
-                let module_name = {
-                    let userdata = unsafe { ctx.user_data().unwrap() }; // TODO: Return Error
-                    userdata
-                        .foreign
-                        .get_class_key::<Self>()
-                        .unwrap()
-                        .module
-                        .clone()
-                };
-                let class_name = <Self as WrenForeignClass>::NAME;
-
-                // Class declarations are simple variables in Wren.
-                let class_ref = ctx.get_var(&module_name, class_name).unwrap();
-
-                // Prepare for foreign value allocation.
-                ToWren::put(class_ref, ctx, slot as i32);
-
-                // Wren wants to own the memory containing the data backing the foreign function.
-                let wren_ptr: *mut WrenCell<Self> = unsafe {
-                    bindings::wrenSetSlotNewForeign(
-                        ctx.vm_ptr(),
-                        slot as i32,
-                        slot as i32,
-                        ::std::mem::size_of::<WrenCell<Self>>() as usize,
-                    ) as _
-                };
-                let wren_val: &mut WrenCell<Self> = unsafe { wren_ptr.as_mut().unwrap() };
-
-                // All foreign classes are wrapped in WrenCell, because it's possible to
-                // borrow the value out of Wren multiple times.
-                let mut rust_val = WrenCell::new(self);
-
-                // Swap the constructed object on the stack with the heap memory
-                // owned by Wren.
-                ::std::mem::swap(wren_val, &mut rust_val);
-
-                // After the swap, this now contains the value Wren wrote after it's allocation,
-                // which is zeroed. However it's safer to treat it as undefined. Dropping a value
-                // that may contain resources like boxes or file handles could cause issues if
-                // it's zeroed or filled with junk.
-                //
-                // We're intentionally disabling drop since it wasn't initialised by Rust.
-                ::std::mem::forget(rust_val);
+                // Failures abort the current fiber, and are reported as a runtime error.
+                if let Err(err) = ctx.put_foreign(self, slot) {
+                    rust_wren::value::ToWren::put(rust_wren::ForeignError::new(err), ctx, slot);
+                }
             }
         }
     }
@@ -0,0 +1,98 @@
+//! `wren_class` attribute on C-like enums.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Fields, ItemEnum};
+
+/// Generate implementations of `FromWren` and `ToWren` for a C-like enum.
+///
+/// The enum is passed to Wren as a number containing its discriminant. Coming
+/// from Wren, either the discriminant or the variant name as a string is accepted.
+pub fn gen_enum_impl(item: &ItemEnum) -> syn::Result<TokenStream> {
+    if item.generics != Default::default() {
+        return Err(syn::Error::new_spanned(
+            &item.generics,
+            "#[wren_class] cannot be used on enums with lifetime parameters or generics",
+        ));
+    }
+
+    for variant in &item.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                &variant.fields,
+                "#[wren_class] enums can only have unit variants, data-carrying variants are not supported",
+            ));
+        }
+    }
+
+    let ident = &item.ident;
+    let enum_name = ident.to_string();
+    let variants = item.variants.iter().map(|variant| &variant.ident).collect::<Vec<_>>();
+    let variant_names = variants.iter().map(|variant| variant.to_string()).collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl<'wren> rust_wren::value::FromWren<'wren> for #ident {
+            type Output = Self;
+
+            fn get_slot(ctx: &rust_wren::WrenContext, slot_num: i32) -> rust_wren::WrenResult<Self::Output> {
+                use rust_wren::{types::WrenType, WrenError};
+
+                match ctx.slot_type(slot_num as usize) {
+                    Some(WrenType::Number) => {
+                        let value = ctx.get_slot::<f64>(slot_num)?;
+                        #(
+                            if value == (#ident::#variants as i64) as f64 {
+                                return Ok(#ident::#variants);
+                            }
+                        )*
+                        Err(WrenError::UnknownVariant { ty: #enum_name, value: value.to_string() })
+                    }
+                    Some(WrenType::String) => {
+                        let value = ctx.get_slot::<&str>(slot_num)?;
+                        match value {
+                            #(#variant_names => Ok(#ident::#variants),)*
+                            _ => Err(WrenError::UnknownVariant { ty: #enum_name, value: value.to_string() }),
+                        }
+                    }
+                    Some(actual) => Err(WrenError::SlotType { expected: WrenType::Number, actual }),
+                    None => Err(WrenError::SlotOutOfBounds(slot_num)),
+                }
+            }
+        }
+
+        impl rust_wren::value::ToWren for #ident {
+            #[inline]
+            fn put(self, ctx: &mut rust_wren::WrenContext, slot: i32) {
+                rust_wren::value::ToWren::put(self as i64 as f64, ctx, slot)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_unit_variants() {
+        let item: ItemEnum = syn::parse_quote! {
+            enum State {
+                Idle,
+                Running = 4,
+            }
+        };
+
+        assert!(gen_enum_impl(&item).is_ok());
+    }
+
+    #[test]
+    fn test_reject_data_variants() {
+        let item: ItemEnum = syn::parse_quote! {
+            enum State {
+                Idle,
+                Running(f64),
+            }
+        };
+
+        assert!(gen_enum_impl(&item).is_err());
+    }
+}
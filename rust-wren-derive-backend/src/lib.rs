@@ -3,9 +3,13 @@
 //! A `proc_macro` crate cannot export anything that's not a procedural macro, requiring
 //! any public functions or structs to live in a seperate crate.
 mod class;
+mod enumeration;
 mod method;
+mod number;
 mod prop;
 
 pub use class::{gen_from_wren_impl, gen_to_wren_impl, WrenClassArgs};
-pub use method::build_wren_methods;
-pub use prop::{gen_class_props, strip_prop_attrs};
+pub use enumeration::gen_enum_impl;
+pub use method::{build_wren_methods, declare_wren_methods};
+pub use number::gen_number_impl;
+pub use prop::{declare_class_props, gen_class_props, strip_prop_attrs};
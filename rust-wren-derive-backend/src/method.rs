@@ -1,11 +1,13 @@
 use proc_macro2::{Literal, Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned};
+use std::collections::HashMap;
 use syn::{
     parenthesized,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
     spanned::Spanned,
-    Attribute, Expr, ExprAssign, FnArg, Ident, ImplItem, ImplItemMethod, ItemImpl, Lit, Signature, Token, Type,
+    Attribute, Expr, ExprAssign, ExprLit, FnArg, GenericArgument, Ident, ImplItem, ImplItemConst, ImplItemMethod,
+    ItemImpl, Lit, Meta, MetaNameValue, Pat, PathArguments, ReturnType, Signature, Token, Type,
 };
 
 pub fn build_wren_methods(mut ast: ItemImpl) -> syn::Result<TokenStream> {
@@ -21,15 +23,28 @@ pub fn build_wren_methods(mut ast: ItemImpl) -> syn::Result<TokenStream> {
         ))
     } else {
         // TODO: Return ast
-        let tokens = impl_methods(&ast.self_ty, &mut ast.items)?;
+        let (tokens, _) = impl_methods(&ast.self_ty, &mut ast.items)?;
         // let gen = quote! { #ast };
         Ok(tokens)
     }
 }
 
-fn impl_methods(cls: &Type, impls: &mut Vec<ImplItem>) -> syn::Result<TokenStream> {
+/// Wren declarations of the constructors and foreign methods in an impl
+/// block, in the same order as the generated `__wren_declare_methods`.
+pub fn declare_wren_methods(ast: &ItemImpl) -> syn::Result<Vec<String>> {
+    let mut items = ast.items.clone();
+    let (_, decls) = impl_methods(&ast.self_ty, &mut items)?;
+    Ok(decls)
+}
+
+fn impl_methods(cls: &Type, impls: &mut Vec<ImplItem>) -> syn::Result<(TokenStream, Vec<String>)> {
     let mut new_impl = vec![];
     let mut specs = vec![];
+    let mut decls = vec![];
+    // Declarations gated by the same `#[cfg]` attributes as their methods.
+    let mut decl_items = vec![];
+    let mut finalize_hook: Option<Ident> = None;
+    let mut has_construct = false;
 
     for im in impls.iter_mut() {
         match im {
@@ -38,16 +53,50 @@ fn impl_methods(cls: &Type, impls: &mut Vec<ImplItem>) -> syn::Result<TokenStrea
 
                 new_impl.push(tokens);
 
-                // Don't add the constructor to method bindings.
-                if matches!(spec.ty, WrenFnType::Method) {
-                    specs.push(spec);
+                match spec.ty {
+                    WrenFnType::Method => {
+                        decl_items.push(gen_decl(&spec));
+                        decls.push(spec.decl.clone());
+                        specs.push(spec);
+                    }
+                    // Don't add the constructor to method bindings.
+                    WrenFnType::Construct => {
+                        has_construct = true;
+                        decl_items.push(gen_decl(&spec));
+                        decls.push(spec.decl.clone());
+                    }
+                    // Finalize hook is not visible to Wren.
+                    WrenFnType::Finalize => {
+                        if finalize_hook.is_some() {
+                            return Err(syn::Error::new_spanned(
+                                &spec.ident,
+                                "Only one method can be marked with #[finalize]",
+                            ));
+                        }
+                        finalize_hook = Some(spec.ident);
+                    }
+                    WrenFnType::Operator => {}
                 }
             }
+            ImplItem::Const(item_const) if has_attr(&item_const.attrs, "wren_const") => {
+                let (tokens, spec) = handle_const(item_const)?;
+                new_impl.push(tokens);
+                decl_items.push(gen_decl(&spec));
+                decls.push(spec.decl.clone());
+                specs.push(spec);
+            }
             _ => new_impl.push(quote! { #im }),
         }
     }
 
-    let finalizer = gen_wren_finalize()?;
+    // Without a constructor, the class is constructed from its `Default` implementation.
+    if !has_construct {
+        decls.insert(0, "construct new() {}".to_owned());
+        decl_items.insert(0, quote! { "construct new() {}" });
+        new_impl.push(gen_default_construct(cls));
+    }
+
+    let finalizer = gen_wren_finalize(finalize_hook.as_ref())?;
 
     let register = gen_register(&specs)?;
 
@@ -60,28 +109,116 @@ fn impl_methods(cls: &Type, impls: &mut Vec<ImplItem>) -> syn::Result<TokenStrea
             #finalizer
 
             #register
+
+            /// Wren declarations of the constructor and foreign methods.
+            fn __wren_declare_methods() -> &'static [&'static str] {
+                &[#(#decl_items),*]
+            }
         }
     };
 
-    Ok(tokens)
+    Ok((tokens, decls))
 }
 
 fn handle_method(cls: &Type, method: &mut ImplItemMethod) -> syn::Result<(TokenStream, WrenFnSpec)> {
     let spec = WrenFnSpec::build(&method.sig, &mut method.attrs)?;
 
     // Strip attributes so we can compile.
-    method.attrs.retain(|attr| !attr.path.is_ident("construct"));
+    method.attrs.retain(|attr| {
+        !["construct", "finalize", "to_string", "manual_return"]
+            .iter()
+            .any(|name| attr.path.is_ident(name))
+    });
 
     // FIXME: These generation functions should operate on WrenFnSpec
     let tokens = match spec.ty {
         WrenFnType::Construct => gen_wren_construct(cls, method)?,
-        WrenFnType::Method => gen_wren_method(cls, method)?,
+        WrenFnType::Method => gen_wren_method(cls, method, spec.manual_return)?,
         _ => quote! { #method },
     };
 
     Ok((tokens, spec))
 }
 
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path.is_ident(name))
+}
+
+/// Conditional compilation attributes of an item, which are copied onto
+/// the generated wrapper, registration and declaration, so a method that
+/// is compiled out leaves nothing behind that refers to it.
+fn cfg_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
+    attrs.iter().filter(|attr| attr.path.is_ident("cfg")).cloned().collect()
+}
+
+/// Joins the lines of an item's doc comment, which are `#[doc = "..."]` attributes.
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue { lit: Lit::Str(s), .. })) => Some(s.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_owned).unwrap_or(line))
+        .collect::<Vec<_>>();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// Wren declaration of a method, as an element of the declarations array.
+fn gen_decl(spec: &WrenFnSpec) -> TokenStream {
+    let cfg = &spec.cfg;
+    let decl = &spec.decl;
+    quote! { #(#cfg)* #decl }
+}
+
+/// Exposes an associated const marked with `#[wren_const]` as a static getter.
+fn handle_const(item_const: &mut ImplItemConst) -> syn::Result<(TokenStream, WrenFnSpec)> {
+    let args = WrenMethodArgs::take_attr(&mut item_const.attrs, "wren_const")?;
+    let const_ident = item_const.ident.clone();
+    let wren_name = args.name.clone().unwrap_or_else(|| const_ident.clone());
+
+    let spec = WrenFnSpec {
+        ident: const_ident.clone(),
+        wrap_ident: format_ident!("__wren_wrap_const_{}", const_ident),
+        args,
+        ty: WrenFnType::Method,
+        arity: 0,
+        sig: wren_name.to_string(),
+        is_static: true,
+        is_construct: false,
+        decl: format!("foreign static {}", wren_name),
+        manual_return: false,
+        cfg: cfg_attrs(&item_const.attrs),
+        doc: doc_comment(&item_const.attrs),
+    };
+
+    let wrap_ident = &spec.wrap_ident;
+    let cfg = &spec.cfg;
+    let tokens = quote! {
+        #item_const
+
+        #(#cfg)*
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        extern "C" fn #wrap_ident(vm: *mut rust_wren::bindings::WrenVM) {
+            let vm: &mut rust_wren::bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+            let mut ctx = rust_wren::WrenContext::new(vm);
+
+            // Const is copied out to Wren on each access.
+            ctx.ensure_slots(1);
+            rust_wren::value::ToWren::put(<Self>::#const_ident, &mut ctx, 0);
+        }
+    };
+
+    Ok((tokens, spec))
+}
+
 fn gen_wren_construct(_cls: &Type, method: &ImplItemMethod) -> syn::Result<TokenStream> {
     let new_method = method.sig.ident.clone();
     let method_name = new_method.to_string();
@@ -121,6 +258,22 @@ fn gen_wren_construct(_cls: &Type, method: &ImplItemMethod) -> syn::Result<Token
     // Wrapped in WrenCell because the multiple pointers can be retrieved from VM.
     let ty = quote! { WrenCell<Self> };
 
+    // Fallible constructors abort the fiber with their error.
+    let construct = if returns_result(&method.sig)? {
+        quote! {
+            match <Self>::#new_method(#(#args),*) {
+                Ok(value) => value,
+                Err(err) => {
+                    let err: rust_wren::ForeignError = err;
+                    rust_wren::value::ToWren::put(err, &mut ctx, 0);
+                    return;
+                }
+            }
+        }
+    } else {
+        quote! { <Self>::#new_method(#(#args),*) }
+    };
+
     // Get span to function return type, so user gets a nice error when
     // the return type is incorrect.
     let method_span = method.sig.span().clone();
@@ -138,43 +291,111 @@ fn gen_wren_construct(_cls: &Type, method: &ImplItemMethod) -> syn::Result<Token
         extern "C" fn __wren_allocate(vm: *mut rust_wren::bindings::WrenVM) {
             use rust_wren::class::WrenCell;
 
-            // Wren wants to own the memory containing the data backing the foreign function.
-            let wren_ptr: *mut #ty = unsafe {
-                rust_wren::bindings::wrenSetSlotNewForeign(vm, 0, 0, ::std::mem::size_of::<#ty>() as usize) as _
-            };
-            let wren_val: &mut #ty = unsafe { wren_ptr.as_mut().unwrap() };
-
             // Context for extracting slots.
-            // If construct doesn't take arguments, this `vm` and `ctx` are unused.
+            // If construct doesn't take arguments, this `ctx` is unused.
             let vm: &mut rust_wren::bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
             #[allow(unused_variables)]
             let mut ctx = rust_wren::WrenContext::new(vm);
 
-            // TODO: Constructor method is not required, so make this optional.
-            // TODO: Validate return type of constructor.
-            let mut rust_val: WrenCell<Self> = WrenCell::new(<Self>::#new_method(#(#args),*));
+            // The value is constructed before Wren allocates the foreign object. When
+            // an argument or the constructor fails, the fiber is aborted without leaving
+            // an uninitialised object behind for the finalizer to drop.
+            let rust_val: #ty = WrenCell::new(#construct);
 
-            // Swap the constructed object on the stack with the heap memory
-            // owned by Wren.
-            ::std::mem::swap(wren_val, &mut rust_val);
+            // Wren wants to own the memory containing the data backing the foreign function.
+            let wren_ptr: *mut #ty = unsafe {
+                rust_wren::bindings::wrenSetSlotNewForeign(ctx.vm_ptr(), 0, 0, ::std::mem::size_of::<#ty>() as usize) as _
+            };
 
-            // After the swap, this now contains the value Wren wrote after it's allocation,
-            // which is zeroed. However it's safer to treat it as undefined. Dropping a value
-            // that may contain resources like boxes or file handles could cause issues if
-            // it's zeroed or filled with junk.
-            //
-            // We're intentionally disabling drop since it wasn't initialised by Rust.
-            ::std::mem::forget(rust_val);
+            // Move the constructed value into the heap memory owned by Wren. The
+            // memory is uninitialised, so it must not be dropped.
+            unsafe { wren_ptr.write(rust_val) };
         }
     };
 
     Ok(tokens)
 }
 
-fn gen_wren_finalize() -> syn::Result<TokenStream> {
+/// Generates an allocation function constructing the class with `Default::default()`,
+/// for impl blocks without a `#[construct]` method.
+fn gen_default_construct(cls: &Type) -> TokenStream {
+    // Span to the class, so a missing `Default` implementation points at it.
+    quote_spanned! {cls.span()=>
+        /// Allocation function called by Wren when a class is constructed
+        /// with `new()`, using the class' `Default` implementation.
+        ///
+        /// See: [Storing C Data](https://wren.io/embedding/storing-c-data.html)
+        extern "C" fn __wren_allocate(vm: *mut rust_wren::bindings::WrenVM) {
+            use rust_wren::class::WrenCell;
+
+            let vm: &mut rust_wren::bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+            let ctx = rust_wren::WrenContext::new(vm);
+
+            let rust_val: WrenCell<Self> = WrenCell::new(<Self as ::std::default::Default>::default());
+
+            // Wren wants to own the memory containing the data backing the foreign function.
+            let wren_ptr: *mut WrenCell<Self> = unsafe {
+                rust_wren::bindings::wrenSetSlotNewForeign(ctx.vm_ptr(), 0, 0, ::std::mem::size_of::<WrenCell<Self>>() as usize) as _
+            };
+
+            // Move the constructed value into the heap memory owned by Wren. The
+            // memory is uninitialised, so it must not be dropped.
+            unsafe { wren_ptr.write(rust_val) };
+        }
+    }
+}
+
+/// Whether the method's return type is a `Result`, ie. `rust_wren::Result<Self>`.
+///
+/// Only `ForeignError` aborts the fiber, so a `Result` with any other
+/// error type is rejected.
+fn returns_result(sig: &Signature) -> syn::Result<bool> {
+    let segment = match &sig.output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Path(type_path) => match type_path.path.segments.last() {
+                Some(segment) if segment.ident == "Result" => segment,
+                _ => return Ok(false),
+            },
+            _ => return Ok(false),
+        },
+        ReturnType::Default => return Ok(false),
+    };
+
+    // A single argument is the `rust_wren::Result` alias.
+    let is_foreign_error = match &segment.arguments {
+        PathArguments::AngleBracketed(generics) => match generics.args.len() {
+            1 => true,
+            2 => matches!(
+                generics.args.last(),
+                Some(GenericArgument::Type(Type::Path(err_path)))
+                    if err_path.path.segments.last().map(|s| s.ident == "ForeignError").unwrap_or(false)
+            ),
+            _ => false,
+        },
+        _ => false,
+    };
+
+    if is_foreign_error {
+        Ok(true)
+    } else {
+        Err(syn::Error::new_spanned(
+            &sig.output,
+            "fallible constructors must return rust_wren::Result<Self>, with a ForeignError as error",
+        ))
+    }
+}
+
+fn gen_wren_finalize(finalize_hook: Option<&Ident>) -> syn::Result<TokenStream> {
     // Wrapped in WrenCell because the multiple pointers can be retrieved from VM.
     let ty = quote! { ::rust_wren::class::WrenCell<Self> };
 
+    // User defined teardown, run while the value is still in Wren's heap.
+    let hook_call = finalize_hook.map(|ident| {
+        quote! {
+            (data as *mut #ty).as_mut().unwrap().borrow_mut().#ident();
+        }
+    });
+
     Ok(quote! {
         /// Finalizer method, called when the object instance is garbage collected.
         ///
@@ -199,6 +420,8 @@ fn gen_wren_finalize() -> syn::Result<TokenStream> {
         /// be dropped by the stack, and the Wren garbage collector will deallocate the
         /// unsafe zeroed struct.
         unsafe extern "C" fn __wren_finalize(data: *mut ::std::os::raw::c_void) {
+            #hook_call
+
             // This zeroed value is assumed initialised, but really it's not. Importantly
             // this value shouldn't be dropped. The drop method for the type could
             // reasonably expect valid contents.
@@ -216,8 +439,9 @@ fn gen_wren_finalize() -> syn::Result<TokenStream> {
 }
 
 /// Generate a method AST.
-fn gen_wren_method(_cls: &Type, method: &mut ImplItemMethod) -> syn::Result<TokenStream> {
+fn gen_wren_method(_cls: &Type, method: &mut ImplItemMethod, manual_return: bool) -> syn::Result<TokenStream> {
     let method_ident = method.sig.ident.clone();
+    let method_name = method_ident.to_string();
 
     let ctx = format_ident!("ctx");
     let (vars, args) = gen_args_from_slots(&ctx, method)?;
@@ -225,8 +449,47 @@ fn gen_wren_method(_cls: &Type, method: &mut ImplItemMethod) -> syn::Result<Toke
     // Cleanup attributes that the Rust compiler won't recognise.
     method.sig.inputs.iter_mut().for_each(strip_injections);
 
+    let call = if manual_return && method.sig.receiver().is_some() {
+        // Method is responsible for putting its result into slot 0.
+        //
+        // Slot 0 is the only root of the receiver, so it's pinned with a handle
+        // while the method runs, in case it replaces slot 0 and then allocates.
+        quote! {
+            let receiver = match ctx.get_slot::<rust_wren::handle::WrenRef>(0) {
+                Ok(receiver) => receiver,
+                Err(err) => {
+                    let wren_error = rust_wren::WrenError::new_foreign_call(
+                            #method_name,
+                            Box::new(rust_wren::WrenError::GetArg { slot: 0, cause: err.into(), })
+                        );
+
+                    rust_wren::ForeignError::Simple(Box::new(wren_error)).put(&mut ctx, 0);
+                    return;
+                }
+            };
+
+            <Self>::#method_ident(#(#args),*);
+            drop(receiver);
+        }
+    } else if manual_return {
+        // Method is responsible for putting its result into slot 0.
+        quote! {
+            <Self>::#method_ident(#(#args),*);
+        }
+    } else {
+        quote! {
+            let result = <Self>::#method_ident(#(#args),*);
+
+            // Method result goes into slot 0
+            ctx.ensure_slots(1);
+            rust_wren::value::ToWren::put(result, &mut ctx, 0);
+        }
+    };
+
     let wrap_ident = format_ident!("__wren_wrap_{}", method.sig.ident);
+    let cfg = cfg_attrs(&method.attrs);
     let wrap = quote! {
+        #(#cfg)*
         #[doc(hidden)]
         extern "C" fn #wrap_ident(vm: *mut rust_wren::bindings::WrenVM) {
             // Context for extracting slots.
@@ -235,12 +498,7 @@ fn gen_wren_method(_cls: &Type, method: &mut ImplItemMethod) -> syn::Result<Toke
 
             #(#vars)*
 
-            // let result = <Self>::#method_ident(#(#args),*);
-            let result = <Self>::#method_ident(#(#args),*);
-
-            // Method result goes into slot 0
-            ctx.ensure_slots(1);
-            rust_wren::value::ToWren::put(result, &mut ctx, 0);
+            #call
         }
     };
 
@@ -464,6 +722,8 @@ fn strip_injections(arg: &mut FnArg) {
 }
 
 fn gen_register(wrappers: &[WrenFnSpec]) -> syn::Result<TokenStream> {
+    validate_signatures(wrappers)?;
+
     let calls = wrappers
         .iter()
         .map(|spec| {
@@ -473,8 +733,14 @@ fn gen_register(wrappers: &[WrenFnSpec]) -> syn::Result<TokenStream> {
 
             let wrap_ident = spec.wrap_ident.clone();
             let func = quote! { #wrap_ident };
+            let cfg = &spec.cfg;
+            let doc = match &spec.doc {
+                Some(doc) => quote! { Some(#doc) },
+                None => quote! { None },
+            };
 
             quote! {
+                #(#cfg)*
                 builder.add_method_binding(
                     <Self as rust_wren::class::WrenForeignClass>::NAME,
                     rust_wren::foreign::ForeignMethod {
@@ -482,18 +748,47 @@ fn gen_register(wrappers: &[WrenFnSpec]) -> syn::Result<TokenStream> {
                         arity: #arity,
                         sig: #sig.to_owned(),
                         func: <Self>::#func,
-                })
+                        doc: #doc,
+                });
             }
         })
         .collect::<Vec<_>>();
 
     Ok(quote! {
         extern "C" fn __wren_register_methods(builder: &mut rust_wren::ModuleBuilder) {
-            #(#calls);*
+            #(#calls)*
         }
     })
 }
 
+/// Methods may share a Wren name as long as their arities differ, because
+/// Wren encodes the arity in the signature. Two methods that produce the
+/// same signature would clobber each other's binding, so it's an error.
+fn validate_signatures(wrappers: &[WrenFnSpec]) -> syn::Result<()> {
+    let mut seen: HashMap<(bool, &str), &Ident> = HashMap::new();
+
+    // Gated methods may be mutually exclusive, so they can't be checked
+    // against each other without knowing which ones are compiled.
+    for spec in wrappers.iter().filter(|spec| spec.cfg.is_empty()) {
+        if let Some(existing) = seen.insert((spec.is_static, spec.sig.as_str()), &spec.ident) {
+            return Err(syn::Error::new_spanned(
+                &spec.ident,
+                format!(
+                    "Wren {}method signature `{}` is already bound to `{}`",
+                    if spec.is_static { "static " } else { "" },
+                    spec.sig,
+                    existing
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Maximum number of parameters Wren allows in a method signature.
+const MAX_ARITY: usize = 16;
+
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct WrenFnSpec {
@@ -514,6 +809,15 @@ pub struct WrenFnSpec {
     is_static: bool,
     /// Indicates whether the method is the class constructor.
     is_construct: bool,
+    /// Declaration of the method in the Wren foreign class.
+    decl: String,
+    /// Indicates whether the method puts its own result into slot 0,
+    /// instead of returning it.
+    manual_return: bool,
+    /// Conditional compilation attributes of the method.
+    cfg: Vec<Attribute>,
+    /// Doc comment of the method, passed to the binding for introspection.
+    doc: Option<String>,
 }
 
 impl WrenFnSpec {
@@ -526,22 +830,106 @@ impl WrenFnSpec {
         // https://docs.rs/syn/1.0.48/syn/enum.FnArg.html
         let is_static = sig.inputs.iter().all(|arg| !matches!(arg, FnArg::Receiver(_))) || sig.inputs.is_empty();
 
-        // Wren does not include the receiver in the function signature, but Rust does.
-        let arity = if !sig.inputs.is_empty() {
-            if is_static {
-                sig.inputs.len()
-            } else {
-                sig.inputs.len() - 1
+        // Wren does not include the receiver or injections in the function signature, but Rust does.
+        let arity = Self::wren_args(sig).count();
+
+        // Wren would only fail to bind the method at runtime.
+        if arity > MAX_ARITY {
+            return Err(syn::Error::new_spanned(
+                sig,
+                format!(
+                    "Wren methods can have at most {} parameters, but `{}` has {}",
+                    MAX_ARITY, ident, arity
+                ),
+            ));
+        }
+
+        let manual_return = attrs.iter().any(|attr| attr.path.is_ident("manual_return"));
+        if manual_return {
+            let is_special = ["construct", "finalize", "to_string"]
+                .iter()
+                .any(|name| attrs.iter().any(|attr| attr.path.is_ident(name)));
+            if is_special {
+                return Err(syn::Error::new_spanned(
+                    sig,
+                    "#[manual_return] can only be used on ordinary methods",
+                ));
             }
-        } else {
-            0
-        };
+            if !matches!(sig.output, ReturnType::Default) {
+                return Err(syn::Error::new_spanned(
+                    &sig.output,
+                    "#[manual_return] method must return `()`, and put its result into slot 0 itself",
+                ));
+            }
+        }
+
+        let doc = doc_comment(attrs);
+        let cfg = cfg_attrs(attrs);
+        if !cfg.is_empty() {
+            let is_lifecycle = ["construct", "finalize"]
+                .iter()
+                .any(|name| attrs.iter().any(|attr| attr.path.is_ident(name)));
+            if is_lifecycle {
+                return Err(syn::Error::new_spanned(
+                    sig,
+                    "#[cfg] can only be used on ordinary methods, not on constructors or finalizers",
+                ));
+            }
+        }
 
         let wren_sig = Self::make_wren_signature(sig, args.name.as_ref());
 
-        if attrs.iter().any(|attr| attr.path.is_ident("construct")) {
+        if attrs.iter().any(|attr| attr.path.is_ident("finalize")) {
+            // Finalize hook
+            if is_static || sig.inputs.len() != 1 {
+                Err(syn::Error::new_spanned(
+                    sig,
+                    "Finalize method must only receive `self` by reference, ie. `&self` or `&mut self`",
+                ))
+            } else {
+                Ok(WrenFnSpec {
+                    ident,
+                    wrap_ident,
+                    args,
+                    ty: WrenFnType::Finalize,
+                    arity,
+                    sig: wren_sig,
+                    is_static,
+                    is_construct: false,
+                    decl: String::new(),
+                    manual_return,
+                    cfg,
+                    doc,
+                })
+            }
+        } else if attrs.iter().any(|attr| attr.path.is_ident("to_string")) {
+            // Wren's string conversion, used by interpolation and `System.print()`
+            if is_static || arity != 0 {
+                Err(syn::Error::new_spanned(
+                    sig,
+                    "To string method must only receive `self` by reference, ie. `&self`",
+                ))
+            } else {
+                Ok(WrenFnSpec {
+                    ident,
+                    wrap_ident,
+                    args,
+                    ty: WrenFnType::Method,
+                    arity,
+                    sig: "toString".to_owned(),
+                    is_static,
+                    is_construct: false,
+                    decl: "foreign toString".to_owned(),
+                    manual_return,
+                    cfg,
+                    doc,
+                })
+            }
+        } else if attrs.iter().any(|attr| attr.path.is_ident("construct")) {
             // Constructor
             if is_static {
+                let decl = Self::make_wren_declaration(sig, args.name.as_ref(), "construct ", " {}");
+
                 Ok(WrenFnSpec {
                     ident,
                     wrap_ident,
@@ -551,6 +939,10 @@ impl WrenFnSpec {
                     sig: wren_sig,
                     is_static,
                     is_construct: true,
+                    decl,
+                    manual_return,
+                    cfg,
+                    doc,
                 })
             } else {
                 Err(syn::Error::new_spanned(
@@ -559,6 +951,9 @@ impl WrenFnSpec {
                 ))
             }
         } else {
+            let prefix = if is_static { "foreign static " } else { "foreign " };
+            let decl = Self::make_wren_declaration(sig, args.name.as_ref(), prefix, "");
+
             Ok(WrenFnSpec {
                 ident,
                 wrap_ident,
@@ -568,6 +963,10 @@ impl WrenFnSpec {
                 sig: wren_sig,
                 is_static,
                 is_construct: false,
+                decl,
+                manual_return,
+                cfg,
+                doc,
             })
         }
     }
@@ -577,15 +976,51 @@ impl WrenFnSpec {
         // Wren name can be specified using a attribute, else use Rust identifier.
         let mut sb = wren_name.unwrap_or_else(|| &sig.ident).to_string();
 
+        let args = Self::wren_args(sig)
+            .map(|_| "_")
+            .collect::<Vec<&'static str>>()
+            .join(",");
+        sb.push_str("(");
+        sb.push_str(&args);
+        sb.push_str(")");
+        sb
+    }
+
+    /// Create the declaration of the method in a Wren foreign class.
+    ///
+    /// Parameters are named after the Rust arguments where possible. Wren
+    /// treats names starting with an underscore as fields, so those and
+    /// destructuring patterns get a positional name instead.
+    fn make_wren_declaration(sig: &Signature, wren_name: Option<&Ident>, prefix: &str, suffix: &str) -> String {
+        let name = wren_name.unwrap_or(&sig.ident);
+
+        let params = Self::wren_args(sig)
+            .enumerate()
+            .map(|(idx, arg)| match arg {
+                FnArg::Typed(pat_ty) => match &*pat_ty.pat {
+                    Pat::Ident(pat_ident) if !pat_ident.ident.to_string().starts_with('_') => {
+                        pat_ident.ident.to_string()
+                    }
+                    _ => format!("arg{}", idx),
+                },
+                FnArg::Receiver(_) => unreachable!("receivers are filtered out"),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("{}{}({}){}", prefix, name, params, suffix)
+    }
+
+    /// Arguments that are passed from Wren, excluding the receiver and injections.
+    fn wren_args(sig: &Signature) -> impl Iterator<Item = &FnArg> {
         let ctx_ident = format_ident!("ctx");
 
         // Note that self receivers with a specified type, such as self: Box<Self>, are parsed as a FnArg::Typed.
         // https://docs.rs/syn/1.0.48/syn/enum.FnArg.html
-        let args = sig
-            .inputs
+        sig.inputs
             .iter()
             .filter(|arg| !matches!(arg, FnArg::Receiver(_)))
-            .filter(|arg| {
+            .filter(move |arg| {
                 // Filter out injections.
                 let attrs = match arg {
                     FnArg::Receiver(receiver) => &receiver.attrs,
@@ -597,13 +1032,6 @@ impl WrenFnSpec {
                     attr.path.is_ident(&ctx_ident)
                 })
             })
-            .map(|_| "_")
-            .collect::<Vec<&'static str>>()
-            .join(",");
-        sb.push_str("(");
-        sb.push_str(&args);
-        sb.push_str(")");
-        sb
     }
 }
 
@@ -613,6 +1041,8 @@ pub enum WrenFnType {
     Construct,
     Method,
     Operator,
+    /// Hook called when the instance is garbage collected.
+    Finalize,
 }
 
 /// Arguments to method attribute on an associated function.
@@ -639,21 +1069,27 @@ impl Parse for WrenMethodArgs {
 
 impl WrenMethodArgs {
     fn build_args(attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        Self::take_attr(attrs, "method")
+    }
+
+    /// Removes the attribute with the given name, and parses its arguments.
+    ///
+    /// A missing attribute, or one without arguments, results in the defaults.
+    fn take_attr(attrs: &mut Vec<Attribute>, name: &str) -> syn::Result<Self> {
         if attrs.is_empty() {
             return Ok(WrenMethodArgs::default());
         }
 
         // Find pertinent attribute.
-        let attr_ident = format_ident!("method");
-        let maybe_attr_pos = attrs
-            .iter()
-            .filter(|attr| attr.path.get_ident().is_some())
-            .position(|attr| attr.path.get_ident() == Some(&attr_ident));
+        let maybe_attr_pos = attrs.iter().position(|attr| attr.path.is_ident(name));
 
         if let Some(index) = maybe_attr_pos {
             // Keeping the attribute would cause a compile error
             // since the compiler doesn't know what to do with it.
-            let tokens = attrs.remove(index).tokens.clone();
+            let tokens = attrs.remove(index).tokens;
+            if tokens.is_empty() {
+                return Ok(WrenMethodArgs::default());
+            }
             let args = syn::parse2(tokens)?;
 
             Ok(args)
@@ -684,6 +1120,20 @@ impl WrenMethodArgs {
                 Expr::Path(right_expr) if right_expr.path.segments.len() == 1 => {
                     self.name = right_expr.path.get_ident().cloned();
                 }
+                // String literal allows names that are keywords in Rust, ie. `name = "type"`
+                Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) => {
+                    let name = lit.value();
+                    let mut chars = name.chars();
+                    let is_valid = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+                        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+                    if !is_valid {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "Expected method name to be a Wren identifier",
+                        ));
+                    }
+                    self.name = Some(Ident::new(&name, lit.span()));
+                }
                 _ => return Err(syn::parse::Error::new_spanned(expr, "Expected class name")),
             },
             _ => return Err(syn::Error::new_spanned(expr, "Failed to parse arguments")),
@@ -700,3 +1150,254 @@ enum ArgInjection {
     /// Inject the [rust_wren::WrenContext]
     Context,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_overloaded_arity() {
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[method(name = fooBar)]
+                fn foo_bar_0(&self) {}
+
+                #[method(name = fooBar)]
+                fn foo_bar_1(&self, a: f64) {}
+
+                #[method(name = fooBar)]
+                fn foo_bar_static(a: f64) {}
+            }
+        };
+
+        assert!(build_wren_methods(ast).is_ok());
+    }
+
+    #[test]
+    fn test_to_string() {
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[to_string]
+                fn to_string(&self) -> String {
+                    String::new()
+                }
+            }
+        };
+        assert!(build_wren_methods(ast).is_ok());
+
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[to_string]
+                fn to_string(&self, #[ctx] ctx: &mut WrenContext) -> String {
+                    String::new()
+                }
+            }
+        };
+        assert!(build_wren_methods(ast).is_ok());
+
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[to_string]
+                fn to_string() -> String {
+                    String::new()
+                }
+            }
+        };
+        assert!(build_wren_methods(ast).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_signature() {
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[method(name = fooBar)]
+                fn foo_bar_a(&self, a: f64) {}
+
+                #[method(name = fooBar)]
+                fn foo_bar_b(&self, b: String) {}
+            }
+        };
+
+        let err = build_wren_methods(ast).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Wren method signature `fooBar(_)` is already bound to `foo_bar_a`"
+        );
+    }
+
+    #[test]
+    fn test_string_name() {
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[method(name = "type")]
+                fn ty(&self) {}
+            }
+        };
+        assert!(build_wren_methods(ast).is_ok());
+
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[method(name = "foo bar")]
+                fn foo_bar(&self) {}
+            }
+        };
+        let err = build_wren_methods(ast).unwrap_err();
+        assert_eq!(err.to_string(), "Expected method name to be a Wren identifier");
+    }
+
+    #[test]
+    fn test_default_construct() {
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                fn bar(&self) {}
+            }
+        };
+        let decls = declare_wren_methods(&ast).unwrap();
+        assert_eq!(decls, vec!["construct new() {}", "foreign bar()"]);
+
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[construct]
+                fn create(a: f64) -> Self {}
+
+                fn bar(&self) {}
+            }
+        };
+        let decls = declare_wren_methods(&ast).unwrap();
+        assert_eq!(decls, vec!["construct create(a) {}", "foreign bar()"]);
+    }
+
+    #[test]
+    fn test_wren_const() {
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[wren_const]
+                const MAX: f64 = 100.0;
+
+                #[wren_const(name = minimum)]
+                const MIN: f64 = 0.0;
+
+                const HIDDEN: f64 = 1.0;
+            }
+        };
+        let decls = declare_wren_methods(&ast).unwrap();
+        assert_eq!(
+            decls,
+            vec!["construct new() {}", "foreign static MAX", "foreign static minimum"]
+        );
+    }
+
+    #[test]
+    fn test_fallible_construct() {
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[construct]
+                fn new() -> rust_wren::Result<Self> {
+                    Ok(Foo)
+                }
+            }
+        };
+        assert!(build_wren_methods(ast).is_ok());
+
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[construct]
+                fn new() -> Result<Self, ForeignError> {
+                    Ok(Foo)
+                }
+            }
+        };
+        assert!(build_wren_methods(ast).is_ok());
+
+        // Any other error would be put into the slot of the new instance, instead of aborting.
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[construct]
+                fn new() -> Result<Self, String> {
+                    Ok(Foo)
+                }
+            }
+        };
+        assert!(build_wren_methods(ast).is_err());
+    }
+
+    #[test]
+    fn test_cfg_method() {
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[cfg(feature = "extra")]
+                fn extra(&self) -> f64 { 0.0 }
+
+                // Mutually exclusive methods may share a signature.
+                #[cfg(feature = "fast")]
+                fn run(&self) {}
+                #[cfg(not(feature = "fast"))]
+                fn run(&self) {}
+            }
+        };
+        let tokens = build_wren_methods(ast).unwrap().to_string();
+
+        // Wrapper, registration and declaration are all gated.
+        let gated = quote! { #[cfg(feature = "extra")] }.to_string();
+        assert_eq!(tokens.matches(&gated).count(), 4);
+
+        let err = build_wren_methods(syn::parse_quote! {
+            impl Foo {
+                #[construct]
+                #[cfg(feature = "extra")]
+                fn new() -> Self { Foo }
+            }
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "#[cfg] can only be used on ordinary methods, not on constructors or finalizers"
+        );
+    }
+
+    #[test]
+    fn test_manual_return() {
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[manual_return]
+                fn pair(&self, #[ctx] ctx: &mut WrenContext) {}
+            }
+        };
+        assert!(build_wren_methods(ast).is_ok());
+
+        let ast: ItemImpl = syn::parse_quote! {
+            impl Foo {
+                #[manual_return]
+                fn pair(&self, #[ctx] ctx: &mut WrenContext) -> f64 { 0.0 }
+            }
+        };
+        let err = build_wren_methods(ast).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "#[manual_return] method must return `()`, and put its result into slot 0 itself"
+        );
+    }
+
+    #[test]
+    fn test_max_arity() {
+        let params = (0..16).map(|i| format!("a{}: f64", i)).collect::<Vec<_>>().join(", ");
+        let ast: ItemImpl = syn::parse_str(&format!("impl Foo {{ fn max(&self, {}) {{}} }}", params)).unwrap();
+        assert!(build_wren_methods(ast).is_ok());
+
+        // Injections are not passed from Wren, so they don't count.
+        let ast: ItemImpl = syn::parse_str(&format!(
+            "impl Foo {{ fn max_ctx(#[ctx] ctx: &mut WrenContext, {}) {{}} }}",
+            params
+        ))
+        .unwrap();
+        assert!(build_wren_methods(ast).is_ok());
+
+        let params = (0..17).map(|i| format!("a{}: f64", i)).collect::<Vec<_>>().join(", ");
+        let ast: ItemImpl = syn::parse_str(&format!("impl Foo {{ fn too_many({}) {{}} }}", params)).unwrap();
+        let err = build_wren_methods(ast).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Wren methods can have at most 16 parameters, but `too_many` has 17"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! `WrenNumber` derive for numeric newtypes.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Type};
+
+/// Rust numeric types that can be converted to and from a Wren number.
+const NUMERIC_TYPES: &[&str] = &["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "f32", "f64"];
+
+/// Generate implementations of `FromWren` and `ToWren` for a single field
+/// tuple struct, which is passed to Wren as its inner number.
+pub fn gen_number_impl(input: &DeriveInput) -> syn::Result<TokenStream> {
+    const SHAPE_ERR: &str = "WrenNumber can only be derived for tuple structs with a single numeric field";
+
+    if input.generics != Default::default() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "WrenNumber cannot be derived for structs with lifetime parameters or generics",
+        ));
+    }
+
+    let field = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => fields.unnamed.first().unwrap(),
+            fields => return Err(syn::Error::new_spanned(fields, SHAPE_ERR)),
+        },
+        _ => return Err(syn::Error::new_spanned(&input.ident, SHAPE_ERR)),
+    };
+
+    let is_numeric = match &field.ty {
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            NUMERIC_TYPES.iter().any(|numeric| type_path.path.is_ident(numeric))
+        }
+        _ => false,
+    };
+    if !is_numeric {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            format!("WrenNumber field must be one of {}", NUMERIC_TYPES.join(", ")),
+        ));
+    }
+
+    let ident = &input.ident;
+    let inner = &field.ty;
+
+    Ok(quote! {
+        impl<'wren> rust_wren::value::FromWren<'wren> for #ident {
+            type Output = Self;
+
+            #[inline]
+            fn get_slot(ctx: &rust_wren::WrenContext, slot_num: i32) -> rust_wren::WrenResult<Self::Output> {
+                <#inner as rust_wren::value::FromWren>::get_slot(ctx, slot_num).map(#ident)
+            }
+        }
+
+        impl rust_wren::value::ToWren for #ident {
+            #[inline]
+            fn put(self, ctx: &mut rust_wren::WrenContext, slot: i32) {
+                rust_wren::value::ToWren::put(self.0, ctx, slot)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_numeric_newtype() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct EntityId(u32);
+        };
+
+        assert!(gen_number_impl(&input).is_ok());
+    }
+
+    #[test]
+    fn test_reject_shape() {
+        let inputs: Vec<DeriveInput> = vec![
+            syn::parse_quote! { struct EntityId(String); },
+            syn::parse_quote! { struct EntityId(u32, u32); },
+            syn::parse_quote! { struct EntityId { id: u32 } },
+            syn::parse_quote! { struct EntityId; },
+            syn::parse_quote! { enum EntityId { A } },
+        ];
+
+        for input in &inputs {
+            assert!(gen_number_impl(input).is_err());
+        }
+    }
+}
@@ -1,51 +1,119 @@
 //! Class property generation.
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote, quote_spanned};
-use syn::{spanned::Spanned, Field, Fields, ItemStruct, Type};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Expr, ExprAssign, ExprLit, Field, Fields, GenericArgument, Index, ItemStruct, Lit, Member, PathArguments, Token,
+    Type,
+};
 
 pub fn gen_class_props(class: &ItemStruct) -> syn::Result<TokenStream> {
+    build_class_props(class).map(|(tokens, _)| tokens)
+}
+
+/// Wren declarations of the properties of a class, in the same
+/// order as the generated `__wren_declare_properties`.
+pub fn declare_class_props(class: &ItemStruct) -> syn::Result<Vec<String>> {
+    build_class_props(class).map(|(_, decls)| decls)
+}
+
+fn build_class_props(class: &ItemStruct) -> syn::Result<(TokenStream, Vec<String>)> {
     let get_set = format_ident!("getset");
     let get = format_ident!("get");
     let set = format_ident!("set");
+    let iterable = format_ident!("iterable");
+    let get_list = format_ident!("get_list");
+    let get_set_list = format_ident!("getset_list");
 
     let mut registers = vec![];
     let mut gets = vec![];
     let mut sets = vec![];
     let mut assert_clone = vec![];
+    let mut decls = vec![];
 
     for (field_idx, field) in class.fields.iter().enumerate() {
         for attr in &field.attrs {
             match attr.path.get_ident() {
                 ident if ident == Some(&get) => {
+                    let args: WrenPropArgs = syn::parse2(attr.tokens.clone())?;
                     let field_ident = get_field_ident(field_idx, field);
-                    let (g, r) = gen_get(&field_ident);
+                    let field_member = get_field_member(field_idx, field);
+                    let prop_ident = args.name.unwrap_or_else(|| field_ident.clone());
+                    let (g, r) = gen_get(&field_ident, &prop_ident, &field_member, args.map.as_ref())?;
                     gets.push(g);
                     registers.push(r);
-                    assert_clone.push(gen_field_assert(field_idx, field));
+                    decls.push(format!("foreign {}", prop_ident));
+
+                    // Mapped getters borrow the field, so it doesn't need to be cloned.
+                    if args.map.is_none() {
+                        assert_clone.push(gen_field_assert(field_idx, field));
+                    }
                 }
                 ident if ident == Some(&set) => {
                     let field_ident = get_field_ident(field_idx, field);
+                    let prop_ident = get_prop_ident(field_idx, field, &attr.tokens)?;
+                    let field_member = get_field_member(field_idx, field);
                     let field_ty = field.ty.clone();
 
-                    let (s, r) = gen_set(&field_ident, &field_ty);
+                    let (s, r) = gen_set(&field_ident, &prop_ident, &field_member, &field_ty);
                     sets.push(s);
                     registers.push(r);
+                    decls.push(format!("foreign {}=(value)", prop_ident));
                     assert_clone.push(gen_field_assert(field_idx, field));
                 }
                 ident if ident == Some(&get_set) => {
                     let field_ident = get_field_ident(field_idx, field);
+                    let prop_ident = get_prop_ident(field_idx, field, &attr.tokens)?;
+                    let field_member = get_field_member(field_idx, field);
                     let field_ty = field.ty.clone();
 
-                    let (g, r) = gen_get(&field_ident);
+                    let (g, r) = gen_get(&field_ident, &prop_ident, &field_member, None)?;
                     gets.push(g);
                     registers.push(r);
+                    decls.push(format!("foreign {}", prop_ident));
 
-                    let (s, r) = gen_set(&field_ident, &field_ty);
+                    let (s, r) = gen_set(&field_ident, &prop_ident, &field_member, &field_ty);
                     sets.push(s);
                     registers.push(r);
+                    decls.push(format!("foreign {}=(value)", prop_ident));
 
                     assert_clone.push(gen_field_assert(field_idx, field));
                 }
+                ident if ident == Some(&get_list) || ident == Some(&get_set_list) => {
+                    let field_ident = get_field_ident(field_idx, field);
+                    let prop_ident = get_prop_ident(field_idx, field, &attr.tokens)?;
+                    let field_member = get_field_member(field_idx, field);
+                    let elem_ty = get_vec_element_type(&field.ty)?;
+
+                    let (g, r) = gen_get_list(&field_ident, &prop_ident, &field_member);
+                    gets.push(g);
+                    registers.push(r);
+                    decls.push(format!("foreign {}", prop_ident));
+
+                    if ident == Some(&get_set_list) {
+                        let (s, r) = gen_set_list(&field_ident, &prop_ident, &field_member, elem_ty);
+                        sets.push(s);
+                        registers.push(r);
+                        decls.push(format!("foreign {}=(value)", prop_ident));
+                    }
+                }
+                ident if ident == Some(&iterable) => {
+                    if !attr.tokens.is_empty() {
+                        return Err(syn::Error::new_spanned(attr, "#[iterable] does not take arguments"));
+                    }
+
+                    let field_ident = get_field_ident(field_idx, field);
+                    let field_member = get_field_member(field_idx, field);
+
+                    let (i, r) = gen_iterable(&field_ident, &field_member);
+                    gets.push(i);
+                    registers.push(r);
+                    decls.push("foreign iterate(iter)".to_owned());
+                    decls.push("foreign iteratorValue(iter)".to_owned());
+                }
                 _ => {}
             }
         }
@@ -64,25 +132,50 @@ pub fn gen_class_props(class: &ItemStruct) -> syn::Result<TokenStream> {
             fn __wren_register_properties(builder: &mut rust_wren::ModuleBuilder) {
                 #(#registers)*
             }
+
+            /// Wren declarations of the property getters and setters.
+            fn __wren_declare_properties() -> &'static [&'static str] {
+                &[#(#decls),*]
+            }
         }
     };
 
-    Ok(gen)
+    Ok((gen, decls))
 }
 
-fn get_field_ident(_field_index: usize, field: &Field) -> Ident {
-    // Tuple struct fields don't have identifiers, so we
-    // have to access it via an integer identifier.
+/// Identifier used for the property name in Wren, and for naming generated wrapper functions.
+fn get_field_ident(field_index: usize, field: &Field) -> Ident {
+    // Tuple struct fields don't have identifiers, and identifiers
+    // cannot start with numbers, so the property name is derived
+    // from the field's position.
     match field.ident {
         Some(ref ident) => ident.clone(),
-        None => {
-            // FIXME: Find a solution for tuple structs.
-            //        Identifies cannot start with numbers,
-            //        so tuple field accessors have to be
-            //        number literals.
-            // format_ident!("{}", field_index);
-            unimplemented!("FIXME: Tuple field accessors")
-        }
+        None => format_ident!("field{}", field_index, span = field.span()),
+    }
+}
+
+/// Identifier used for the property name in Wren, taking into
+/// account the optional `name` argument of the property attribute.
+fn get_prop_ident(field_index: usize, field: &Field, attr_tokens: &TokenStream) -> syn::Result<Ident> {
+    let args: WrenPropArgs = syn::parse2(attr_tokens.clone())?;
+
+    if let Some(map) = args.map {
+        return Err(syn::Error::new_spanned(map, "`map` is only supported by #[get]"));
+    }
+
+    Ok(args.name.unwrap_or_else(|| get_field_ident(field_index, field)))
+}
+
+/// Member used to access the field on the Rust struct.
+fn get_field_member(field_index: usize, field: &Field) -> Member {
+    // Tuple struct fields have to be accessed via
+    // number literals, ie. `self.0`.
+    match field.ident {
+        Some(ref ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index {
+            index: field_index as u32,
+            span: field.span(),
+        }),
     }
 }
 
@@ -95,23 +188,52 @@ fn gen_field_assert(field_index: usize, field: &Field) -> TokenStream {
 
     // Compile time assertion to provide user friendly error
     // when property does not implement `Clone`.
+    //
+    // The helper trait is declared per field so the diagnostic
+    // message can name the offending property.
     let field_span = field_ty.span();
-    let assert_ident = format_ident!("_{}_AssertClone", field_ident);
+    let message = format!(
+        "property `{}` requires its type to implement `Clone` because getters clone the value out to Wren",
+        field_ident
+    );
 
     quote_spanned! {field_span=>
-        #[allow(non_camel_case_types)]
-        struct #assert_ident where #field_ty: Clone;
+        const _: () = {
+            #[diagnostic::on_unimplemented(
+                message = #message,
+                label = "property type does not implement `Clone`"
+            )]
+            trait WrenPropertyClone {}
+            impl<T: Clone> WrenPropertyClone for T {}
+            fn assert_clone<T: WrenPropertyClone>() {}
+            let _ = assert_clone::<#field_ty>;
+        };
     }
 }
 
 /// Generate property get function.
-fn gen_get(field_ident: &Ident) -> (TokenStream, TokenStream) {
+///
+/// Without a mapping the field value is cloned out to Wren, otherwise
+/// the mapping is applied to a borrow of the field and its result is
+/// returned instead.
+fn gen_get(
+    field_ident: &Ident,
+    prop_ident: &Ident,
+    field_member: &Member,
+    map: Option<&Expr>,
+) -> syn::Result<(TokenStream, TokenStream)> {
     // Signature of a property get is simply the property name; no parentheses or argument arity.
-    let sig = field_ident.to_string();
-    let wrap_func = format_ident!("__wren_wrap_get_{}", field_ident);
+    let sig = prop_ident.to_string();
+    // Named after the property, since a field can have multiple mapped getters.
+    let wrap_func = format_ident!("__wren_wrap_get_{}", prop_ident);
     let span = field_ident.span();
+    let value = match map {
+        Some(map) => gen_get_map(field_member, map)?,
+        None => quote! { self_.#field_member.clone() },
+    };
 
     let get = quote_spanned! {span=>
+        #[allow(non_snake_case)]
         extern "C" fn #wrap_func(vm: *mut rust_wren::bindings::WrenVM) {
             // Context for extracting slots.
             let vm: &mut rust_wren::bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
@@ -133,9 +255,9 @@ fn gen_get(field_ident: &Ident) -> (TokenStream, TokenStream) {
                 }
             };
 
-            // Value must be cloned to be sent from Rust to Wren.
+            // Value must be cloned, or mapped, to be sent from Rust to Wren.
             let prop = match cell.try_borrow_mut() {
-                Ok(ref mut self_) => self_.#field_ident.clone(),
+                Ok(ref mut self_) => #value,
                 Err(err) => {
                     let wren_error = rust_wren::WrenError::new_foreign_call(
                         #sig,
@@ -162,17 +284,52 @@ fn gen_get(field_ident: &Ident) -> (TokenStream, TokenStream) {
                 arity: 0,
                 sig: #sig.to_owned(),
                 func: <Self>::#wrap_func,
+                doc: None,
             }
         );
     };
 
-    (get, register)
+    Ok((get, register))
+}
+
+/// Generate the expression applying a getter's mapping to a borrow of the field.
+fn gen_get_map(field_member: &Member, map: &Expr) -> syn::Result<TokenStream> {
+    match map {
+        // Method on the field, ie. `map = "is_empty"`
+        Expr::Path(path_expr) if path_expr.path.get_ident().is_some() => {
+            let method = path_expr.path.get_ident().unwrap();
+            Ok(quote! { self_.#field_member.#method() })
+        }
+        // Function taking a reference to the field, ie. `map = "str::is_empty"`
+        Expr::Path(path_expr) => Ok(quote! { #path_expr(&self_.#field_member) }),
+        // Closure taking a reference to the field, ie. `map = "|s| s.len() as f64"`
+        //
+        // Passing the closure through a function gives it the field's type, which
+        // would otherwise have to be annotated on the closure's argument.
+        Expr::Closure(closure) => Ok(quote! {
+            {
+                fn map<T: ?Sized, R>(value: &T, f: impl FnOnce(&T) -> R) -> R {
+                    f(value)
+                }
+                map(&self_.#field_member, #closure)
+            }
+        }),
+        _ => Err(syn::Error::new_spanned(
+            map,
+            "Expected property map to be a method name, function path or closure",
+        )),
+    }
 }
 
 /// Generate property set function.
-fn gen_set(field_ident: &Ident, field_ty: &Type) -> (TokenStream, TokenStream) {
+fn gen_set(
+    field_ident: &Ident,
+    prop_ident: &Ident,
+    field_member: &Member,
+    field_ty: &Type,
+) -> (TokenStream, TokenStream) {
     // Signature of a property assign is the property name followed by an equal sign.
-    let sig = format!("{}=(_)", field_ident);
+    let sig = format!("{}=(_)", prop_ident);
     let wrap_func = format_ident!("__wren_wrap_set_{}", field_ident);
     let span = field_ident.span();
 
@@ -220,9 +377,9 @@ fn gen_set(field_ident: &Ident, field_ty: &Type) -> (TokenStream, TokenStream) {
 
             // Property value must be cloneable because it is assigned to the Rust struct
             // and also returned later.
-            // cell.borrow_mut().#field_ident = value.clone();
+            // cell.borrow_mut().#field_member = value.clone();
             match cell.try_borrow_mut() {
-                Ok(ref mut self_) => self_.#field_ident = value.clone(),
+                Ok(ref mut self_) => self_.#field_member = value.clone(),
                 Err(err) => {
                     let wren_error = rust_wren::WrenError::new_foreign_call(
                         #sig,
@@ -250,6 +407,165 @@ fn gen_set(field_ident: &Ident, field_ty: &Type) -> (TokenStream, TokenStream) {
                 arity: 0,
                 sig: #sig.to_owned(),
                 func: <Self>::#wrap_func,
+                doc: None,
+            }
+        );
+    };
+
+    (set, register)
+}
+
+/// Element type of a `Vec<T>` field, required by the list properties.
+fn get_vec_element_type(ty: &Type) -> syn::Result<&Type> {
+    let err = || syn::Error::new_spanned(ty, "List property must be of type `Vec<T>`");
+
+    let segment = match ty {
+        Type::Path(type_path) => type_path.path.segments.last().ok_or_else(err)?,
+        _ => return Err(err()),
+    };
+
+    if segment.ident != "Vec" {
+        return Err(err());
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first() {
+            Some(GenericArgument::Type(elem_ty)) => Ok(elem_ty),
+            _ => Err(err()),
+        },
+        _ => Err(err()),
+    }
+}
+
+/// Generate list property get function.
+///
+/// The vector is copied element by element into a new Wren list,
+/// instead of being cloned as a whole.
+fn gen_get_list(field_ident: &Ident, prop_ident: &Ident, field_member: &Member) -> (TokenStream, TokenStream) {
+    let sig = prop_ident.to_string();
+    let wrap_func = format_ident!("__wren_wrap_get_{}", prop_ident);
+    let span = field_ident.span();
+
+    let get = quote_spanned! {span=>
+        #[allow(non_snake_case)]
+        extern "C" fn #wrap_func(vm: *mut rust_wren::bindings::WrenVM) {
+            let vm: &mut rust_wren::bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+            let mut ctx = rust_wren::WrenContext::new(vm);
+
+            let abort = |ctx: &mut rust_wren::WrenContext, err: rust_wren::WrenError| {
+                let wren_error = rust_wren::WrenError::new_foreign_call(
+                    #sig,
+                    Box::new(rust_wren::WrenError::GetArg { slot: 0, cause: Box::new(err) })
+                );
+
+                let foreign_error = rust_wren::ForeignError::Simple(Box::new(wren_error));
+                foreign_error.put(ctx, 0);
+            };
+
+            let cell = match ctx.get_slot::<Self>(0) {
+                Ok(cell) => cell,
+                Err(err) => return abort(&mut ctx, err),
+            };
+
+            // The list replaces the receiver in slot 0. The handle keeps the
+            // receiver alive in case the list allocations trigger garbage collection.
+            let receiver = match ctx.get_slot::<rust_wren::handle::WrenRef>(0) {
+                Ok(receiver) => receiver,
+                Err(err) => return abort(&mut ctx, err),
+            };
+
+            let list = match cell.try_borrow() {
+                Ok(self_) => rust_wren::list::WrenList::from_slice(&mut ctx, &self_.#field_member),
+                Err(err) => Err(err),
+            };
+            drop(receiver);
+
+            match list {
+                Ok(list) => rust_wren::value::ToWren::put(list, &mut ctx, 0),
+                Err(err) => abort(&mut ctx, err),
+            }
+        }
+    };
+
+    let register = quote! {
+        builder.add_method_binding(
+            <Self as rust_wren::class::WrenForeignClass>::NAME,
+            rust_wren::foreign::ForeignMethod {
+                is_static: false,
+                arity: 0,
+                sig: #sig.to_owned(),
+                func: <Self>::#wrap_func,
+                doc: None,
+            }
+        );
+    };
+
+    (get, register)
+}
+
+/// Generate list property set function, which copies the elements of
+/// a Wren list into the vector.
+fn gen_set_list(
+    field_ident: &Ident,
+    prop_ident: &Ident,
+    field_member: &Member,
+    elem_ty: &Type,
+) -> (TokenStream, TokenStream) {
+    let sig = format!("{}=(_)", prop_ident);
+    let wrap_func = format_ident!("__wren_wrap_set_{}", field_ident);
+    let span = field_ident.span();
+
+    let set = quote_spanned! {span=>
+        extern "C" fn #wrap_func(vm: *mut rust_wren::bindings::WrenVM) {
+            let vm: &mut rust_wren::bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+            let mut ctx = rust_wren::WrenContext::new(vm);
+
+            let abort = |ctx: &mut rust_wren::WrenContext, slot: i32, err: rust_wren::WrenError| {
+                let wren_error = rust_wren::WrenError::new_foreign_call(
+                    #sig,
+                    Box::new(rust_wren::WrenError::GetArg { slot, cause: Box::new(err) })
+                );
+
+                let foreign_error = rust_wren::ForeignError::Simple(Box::new(wren_error));
+                foreign_error.put(ctx, 0);
+            };
+
+            let cell = match ctx.get_slot::<Self>(0) {
+                Ok(cell) => cell,
+                Err(err) => return abort(&mut ctx, 0, err),
+            };
+
+            // Handle to the list is taken before copying the elements
+            // overwrites the receiver and argument slots.
+            let list = match ctx.get_slot::<rust_wren::list::WrenList>(1) {
+                Ok(list) => list,
+                Err(err) => return abort(&mut ctx, 1, err),
+            };
+
+            let value = match list.to_vec::<#elem_ty>(&mut ctx) {
+                Ok(value) => value,
+                Err(err) => return abort(&mut ctx, 1, err),
+            };
+
+            match cell.try_borrow_mut() {
+                Ok(ref mut self_) => self_.#field_member = value,
+                Err(err) => return abort(&mut ctx, 0, err),
+            }
+
+            // Assignment returns the assigned list.
+            rust_wren::value::ToWren::put(list, &mut ctx, 0);
+        }
+    };
+
+    let register = quote! {
+        builder.add_method_binding(
+            <Self as rust_wren::class::WrenForeignClass>::NAME,
+            rust_wren::foreign::ForeignMethod {
+                is_static: false,
+                arity: 0,
+                sig: #sig.to_owned(),
+                func: <Self>::#wrap_func,
+                doc: None,
             }
         );
     };
@@ -257,32 +573,219 @@ fn gen_set(field_ident: &Ident, field_ty: &Type) -> (TokenStream, TokenStream) {
     (set, register)
 }
 
+/// Generate the `iterate` and `iteratorValue` functions implementing
+/// Wren's iterator protocol over a field that dereferences to a slice,
+/// such as `Vec<T>`.
+///
+/// The iterator state handed to Wren is the element index. Wren passes
+/// `null` on the first iteration, and `iterate` returns `false` once the
+/// end is reached to stop the loop.
+fn gen_iterable(field_ident: &Ident, field_member: &Member) -> (TokenStream, TokenStream) {
+    let iterate_func = format_ident!("__wren_wrap_iterate_{}", field_ident);
+    let value_func = format_ident!("__wren_wrap_iterator_value_{}", field_ident);
+    let span = field_ident.span();
+
+    let iterable = quote_spanned! {span=>
+        extern "C" fn #iterate_func(vm: *mut rust_wren::bindings::WrenVM) {
+            let vm: &mut rust_wren::bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+            let mut ctx = rust_wren::WrenContext::new(vm);
+
+            let abort = |ctx: &mut rust_wren::WrenContext, slot: i32, err: rust_wren::WrenError| {
+                let wren_error = rust_wren::WrenError::new_foreign_call(
+                    "iterate(_)",
+                    Box::new(rust_wren::WrenError::GetArg { slot, cause: Box::new(err) })
+                );
+
+                let foreign_error = rust_wren::ForeignError::Simple(Box::new(wren_error));
+                foreign_error.put(ctx, 0);
+            };
+
+            let cell = match ctx.get_slot::<Self>(0) {
+                Ok(cell) => cell,
+                Err(err) => return abort(&mut ctx, 0, err),
+            };
+
+            // Iterator is null on the first iteration.
+            let iter = match ctx.get_slot::<Option<f64>>(1) {
+                Ok(iter) => iter,
+                Err(err) => return abort(&mut ctx, 1, err),
+            };
+
+            let len = match cell.try_borrow() {
+                Ok(self_) => self_.#field_member.len(),
+                Err(err) => return abort(&mut ctx, 0, err),
+            };
+
+            // Casting saturates, so a huge iterator from Wren can't be incremented.
+            let next = match iter {
+                None => Some(0),
+                Some(index) => (index as usize).checked_add(1),
+            };
+
+            // Returning `false` stops the loop.
+            match next {
+                Some(next) if next < len => rust_wren::value::ToWren::put(next as f64, &mut ctx, 0),
+                _ => rust_wren::value::ToWren::put(false, &mut ctx, 0),
+            }
+        }
+
+        extern "C" fn #value_func(vm: *mut rust_wren::bindings::WrenVM) {
+            let vm: &mut rust_wren::bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+            let mut ctx = rust_wren::WrenContext::new(vm);
+
+            let abort = |ctx: &mut rust_wren::WrenContext, slot: i32, err: rust_wren::WrenError| {
+                let wren_error = rust_wren::WrenError::new_foreign_call(
+                    "iteratorValue(_)",
+                    Box::new(rust_wren::WrenError::GetArg { slot, cause: Box::new(err) })
+                );
+
+                let foreign_error = rust_wren::ForeignError::Simple(Box::new(wren_error));
+                foreign_error.put(ctx, 0);
+            };
+
+            let cell = match ctx.get_slot::<Self>(0) {
+                Ok(cell) => cell,
+                Err(err) => return abort(&mut ctx, 0, err),
+            };
+
+            let index = match ctx.get_slot::<f64>(1) {
+                Ok(index) => index as usize,
+                Err(err) => return abort(&mut ctx, 1, err),
+            };
+
+            // Element must be cloned to be sent from Rust to Wren.
+            let value = match cell.try_borrow() {
+                Ok(self_) => self_.#field_member.get(index).cloned().ok_or_else(|| {
+                    rust_wren::WrenError::IndexOutOfBounds {
+                        index,
+                        len: self_.#field_member.len(),
+                    }
+                }),
+                Err(err) => Err(err),
+            };
+
+            match value {
+                Ok(value) => rust_wren::value::ToWren::put(value, &mut ctx, 0),
+                Err(err) => abort(&mut ctx, 1, err),
+            }
+        }
+    };
+
+    let register = quote! {
+        builder.add_method_binding(
+            <Self as rust_wren::class::WrenForeignClass>::NAME,
+            rust_wren::foreign::ForeignMethod {
+                is_static: false,
+                arity: 1,
+                sig: "iterate(_)".to_owned(),
+                func: <Self>::#iterate_func,
+                doc: None,
+            }
+        );
+        builder.add_method_binding(
+            <Self as rust_wren::class::WrenForeignClass>::NAME,
+            rust_wren::foreign::ForeignMethod {
+                is_static: false,
+                arity: 1,
+                sig: "iteratorValue(_)".to_owned(),
+                func: <Self>::#value_func,
+                doc: None,
+            }
+        );
+    };
+
+    (iterable, register)
+}
+
 /// Remove known attributes, otherwise compilation would fail after code gen.
 pub fn strip_prop_attrs(fields: &mut Fields) {
     let getset_ident = format_ident!("getset");
     let get_ident = format_ident!("get");
     let set_ident = format_ident!("set");
-    let all = [getset_ident, get_ident, set_ident];
+    let iterable_ident = format_ident!("iterable");
+    let get_list_ident = format_ident!("get_list");
+    let getset_list_ident = format_ident!("getset_list");
+    let all = [
+        getset_ident,
+        get_ident,
+        set_ident,
+        iterable_ident,
+        get_list_ident,
+        getset_list_ident,
+    ];
 
     for field in fields {
-        let maybe_attr_pos = field
-            .attrs
-            .iter()
-            .filter(|attr| attr.path.get_ident().is_some())
-            .position(|attr| {
-                if let Some(attr_ident) = attr.path.get_ident() {
-                    if all.contains(attr_ident) {
-                        return true;
-                    }
-                }
+        // Keeping the attributes would cause a compile error
+        // since the compiler doesn't know what to do with them.
+        field.attrs.retain(|attr| match attr.path.get_ident() {
+            Some(attr_ident) => !all.contains(attr_ident),
+            None => true,
+        });
+    }
+}
 
-                false
-            });
+/// Arguments to a property attribute on a struct field.
+#[derive(Debug, Default)]
+struct WrenPropArgs {
+    name: Option<Ident>,
+    map: Option<Expr>,
+}
 
-        if let Some(index) = maybe_attr_pos {
-            // Keeping the attribute would cause a compile error
-            // since the compiler doesn't know what to do with it.
-            field.attrs.remove(index);
+impl Parse for WrenPropArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = WrenPropArgs::default();
+
+        // Arguments are optional, ie. `#[get]`
+        if input.is_empty() {
+            return Ok(args);
+        }
+
+        let content;
+        parenthesized!(content in input);
+
+        let vars = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?;
+        for expr in vars {
+            args.add_expr(&expr)?;
         }
+
+        Ok(args)
+    }
+}
+
+impl WrenPropArgs {
+    fn add_expr(&mut self, expr: &Expr) -> syn::parse::Result<()> {
+        match expr {
+            Expr::Assign(assign) => self.add_assign(assign),
+            _ => Err(syn::parse::Error::new_spanned(expr, "Failed to parse arguments")),
+        }
+    }
+
+    fn add_assign(&mut self, expr: &ExprAssign) -> syn::parse::Result<()> {
+        let ExprAssign { left, right, .. } = expr;
+
+        let key = match &**left {
+            Expr::Path(path_expr) if path_expr.path.segments.len() == 1 => {
+                path_expr.path.segments.first().unwrap().ident.to_string()
+            }
+            _ => return Err(syn::Error::new_spanned(expr, "Failed to parse arguments")),
+        };
+
+        match key.as_str() {
+            "name" => match &**right {
+                Expr::Path(right_expr) if right_expr.path.segments.len() == 1 => {
+                    self.name = right_expr.path.get_ident().cloned();
+                }
+                _ => return Err(syn::parse::Error::new_spanned(expr, "Expected property name")),
+            },
+            "map" => match &**right {
+                Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) => {
+                    self.map = Some(lit.parse()?);
+                }
+                _ => return Err(syn::parse::Error::new_spanned(expr, "Expected property map string")),
+            },
+            _ => return Err(syn::Error::new_spanned(expr, "Failed to parse arguments")),
+        }
+
+        Ok(())
     }
 }
@@ -1,27 +1,97 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use rust_wren_derive_backend::{
-    build_wren_methods, gen_class_props, gen_from_wren_impl, gen_to_wren_impl, strip_prop_attrs, WrenClassArgs,
+    build_wren_methods, declare_class_props, declare_wren_methods, gen_class_props, gen_enum_impl, gen_from_wren_impl,
+    gen_number_impl, gen_to_wren_impl, strip_prop_attrs, WrenClassArgs,
 };
 use syn::{
     self,
     parse::{Parse, ParseStream},
     parse_macro_input,
     punctuated::Punctuated,
-    Expr, Ident, ItemImpl, ItemStruct, Token,
+    Attribute, DeriveInput, Expr, Ident, Item, ItemEnum, ItemImpl, ItemMod, ItemStruct, Token, Type,
 };
 
+/// Exposes a struct to Wren as a foreign class.
+///
+/// Can also be used on C-like enums, which are passed to Wren as a number
+/// containing the discriminant. From Wren, either the discriminant or the
+/// variant name as a string is accepted.
+///
+/// Fields marked with `#[get]`, `#[set]` or `#[getset]` are exposed as
+/// properties, optionally renamed with `name = ...`. Getters clone the field
+/// out to Wren. For large fields where Wren only needs a computed value,
+/// `#[get(map = "...")]` borrows the field and returns the result of the
+/// mapping instead. The mapping may be:
+///
+/// - a method name, called on the field, ie. `map = "is_empty"`
+/// - a function path, called with a reference to the field, ie. `map = "str::is_empty"`
+/// - a closure, called with a reference to the field, ie. `map = "|items| items.len() as f64"`
+///
+/// ```ignore
+/// #[wren_class]
+/// struct Inventory {
+///     #[get(name = count, map = "|items| items.len() as f64")]
+///     items: Vec<Item>,
+/// }
+/// ```
+///
+/// A `Vec<T>` field marked with `#[iterable]` implements Wren's iterator
+/// protocol, generating `iterate(_)` and `iteratorValue(_)` so instances
+/// can be looped over with `for`. Elements are cloned out to Wren.
+///
+/// ```ignore
+/// #[wren_class]
+/// struct Bag {
+///     #[iterable]
+///     items: Vec<f64>,
+/// }
+/// ```
+///
+/// A `Vec<T>` field marked with `#[get_list]` is exposed as a property
+/// returning a new Wren list, with each element converted to Wren. With
+/// `#[getset_list]` assigning a Wren list converts its elements back into
+/// the vector. Both accept a `name` like `#[get]`.
+///
+/// ```ignore
+/// #[wren_class]
+/// struct Path {
+///     #[getset_list(name = points)]
+///     points: Vec<f64>,
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn wren_class(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attr = parse_macro_input!(attr as WrenClassArgs);
-    let item = parse_macro_input!(item as ItemStruct);
+    let item = parse_macro_input!(item as Item);
 
-    impl_wren_class(attr, item)
+    match item {
+        Item::Struct(item) => impl_wren_class(attr, item),
+        Item::Enum(item) => impl_wren_enum(item),
+        item => syn::Error::new_spanned(item, "#[wren_class] can only be used on structs and enums")
+            .to_compile_error()
+            .into(),
+    }
 }
 
-fn impl_wren_class(attr: WrenClassArgs, mut item: ItemStruct) -> TokenStream {
-    let struct_ident = item.ident.clone();
-    let class_name = if let Some(name) = attr.name {
+fn impl_wren_enum(item: ItemEnum) -> TokenStream {
+    let enum_impl = match gen_enum_impl(&item) {
+        Ok(tokens) => tokens,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let gen = quote! {
+        #item
+
+        #enum_impl
+    };
+
+    gen.into()
+}
+
+/// Name of the class in Wren.
+fn get_class_name(attr: &WrenClassArgs, struct_ident: &Ident) -> String {
+    if let Some(ref name) = attr.name {
         // Use explicitly given name in attribute.
         match name {
             Expr::Path(path_expr) if path_expr.path.segments.len() == 1 => {
@@ -33,11 +103,19 @@ fn impl_wren_class(attr: WrenClassArgs, mut item: ItemStruct) -> TokenStream {
     } else {
         // Use struct name from Rust.
         struct_ident.to_string()
-    };
+    }
+}
+
+fn impl_wren_class(attr: WrenClassArgs, mut item: ItemStruct) -> TokenStream {
+    let struct_ident = item.ident.clone();
+    let class_name = get_class_name(&attr, &struct_ident);
 
     let from_wren_impl = gen_from_wren_impl(struct_ident.clone());
     let to_wren_impl = gen_to_wren_impl(struct_ident.clone());
-    let props_impl = gen_class_props(&item).expect("Failed to generate property accessors");
+    let props_impl = match gen_class_props(&item) {
+        Ok(tokens) => tokens,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
     strip_prop_attrs(&mut item.fields);
 
@@ -59,6 +137,18 @@ fn impl_wren_class(attr: WrenClassArgs, mut item: ItemStruct) -> TokenStream {
                 Self::__wren_register_methods(bindings);
                 Self::__wren_register_properties(bindings);
             }
+
+            fn wren_declaration() -> String {
+                let class_name = <Self as rust_wren::class::WrenForeignClass>::NAME;
+                let mut decl = format!("foreign class {} {{\n", class_name);
+                for line in Self::__wren_declare_methods().iter().chain(Self::__wren_declare_properties()) {
+                    decl.push_str("    ");
+                    decl.push_str(line);
+                    decl.push('\n');
+                }
+                decl.push_str("}\n");
+                decl
+            }
         }
 
         #from_wren_impl
@@ -71,16 +161,311 @@ fn impl_wren_class(attr: WrenClassArgs, mut item: ItemStruct) -> TokenStream {
     gen.into()
 }
 
+/// Generates Wren bindings for the methods in an impl block.
+///
+/// Wren signatures encode arity, so multiple Rust methods can be bound
+/// to the same Wren name with `#[method(name = ...)]` as long as their
+/// arities differ. Two methods producing the same signature is a
+/// compile error.
+///
+/// ```ignore
+/// #[wren_methods]
+/// impl Foo {
+///     #[method(name = fooBar)]
+///     fn foo_bar_0(&self) {}
+///
+///     #[method(name = fooBar)]
+///     fn foo_bar_1(&self, a: f64) {}
+/// }
+/// ```
+///
+/// The name can also be given as a string literal, for Wren names that
+/// are keywords in Rust, ie. `#[method(name = "type")]`.
+///
+/// A `#[construct]` method can return `rust_wren::Result<Self>` to reject
+/// its arguments. On error the fiber is aborted, and no instance is created.
+/// Other error types are rejected, since only a `ForeignError` aborts the fiber.
+///
+/// ```ignore
+/// #[wren_methods]
+/// impl Color {
+///     #[construct]
+///     fn new(r: f64, g: f64, b: f64) -> rust_wren::Result<Self> {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// An associated const marked with `#[wren_const]` is exposed as a static
+/// getter, ie. `Limits.MAX` in Wren. The value is converted to Wren on each
+/// access, and can be renamed with `#[wren_const(name = ...)]`.
+///
+/// ```ignore
+/// #[wren_methods]
+/// impl Limits {
+///     #[wren_const]
+///     const MAX: f64 = 100.0;
+/// }
+/// ```
+///
+/// Without a `#[construct]` method, the class gets a `construct new()` that
+/// builds the value with its `Default` implementation.
+///
+/// ```ignore
+/// #[wren_class]
+/// #[derive(Default)]
+/// struct Counter {
+///     count: f64,
+/// }
+///
+/// #[wren_methods]
+/// impl Counter {
+///     fn count(&self) -> f64 {
+///         self.count
+///     }
+/// }
+/// ```
+///
+/// A method marked with `#[manual_return]` builds its own return value,
+/// for results that have no single `ToWren` type, like a list of mixed
+/// values. The method must return `()`, and is responsible for leaving a
+/// valid value in slot 0 through its `#[ctx]` before returning. The receiver
+/// is kept alive during the call, but should be read before slot 0 is replaced.
+///
+/// ```ignore
+/// #[wren_methods]
+/// impl Foo {
+///     #[manual_return]
+///     fn pair(&self, #[ctx] ctx: &mut WrenContext) {
+///         // The list replaces the receiver in slot 0.
+///         let (name, count) = (self.name.clone(), self.count);
+///         let mut list = WrenList::new(ctx);
+///         list.push(ctx, name);
+///         list.push(ctx, count);
+///         ctx.ensure_slots(1);
+///         ToWren::put(list, ctx, 0);
+///     }
+/// }
+/// ```
+///
+/// Methods and consts can be gated with `#[cfg(...)]`. A compiled out method
+/// is neither bound nor declared, so feature flagged APIs only exist in Wren
+/// when the feature is enabled. Mutually exclusive methods may share a Wren
+/// signature. Constructors and finalizers can't be gated. The `DECLARATION`
+/// generated by `wren_module!` is built before features are known, and still
+/// contains gated methods.
+///
+/// ```ignore
+/// #[wren_methods]
+/// impl Foo {
+///     #[cfg(feature = "network")]
+///     fn connect(&mut self, address: String) {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// A method marked with `#[finalize]` is called when the instance is garbage
+/// collected, before the value is dropped. Garbage collection is in progress,
+/// so the method receives no `WrenContext` and must not touch the VM.
+///
+/// ```ignore
+/// #[wren_methods]
+/// impl Foo {
+///     #[finalize]
+///     fn finalize(&mut self) {
+///         self.buffer.flush();
+///     }
+/// }
+/// ```
+///
+/// A method marked with `#[to_string]` is bound to Wren's `toString`, which
+/// is used by string interpolation and `System.print()`. For types that
+/// implement `Display`, the method can simply defer to it.
+///
+/// ```ignore
+/// #[wren_methods]
+/// impl Foo {
+///     #[to_string]
+///     fn to_wren_string(&self) -> String {
+///         self.to_string()
+///     }
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn wren_methods(_: TokenStream, item: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(item as ItemImpl);
 
     build_wren_methods(ast)
-        .expect("Failed to generate wren method implementations")
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Passes a single field tuple struct wrapping a number to and from Wren as a plain number.
+///
+/// ```ignore
+/// #[derive(WrenNumber)]
+/// struct EntityId(u32);
+/// ```
+#[proc_macro_derive(WrenNumber)]
+pub fn wren_number(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    gen_number_impl(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
 
+/// Declares a module of foreign classes, generating both their registration
+/// and their Wren declarations from the same definitions.
+///
+/// The given Rust module is emitted with two additional items:
+///
+/// - `DECLARATION`, a string constant containing the `foreign class`
+///   declarations of every `#[wren_class]` struct in the module, with the
+///   constructors and methods of their `#[wren_methods]` impl blocks, and
+///   their properties.
+/// - `register`, a function registering every class with a module builder.
+///
+/// ```ignore
+/// wren_module! {
+///     pub mod shapes {
+///         use rust_wren::prelude::*;
+///
+///         #[wren_class]
+///         pub struct Rect {
+///             #[getset]
+///             width: f64,
+///         }
+///
+///         #[wren_methods]
+///         impl Rect {
+///             #[construct]
+///             fn new(width: f64) -> Self {
+///                 Rect { width }
+///             }
+///         }
+///     }
+/// }
+///
+/// let mut vm = WrenBuilder::new().with_module("shapes", shapes::register).build();
+/// vm.interpret("shapes", shapes::DECLARATION).unwrap();
+/// ```
+#[proc_macro]
+pub fn wren_module(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as ItemMod);
+
+    match impl_wren_module(item) {
+        Ok(item) => quote!(#item).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn impl_wren_module(mut item: ItemMod) -> syn::Result<ItemMod> {
+    let items = match item.content {
+        Some((_, ref mut items)) => items,
+        None => {
+            return Err(syn::Error::new_spanned(
+                item,
+                "wren_module! requires a module with a body, ie. `mod name { ... }`",
+            ))
+        }
+    };
+
+    // Classes in declaration order, with their Wren name and declaration lines.
+    let mut classes: Vec<(Ident, String, Vec<String>)> = vec![];
+    let mut methods: Vec<(Ident, Vec<String>)> = vec![];
+
+    for item in items.iter() {
+        match item {
+            Item::Struct(item_struct) => {
+                if let Some(attr) = find_attr(&item_struct.attrs, "wren_class") {
+                    let args = if attr.tokens.is_empty() {
+                        WrenClassArgs::default()
+                    } else {
+                        attr.parse_args::<WrenClassArgs>()?
+                    };
+                    let class_name = get_class_name(&args, &item_struct.ident);
+                    let props = declare_class_props(item_struct)?;
+                    classes.push((item_struct.ident.clone(), class_name, props));
+                }
+            }
+            Item::Impl(item_impl) if find_attr(&item_impl.attrs, "wren_methods").is_some() => {
+                let ident = match &*item_impl.self_ty {
+                    Type::Path(type_path) => type_path.path.segments.last().map(|segment| segment.ident.clone()),
+                    _ => None,
+                };
+                let ident = ident
+                    .ok_or_else(|| syn::Error::new_spanned(&item_impl.self_ty, "Expected a #[wren_class] struct"))?;
+                methods.push((ident, declare_wren_methods(item_impl)?));
+            }
+            _ => {}
+        }
+    }
+
+    for (ident, _) in &methods {
+        if !classes.iter().any(|(class_ident, _, _)| class_ident == ident) {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "#[wren_methods] in wren_module! must be for a #[wren_class] struct declared in the same module",
+            ));
+        }
+    }
+
+    // Same layout as `WrenForeignClass::wren_declaration()`, methods followed by properties.
+    let mut declaration = String::new();
+    for (ident, class_name, props) in &classes {
+        declaration.push_str(&format!("foreign class {} {{\n", class_name));
+        let class_methods = methods
+            .iter()
+            .filter(|(method_ident, _)| method_ident == ident)
+            .flat_map(|(_, decls)| decls);
+        for line in class_methods.chain(props) {
+            declaration.push_str("    ");
+            declaration.push_str(line);
+            declaration.push('\n');
+        }
+        declaration.push_str("}\n");
+    }
+
+    let class_idents = classes.iter().map(|(ident, _, _)| ident);
+    items.push(syn::parse_quote! {
+        /// Wren declarations of the foreign classes in this module.
+        pub const DECLARATION: &str = #declaration;
+    });
+    items.push(syn::parse_quote! {
+        /// Registers the foreign classes in this module.
+        pub fn register(module: &mut rust_wren::ModuleBuilder) {
+            #(module.register::<#class_idents>();)*
+        }
+    });
+
+    Ok(item)
+}
+
+/// Find an attribute by the last segment of its path, so both
+/// `#[wren_class]` and `#[rust_wren::prelude::wren_class]` match.
+fn find_attr<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attrs.iter().find(|attr| {
+        attr.path
+            .segments
+            .last()
+            .map(|segment| segment.ident == name)
+            .unwrap_or(false)
+    })
+}
+
 /// Convenience macro for creating an error that has compile time line and module information.
+///
+/// Builds a `ForeignError::Annotated`, with the source file and line of the
+/// macro invocation. When the error aborts the fiber, the location is added as a
+/// foreign frame at the top of the runtime error's stack trace.
+///
+/// ```ignore
+/// fn load(path: &str) -> rust_wren::Result<String> {
+///     std::fs::read_to_string(path).map_err(|err| foreign_error!(err))
+/// }
+/// ```
 #[proc_macro]
 pub fn foreign_error(args: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(args as Expr);
@@ -98,6 +483,47 @@ pub fn foreign_error(args: TokenStream) -> TokenStream {
     gen.into()
 }
 
+/// Convenience macro for registering multiple foreign classes in a module.
+///
+/// Expands to a `register` call on the given module builder for each type.
+///
+/// ```ignore
+/// WrenBuilder::new().with_module("game", |module| {
+///     register_classes!(module, Sprite, Pos, Rect);
+/// });
+/// ```
+#[proc_macro]
+pub fn register_classes(args: TokenStream) -> TokenStream {
+    let RegisterSpec { module, types } = parse_macro_input!(args as RegisterSpec);
+
+    let gen = quote! {
+        {
+            #(#module.register::<#types>();)*
+        }
+    };
+
+    gen.into()
+}
+
+struct RegisterSpec {
+    /// Expression evaluating to the module builder.
+    module: Expr,
+    /// Foreign class types to register.
+    types: Vec<Type>,
+}
+
+impl Parse for RegisterSpec {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let module = input.parse::<Expr>()?;
+        input.parse::<Token![,]>()?;
+        let types = Punctuated::<Type, Token![,]>::parse_terminated(input)?
+            .into_iter()
+            .collect();
+
+        Ok(RegisterSpec { module, types })
+    }
+}
+
 /// Generate an implementation for converting a tuple struct to Wren slots.
 #[proc_macro]
 pub fn generate_tuple_to_wren(args: TokenStream) -> TokenStream {
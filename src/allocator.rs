@@ -0,0 +1,40 @@
+//! Custom heap allocation for the Wren VM.
+use std::os::raw::c_void;
+
+/// Allocator backing all of a VM's heap memory.
+///
+/// Set on the builder with [`WrenBuilder::with_allocator`](../struct.WrenBuilder.html#method.with_allocator).
+/// Without one, the VM uses Rust's global allocator.
+///
+/// Wren assumes `malloc` semantics. It doesn't pass the size of the block
+/// when reallocating or deallocating, so the allocator has to keep track
+/// of block sizes itself if it needs them. Blocks must be aligned to at
+/// least 8 bytes.
+///
+/// # Safety
+///
+/// Wren trusts the returned blocks to be valid for the requested size,
+/// and not to overlap any other live block.
+pub unsafe trait WrenAllocator {
+    /// Allocates a new block of `size` bytes, which is never 0.
+    ///
+    /// Returns null when the allocation failed.
+    fn alloc(&self, size: usize) -> *mut c_void;
+
+    /// Resizes a block to `new_size` bytes, which is never 0, keeping its contents.
+    ///
+    /// Returns the address of the resized block, which may have moved. When
+    /// the reallocation fails, null is returned and the old block is left live.
+    ///
+    /// # Safety
+    ///
+    /// `memory` is a live block returned by this allocator.
+    unsafe fn realloc(&self, memory: *mut c_void, new_size: usize) -> *mut c_void;
+
+    /// Frees a block.
+    ///
+    /// # Safety
+    ///
+    /// `memory` is a live block returned by this allocator.
+    unsafe fn dealloc(&self, memory: *mut c_void);
+}
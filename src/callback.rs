@@ -0,0 +1,154 @@
+//! Rust closures callable from Wren.
+//!
+//! A [`WrenCallback`](struct.WrenCallback.html) is passed into Wren as an
+//! instance of a hidden foreign class, which responds to `call` like a Wren
+//! `Fn`. Wren APIs expecting a function can be handed a Rust closure.
+//!
+//! ```
+//! # use rust_wren::{prelude::*, callback::WrenCallback};
+//! let mut vm = WrenBuilder::new().build();
+//! vm.interpret("example", r#"
+//! class Example {
+//!   static twice(fn) { fn.call(fn.call(1)) }
+//! }
+//! "#).unwrap();
+//!
+//! vm.context(|ctx| {
+//!     let add_one = WrenCallback::new(|ctx| {
+//!         let value = ctx.get_slot::<f64>(1).unwrap_or(0.0);
+//!         ToWren::put(value + 1.0, ctx, 0);
+//!     });
+//!
+//!     let twice = ctx.make_call_ref("example", "Example", "twice(_)").unwrap();
+//!     let result = twice.call::<_, f64>(ctx, add_one).unwrap();
+//!     assert_eq!(result, 3.0);
+//! });
+//! ```
+use crate::{
+    bindings,
+    class::{WrenCell, WrenForeignClass},
+    errors::{ForeignError, WrenError},
+    foreign::{ForeignClass, ForeignMethod},
+    value::ToWren,
+    ModuleBuilder, WrenContext,
+};
+use std::os::raw::c_void;
+
+/// Hidden module containing the foreign class backing callbacks.
+pub(crate) const CALLBACK_MODULE: &str = "rust_wren/callback";
+
+/// Maximum number of arguments Wren can pass to a callback.
+///
+/// Wren signatures encode arity, so each supported arity needs
+/// its own `call` method binding.
+pub const MAX_CALLBACK_ARITY: usize = 4;
+
+/// Rust closure that can be called from Wren.
+///
+/// The closure receives the context of the `call` from Wren. Arguments
+/// are in slots `1` to the arity, and the return value must be put into
+/// slot `0`. If nothing is put, the callback returns the receiver.
+pub struct WrenCallback {
+    func: Box<dyn Fn(&mut WrenContext)>,
+}
+
+impl WrenCallback {
+    pub fn new<F>(func: F) -> Self
+    where
+        F: 'static + Fn(&mut WrenContext),
+    {
+        WrenCallback { func: Box::new(func) }
+    }
+}
+
+impl WrenForeignClass for WrenCallback {
+    const NAME: &'static str = "Callback";
+
+    fn register(bindings: &mut ModuleBuilder) {
+        bindings.add_class_binding(
+            Self::NAME,
+            ForeignClass {
+                allocate: callback_allocate,
+                finalize: callback_finalize,
+            },
+        );
+        bindings.add_reverse_class_lookup::<Self>();
+
+        for arity in 0..=MAX_CALLBACK_ARITY {
+            bindings.add_method_binding(
+                Self::NAME,
+                ForeignMethod {
+                    is_static: false,
+                    arity,
+                    sig: call_signature(arity),
+                    func: callback_call,
+                    doc: None,
+                },
+            );
+        }
+    }
+
+    fn wren_declaration() -> String {
+        let mut decl = format!("foreign class {} {{\n", Self::NAME);
+        for arity in 0..=MAX_CALLBACK_ARITY {
+            let params = (0..arity).map(|i| format!("a{}", i)).collect::<Vec<_>>().join(", ");
+            decl.push_str(&format!("    foreign call({})\n", params));
+        }
+        decl.push_str("}\n");
+        decl
+    }
+}
+
+/// Signature of the `call` method with the given arity, ie. `call(_,_)`.
+fn call_signature(arity: usize) -> String {
+    format!("call({})", vec!["_"; arity].join(","))
+}
+
+/// Moves the callback into Wren as an instance of the hidden foreign class.
+///
+/// # Errors
+///
+/// Aborts the current fiber with a foreign error if the hidden class
+/// can't be found.
+impl ToWren for WrenCallback {
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        // The class is loaded straight into the target slot, so the
+        // receiver of a call in slot 0 is left untouched.
+        if let Err(err) = ctx.put_foreign(self, slot) {
+            ForeignError::new(err).put(ctx, slot);
+        }
+    }
+}
+
+/// Callbacks are only created from Rust. The class declares no
+/// constructor, so Wren has no way to call this.
+extern "C" fn callback_allocate(vm: *mut bindings::WrenVM) {
+    let vm: &mut bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+    let mut ctx = WrenContext::new(vm);
+    ForeignError::new(WrenError::ForeignType).put(&mut ctx, 0);
+}
+
+unsafe extern "C" fn callback_finalize(data: *mut c_void) {
+    // Contents were initialised by `put`, and are dropped in place
+    // before the garbage collector frees the memory.
+    std::ptr::drop_in_place(data as *mut WrenCell<WrenCallback>);
+}
+
+/// Entry point from Wren for all arities of `call`.
+extern "C" fn callback_call(vm: *mut bindings::WrenVM) {
+    let vm: &mut bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+    let mut ctx = WrenContext::new(vm);
+
+    let cell = match ctx.get_foreign_cell::<WrenCallback>(0) {
+        Ok(cell) => cell,
+        Err(err) => return ForeignError::new(err).put(&mut ctx, 0),
+    };
+
+    // Shared borrow, so the callback can be called again re-entrantly from Wren.
+    let callback = match cell.try_borrow() {
+        Ok(callback) => callback,
+        Err(err) => return ForeignError::new(err).put(&mut ctx, 0),
+    };
+
+    (callback.func)(&mut ctx);
+}
@@ -96,15 +96,27 @@
 //! sprite.set(Pos.new(2, 4))
 //! "#).expect("Interpret failed");
 //! ```
-use crate::{ModuleBuilder, WrenError, WrenResult};
+use crate::{
+    bindings,
+    handle::{WrenHandle, WrenRef},
+    value::{FromWren, ToWren},
+    ModuleBuilder, WrenContext, WrenError, WrenResult,
+};
 pub use std::cell::{Ref, RefMut};
-use std::{any::TypeId, cell::RefCell, fmt, os::raw::c_void};
+use std::{any::TypeId, cell::RefCell, fmt, marker::PhantomData, os::raw::c_void};
 
 /// Trait for any type to be registered as a foreign class.
 pub trait WrenForeignClass {
     const NAME: &'static str;
 
     fn register(bindings: &mut ModuleBuilder);
+
+    /// Wren source declaring the foreign class, with its constructors,
+    /// foreign methods and properties.
+    ///
+    /// Can be interpreted instead of declaring the class by hand, so the
+    /// signatures in Wren always match the Rust bindings.
+    fn wren_declaration() -> String;
 }
 
 /// Wrapper for foreign class values stored in Wren.
@@ -266,6 +278,121 @@ where
     }
 }
 
+/// Handle to a foreign class instance that already lives in Wren.
+///
+/// Passing a `ForeignRef` to Wren hands over a reference to the existing
+/// object, instead of moving a new value into Wren's heap like passing a
+/// foreign class by value does. This allows a method to return `self`, or
+/// an object it holds on to, without duplicating it.
+///
+/// The type of the object is checked when the reference is retrieved
+/// from a slot.
+///
+/// # Finalization
+///
+/// The handle keeps the object from being garbage collected, so its
+/// finalizer won't run while any `ForeignRef` to it is alive. Like
+/// [`WrenHandle`](../handle/struct.WrenHandle.html), it must be dropped
+/// before the VM is dropped.
+///
+/// # Examples
+///
+/// During a foreign method call the receiver is in slot 0, so a method
+/// can return a reference to itself.
+///
+/// ```
+/// # use rust_wren::{prelude::*, class::ForeignRef};
+/// #[wren_class]
+/// struct Builder(f64);
+///
+/// #[wren_methods]
+/// impl Builder {
+///     #[construct]
+///     fn new() -> Self {
+///         Self(0.0)
+///     }
+///
+///     fn add(&mut self, #[ctx] ctx: &mut WrenContext, value: f64) -> rust_wren::Result<ForeignRef<Self>> {
+///         self.0 += value;
+///         ForeignRef::receiver(ctx).map_err(|err| foreign_error!(err))
+///     }
+/// }
+/// ```
+pub struct ForeignRef<T> {
+    handle: WrenHandle,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ForeignRef<T>
+where
+    T: 'static + WrenForeignClass,
+{
+    /// Retrieve a reference to the receiver of the foreign method
+    /// currently being called.
+    pub fn receiver(ctx: &WrenContext) -> WrenResult<Self> {
+        <Self as FromWren>::get_slot(ctx, 0)
+    }
+
+    /// Borrow the cell of the referenced object.
+    ///
+    /// The handle is placed in a scratch slot past the caller's slots,
+    /// so values already in slots are left untouched. The same scratch
+    /// slot is reused for the rest of the context.
+    pub fn cell<'wren>(&self, ctx: &WrenContext<'wren>) -> WrenResult<&'wren WrenCell<T>> {
+        let scratch = ctx.scratch_slot();
+
+        unsafe {
+            bindings::wrenSetSlotHandle(ctx.vm_ptr(), scratch, self.handle.raw_ptr().as_ptr());
+        }
+
+        ctx.get_foreign_cell::<T>(scratch)
+    }
+
+    /// Unwraps the underlying handle.
+    pub fn into_handle(self) -> WrenHandle {
+        self.handle
+    }
+}
+
+impl<'wren, T> FromWren<'wren> for ForeignRef<T>
+where
+    T: 'static + WrenForeignClass,
+{
+    type Output = Self;
+
+    fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        // Type check the foreign value before taking a handle to it.
+        <WrenCell<T> as FromWren>::get_slot(ctx, slot_num)?;
+
+        let handle = WrenRef::get_slot(ctx, slot_num)?.leak()?;
+
+        Ok(ForeignRef {
+            handle,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T> ToWren for ForeignRef<T> {
+    #[inline]
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        ToWren::put(&self.handle, ctx, slot)
+    }
+}
+
+impl<T> ToWren for &ForeignRef<T> {
+    #[inline]
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        ToWren::put(&self.handle, ctx, slot)
+    }
+}
+
+impl<T> fmt::Debug for ForeignRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ForeignRef").field(&self.handle).finish()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
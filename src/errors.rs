@@ -9,10 +9,18 @@ use std::{
     error::Error,
     ffi::CString,
     fmt::{self, Display},
+    io,
+    path::PathBuf,
 };
 
 pub type WrenResult<T> = ::std::result::Result<T, WrenError>;
 
+/// Convert a string to a C string for Wren, failing on interior null bytes
+/// instead of panicking.
+pub(crate) fn to_c_string<T: Into<Vec<u8>>>(value: T, context: &'static str) -> WrenResult<CString> {
+    CString::new(value).map_err(|_| WrenError::NulByte { context })
+}
+
 #[derive(Debug)]
 pub enum WrenError {
     CompileError(Vec<WrenCompileError>),
@@ -20,9 +28,30 @@ pub enum WrenError {
         message: String,
         foreign: Option<Box<dyn Error>>,
         stack: Vec<WrenStackFrame>,
+        /// Result code returned by the VM call that failed.
+        ///
+        /// Usually a runtime error, but a missing foreign class with strict
+        /// bindings is reported after the VM succeeded.
+        result: bindings::WrenInterpretResult,
     },
     ModuleNotFound(String),
     VariableNotFound(String),
+
+    /// Module resolver rejected an import.
+    ImportRejected {
+        importer: String,
+        module: String,
+        reason: String,
+    },
+
+    /// Variable name is not a valid Wren identifier.
+    InvalidVariableName(String),
+
+    /// Reading a script file failed.
+    Io {
+        path: PathBuf,
+        cause: io::Error,
+    },
     ResultQueueMismatch,
     ErrorAbsent(bindings::WrenInterpretResult),
     UserDataNull,
@@ -38,8 +67,54 @@ pub enum WrenError {
         actual: WrenType,
     },
     Utf8(::std::str::Utf8Error),
+
+    /// String from Wren could not be interpreted as a number.
+    NotNumeric(String),
+
+    /// String passed to Wren contains an interior null byte.
+    NulByte {
+        context: &'static str,
+    },
     ForeignType,
 
+    /// Map key put into Wren is not a value type, like a string or number.
+    InvalidMapKey(WrenType),
+
+    /// Receiver of a foreign method is not a foreign object.
+    ReceiverNotForeign,
+
+    /// Foreign class or method declared in Wren has no binding
+    /// registered with the builder.
+    ///
+    /// Only reported when strict bindings are enabled.
+    BindingNotFound {
+        module: String,
+        class: String,
+        sig: Option<String>,
+    },
+
+    /// Foreign class has no binding registered with a module.
+    ClassNotRegistered(&'static str),
+
+    /// A number from Wren falls outside the range of the
+    /// Rust numeric type it is being converted to.
+    NumberRange {
+        value: f64,
+        target: &'static str,
+    },
+
+    /// Value from Wren does not match any variant of a C-like enum.
+    UnknownVariant {
+        ty: &'static str,
+        value: String,
+    },
+
+    /// Index into a Wren collection is past its end.
+    IndexOutOfBounds {
+        index: usize,
+        len: usize,
+    },
+
     /// Wrapped error caused by invalid call from Wren to Rust.
     /// Used in generated code of wrapped functions.
     ForeignCall {
@@ -62,6 +137,11 @@ pub enum WrenError {
     /// is being leaked twice.
     AlreadyLeaked,
 
+    /// Call into Wren while Wren is already executing, for example from
+    /// within a foreign method. Wren doesn't support re-entrant calls, and
+    /// the nested call would corrupt the running fiber's stack.
+    ReentrantCall,
+
     /// Attempt to borrow `WrenCell`, but already borrowed.
     BorrowMutError,
 
@@ -72,7 +152,19 @@ pub enum WrenError {
     Ctx(Box<dyn Error>),
 }
 
-impl ::std::error::Error for WrenError {}
+impl ::std::error::Error for WrenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WrenError::RuntimeError { foreign, .. } => foreign.as_deref(),
+            WrenError::Utf8(err) => Some(err),
+            WrenError::Io { cause, .. } => Some(cause),
+            WrenError::ForeignCall { cause, .. } => Some(&**cause),
+            WrenError::GetArg { cause, .. } => Some(&**cause),
+            WrenError::Ctx(err) => Some(&**err),
+            _ => None,
+        }
+    }
+}
 
 impl ::std::fmt::Display for WrenError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -99,7 +191,14 @@ impl ::std::fmt::Display for WrenError {
                 Ok(())
             }
             WrenError::ModuleNotFound(mod_name) => write!(f, "Module '{}' not found", mod_name),
+            WrenError::ImportRejected {
+                importer,
+                module,
+                reason,
+            } => write!(f, "Import of '{}' from '{}' rejected: {}", module, importer, reason),
             WrenError::VariableNotFound(var_name) => write!(f, "Variable '{}' not found", var_name),
+            WrenError::InvalidVariableName(var_name) => write!(f, "Invalid variable name '{}'", var_name),
+            WrenError::Io { path, cause } => write!(f, "Reading script '{}' failed: {}", path.display(), cause),
             WrenError::ResultQueueMismatch => write!(
                 f,
                 "Wren VM returned success, but errors were recorded on the error queue"
@@ -113,16 +212,57 @@ impl ::std::fmt::Display for WrenError {
             WrenError::NullPtr => writeln!(f, "Unexpected null pointer"),
             WrenError::SlotOutOfBounds(slot) => write!(f, "Slot {} is out of bounds", slot),
             WrenError::SlotType { expected, actual } => {
-                write!(f, "Expected slot type '{:?}', actual '{:?}'", expected, actual)
+                write!(f, "Expected slot type '{:?}', actual '{:?}'", expected, actual)?;
+                // Unknown covers every object without a slot API of its own, which is rarely obvious.
+                if *actual == WrenType::Unknown {
+                    write!(
+                        f,
+                        "; the slot holds an UNKNOWN value, which usually means a static class receiver \
+                         or a class missing the `foreign` keyword"
+                    )?;
+                }
+                Ok(())
             }
             WrenError::InvalidSlot => write!(f, "Invalid slot"),
             WrenError::Utf8(utf8_err) => ::std::fmt::Display::fmt(utf8_err, f),
+            WrenError::NotNumeric(value) => write!(f, "String '{}' is not a number", value),
+            WrenError::NulByte { context } => write!(f, "{} contains a null byte", context),
             WrenError::ForeignType => write!(f, "Unexpected foreign type"),
+            WrenError::InvalidMapKey(ty) => write!(f, "Map key must be a value type, not '{:?}'", ty),
+            WrenError::ReceiverNotForeign => write!(
+                f,
+                "Receiver in slot 0 is UNKNOWN, not a foreign object. Was an instance method called on an \
+                 object whose Wren class isn't declared `foreign`?"
+            ),
+            WrenError::BindingNotFound { module, class, sig } => match sig {
+                Some(sig) => write!(
+                    f,
+                    "Foreign method '{}' of class '{}' in module '{}' is not registered",
+                    sig, class, module
+                ),
+                None => write!(f, "Foreign class '{}' in module '{}' is not registered", class, module),
+            },
+            WrenError::ClassNotRegistered(class) => write!(f, "Foreign class '{}' is not registered", class),
+            WrenError::NumberRange { value, target } => {
+                write!(f, "Number {} is out of range for type '{}'", value, target)
+            }
+            WrenError::UnknownVariant { ty, value } => write!(f, "Unknown variant '{}' for enum '{}'", value, ty),
+            WrenError::IndexOutOfBounds { index, len } => {
+                write!(f, "Index {} is out of bounds for length {}", index, len)
+            }
             WrenError::ForeignCall { function, cause } => {
-                write!(f, "Invalid call to foreign '{}': {}", function, cause)
+                write!(f, "Invalid call to foreign '{}'", function)?;
+                write_cause(f, cause)
+            }
+            WrenError::GetArg { slot, cause } => {
+                write!(f, "Getting argument from slot {} failed", slot)?;
+                write_cause(f, cause)
             }
-            WrenError::GetArg { slot, cause } => write!(f, "Getting argument from slot {} failed: {}", slot, cause),
             WrenError::AlreadyLeaked => write!(f, "Already leaked handle"),
+            WrenError::ReentrantCall => write!(
+                f,
+                "Wren can't be called while it's executing. Was the call made from within a foreign method?"
+            ),
             WrenError::BorrowMutError | WrenError::BorrowError => write!(
                 f,
                 "Foreign class already borrowed. Was it passed into multiple foreign call arguments?"
@@ -132,6 +272,15 @@ impl ::std::fmt::Display for WrenError {
     }
 }
 
+/// Writes a wrapped error on the following lines, indented one level
+/// deeper, so nested causes read as a breakdown from outer to root.
+fn write_cause(f: &mut fmt::Formatter, cause: &WrenError) -> fmt::Result {
+    for line in cause.to_string().lines() {
+        write!(f, "\n  {}", line)?;
+    }
+    Ok(())
+}
+
 impl WrenError {
     /// Construct a `ForeignCall` variant.
     ///
@@ -158,6 +307,42 @@ impl WrenError {
     pub fn is_compile_error(&self) -> bool {
         matches!(self, WrenError::CompileError(_))
     }
+
+    /// The message the fiber was aborted with, without the stack trace.
+    ///
+    /// For `Fiber.abort("msg")` this is `msg`. When a foreign method aborted
+    /// the fiber, it's the message of the Rust error. Returns `None` for
+    /// errors other than runtime errors.
+    pub fn abort_message(&self) -> Option<&str> {
+        match self {
+            WrenError::RuntimeError { message, .. } => Some(message.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Finds the columns of compile errors in the given source, which
+    /// was interpreted as the given module.
+    pub(crate) fn locate_in_source(mut self, module: &str, source: &str) -> Self {
+        if let WrenError::CompileError(errors) = &mut self {
+            for err in errors.iter_mut().filter(|err| err.module == module) {
+                err.locate(source);
+            }
+        }
+        self
+    }
+
+    /// Raw result code of the failed VM call that caused this error.
+    ///
+    /// Returns `None` for errors that don't originate from interpreting
+    /// or calling into the VM.
+    pub fn interpret_result(&self) -> Option<bindings::WrenInterpretResult> {
+        match self {
+            WrenError::CompileError(_) => Some(bindings::WrenInterpretResult_WREN_RESULT_COMPILE_ERROR),
+            WrenError::RuntimeError { result, .. } => Some(*result),
+            WrenError::ErrorAbsent(result) => Some(*result),
+            _ => None,
+        }
+    }
 }
 
 /// Wren VM errors collected from the error callback function.
@@ -170,6 +355,8 @@ pub enum WrenVmError {
         module: SmolStr,
         message: String,
         line: i32,
+        /// Source text of the token the error was reported at, when the message names one.
+        token: Option<String>,
     },
     /// Error in Wren VM during runtime.
     Runtime { msg: String },
@@ -201,6 +388,42 @@ pub struct WrenCompileError {
     pub module: SmolStr,
     pub message: String,
     pub line: i32,
+    /// Source text of the token the error was reported at.
+    ///
+    /// Wren names the token in the message, ie. `Error at 'foo': ...`, except
+    /// for errors at a newline, at the end of the file, or in the lexer. Tokens
+    /// longer than Wren's maximum variable name are truncated.
+    pub token: Option<String>,
+    /// Column of the token on the error's line, starting at 1 and counted in chars.
+    ///
+    /// Wren doesn't report columns, so the column is found by searching the
+    /// line for the token. It's only known for errors in source passed to
+    /// `interpret` or `compile_only`, and when the token occurs more than once on the line, the
+    /// first occurrence is taken.
+    pub column: Option<usize>,
+}
+
+impl WrenCompileError {
+    /// Finds the column of the error's token in the source it was compiled from.
+    pub(crate) fn locate(&mut self, source: &str) {
+        let token = match &self.token {
+            // Truncated tokens are matched by their prefix.
+            Some(token) => token.strip_suffix("...").unwrap_or(token),
+            None => return,
+        };
+
+        self.column = source
+            .lines()
+            .nth((self.line as usize).saturating_sub(1))
+            .and_then(|line| line.find(token).map(|index| line[..index].chars().count() + 1));
+    }
+}
+
+/// Extracts the token named by a Wren compile error message, ie. `foo` from
+/// `Error at 'foo': Expected ...`.
+pub(crate) fn parse_error_token(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("Error at '")?;
+    rest.find("': ").map(|end| rest[..end].to_owned())
 }
 
 // TODO: Rename to ForeignResult
@@ -0,0 +1,155 @@
+//! Fibers, Wren's lightweight coroutines.
+use crate::{
+    bindings,
+    callback::CALLBACK_MODULE,
+    errors::{WrenError, WrenResult},
+    handle::{wren_call, WrenHandle},
+    types::WrenType,
+    value::{FromWren, ToWren},
+    vm::WrenContext,
+};
+use std::fmt;
+
+/// Handle to a fiber in Wren.
+///
+/// Requires the [`WrenContext`] that owns the fiber
+/// to perform operations on it.
+///
+/// Operations are performed by calling the methods of Wren's
+/// `Fiber` class. The function symbols are compiled once and
+/// cached in the VM.
+pub struct WrenFiber(WrenHandle);
+
+impl WrenFiber {
+    /// Create a `WrenFiber` from a given `WrenHandle`.
+    ///
+    /// # Safety
+    ///
+    /// This is unsafe because the handle cannot be
+    /// checked if its type is indeed fiber.
+    #[doc(hidden)]
+    pub unsafe fn from_handle_unchecked(handle: WrenHandle) -> Self {
+        WrenFiber(handle)
+    }
+
+    /// Starts or resumes the fiber, passing the argument as
+    /// the value of the fiber's function parameter or the
+    /// result of its `Fiber.yield()` call.
+    ///
+    /// Returns the value the fiber yields or returns.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fiber is done, or if it aborts
+    /// with a runtime error.
+    pub fn call<'wren, A, R>(&self, ctx: &mut WrenContext, arg: A) -> WrenResult<R::Output>
+    where
+        A: ToWren,
+        R: FromWren<'wren>,
+    {
+        self.invoke::<A, R>(ctx, "call(_)", arg)
+    }
+
+    /// Transfers control to the fiber, passing the argument as
+    /// the value of the fiber's function parameter or the
+    /// result of its `Fiber.yield()` call.
+    ///
+    /// Unlike [`call`](#method.call), the fiber does not return
+    /// to its caller when it yields.
+    pub fn transfer<'wren, A, R>(&self, ctx: &mut WrenContext, arg: A) -> WrenResult<R::Output>
+    where
+        A: ToWren,
+        R: FromWren<'wren>,
+    {
+        self.invoke::<A, R>(ctx, "transfer(_)", arg)
+    }
+
+    /// Returns `true` if the fiber has run to completion, or
+    /// was aborted with a runtime error.
+    pub fn is_done(&self, ctx: &mut WrenContext) -> WrenResult<bool> {
+        self.invoke::<(), bool>(ctx, "isDone", ())
+    }
+
+    /// Returns the error the fiber was aborted with, or `None`
+    /// if it has not been aborted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fiber was aborted with a value
+    /// that is not a string.
+    pub fn error(&self, ctx: &mut WrenContext) -> WrenResult<Option<String>> {
+        self.invoke::<(), Option<String>>(ctx, "error", ())
+    }
+
+    /// Checks whether the value of the handle is a fiber, using Wren's `is` operator.
+    pub(crate) fn is_fiber(&self, ctx: &mut WrenContext) -> WrenResult<bool> {
+        // Core classes are implicitly imported into every module, including the hidden ones.
+        let fiber_class = ctx.get_var(CALLBACK_MODULE, "Fiber")?;
+        self.invoke::<_, bool>(ctx, "is(_)", fiber_class)
+    }
+
+    fn invoke<'wren, A, R>(&self, ctx: &mut WrenContext, signature: &str, arg: A) -> WrenResult<R::Output>
+    where
+        A: ToWren,
+        R: FromWren<'wren>,
+    {
+        let mut func = ctx.cached_symbol(signature)?;
+        let mut receiver = unsafe { self.0.raw_ptr() };
+
+        unsafe { wren_call::<A, R>(ctx, receiver.as_mut(), func.as_mut(), arg) }
+    }
+}
+
+/// Fibers have no slot type of their own, so the value is only checked to
+/// be a value Wren reports as an unknown type. Reading a fiber doesn't call
+/// into Wren, so the values in the other slots are left intact.
+///
+/// [`WrenContext::get_fiber`](../struct.WrenContext.html#method.get_fiber)
+/// also checks that the value is a fiber.
+///
+/// # Errors
+///
+/// Returns [`WrenError::SlotType`](../errors/enum.WrenError.html#variant.SlotType)
+/// if the value is not of an unknown type.
+impl<'wren> FromWren<'wren> for WrenFiber {
+    type Output = WrenFiber;
+
+    fn get_slot(ctx: &WrenContext, fiber_slot: i32) -> WrenResult<Self::Output> {
+        if ctx.slot_type(fiber_slot as usize) != Some(WrenType::Unknown) {
+            return Err(WrenError::SlotType {
+                actual: ctx.slot_type(fiber_slot as usize).unwrap(),
+                expected: WrenType::Unknown,
+            });
+        }
+
+        unsafe {
+            let fiber_handle = bindings::wrenGetSlotHandle(ctx.vm_ptr(), fiber_slot);
+            if fiber_handle.is_null() {
+                return Err(WrenError::NullPtr);
+            }
+            ctx.record_handle();
+
+            let destructors = ctx.destructor_sender();
+
+            Ok(WrenFiber(WrenHandle::from_raw(fiber_handle, destructors)))
+        }
+    }
+}
+
+impl fmt::Debug for WrenFiber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WrenFiber").field(unsafe { &self.0.raw_ptr() }).finish()
+    }
+}
+
+impl ToWren for WrenFiber {
+    fn put(self, ctx: &mut WrenContext, fiber_slot: i32) {
+        ToWren::put(self.0, ctx, fiber_slot)
+    }
+}
+
+impl ToWren for &WrenFiber {
+    fn put(self, ctx: &mut WrenContext, fiber_slot: i32) {
+        ToWren::put(&self.0, ctx, fiber_slot)
+    }
+}
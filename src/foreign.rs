@@ -1,12 +1,18 @@
 //! Foreign binding registry.
 //!
 //! Allows Wren to lookup Rust types at runtime.
-use crate::{bindings, WrenVm};
+use crate::{
+    bindings,
+    errors::{ForeignError, WrenError, WrenVmError},
+    vm::UserData,
+    ModuleBuilder, WrenContext, WrenVm,
+};
 use log::{trace, warn};
 use std::{
     any::TypeId,
+    borrow::{Borrow, Cow},
     collections::HashMap,
-    ffi::CStr,
+    ffi::{CStr, CString},
     os::raw::{c_char, c_void},
 };
 
@@ -15,8 +21,45 @@ pub struct ForeignBindings {
     pub(crate) classes: HashMap<ForeignClassKey, ForeignClass>,
     pub(crate) methods: HashMap<ForeignMethodKey, ForeignMethod>,
     pub(crate) reverse: HashMap<TypeId, ForeignClassKey>,
+    /// Closures backing foreign methods, indexed by their trampoline.
+    pub(crate) closures: Vec<ClosureMethod>,
+    /// Index of the closure backing each closure method, needed to
+    /// assign new trampolines when merging registries.
+    pub(crate) closure_methods: HashMap<ForeignMethodKey, usize>,
+}
+
+/// Foreign method implemented by a closure instead of generated code.
+pub type ClosureMethod = Box<dyn Fn(&mut WrenContext)>;
+
+/// Maximum number of closure methods that can be registered per VM.
+///
+/// Wren doesn't tell a foreign method which binding it was called through,
+/// so every closure needs its own trampoline function, taken from a fixed pool.
+pub const MAX_CLOSURE_METHODS: usize = 64;
+
+/// Entry point from Wren into the closure at index `N`.
+extern "C" fn closure_trampoline<const N: usize>(vm: *mut bindings::WrenVM) {
+    let userdata = unsafe { WrenVm::get_user_data(vm).expect("User data is null") };
+
+    // Closure is owned by the user data, which outlives the call.
+    let closure: *const ClosureMethod = &userdata.foreign.closures[N];
+
+    let vm: &mut bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+    let mut ctx = WrenContext::new(vm);
+    unsafe { (*closure)(&mut ctx) };
 }
 
+macro_rules! closure_trampolines {
+    ($($n:literal)*) => {
+        [$(closure_trampoline::<$n> as unsafe extern "C" fn(*mut bindings::WrenVM)),*]
+    };
+}
+
+pub(crate) const CLOSURE_TRAMPOLINES: [unsafe extern "C" fn(*mut bindings::WrenVM); MAX_CLOSURE_METHODS] = closure_trampolines!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+    32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+);
+
 /// Key for foreign class lookup.
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct ForeignClassKey {
@@ -31,7 +74,7 @@ pub struct ForeignClass {
 }
 
 /// Key for foreign method lookup.
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ForeignMethodKey {
     pub module: String,
     pub class: String,
@@ -46,6 +89,9 @@ pub struct ForeignMethod {
     pub arity: usize,
     pub sig: String,
     pub func: unsafe extern "C" fn(*mut bindings::WrenVM),
+    /// Documentation of the method, taken from the doc comment
+    /// of the Rust method by `#[wren_methods]`.
+    pub doc: Option<&'static str>,
 }
 
 impl ForeignBindings {
@@ -54,6 +100,68 @@ impl ForeignBindings {
             classes: HashMap::new(),
             methods: HashMap::new(),
             reverse: HashMap::new(),
+            closures: Vec::new(),
+            closure_methods: HashMap::new(),
+        }
+    }
+
+    /// Registers bindings for a module, like [`WrenBuilder::with_module`](../struct.WrenBuilder.html#method.with_module).
+    ///
+    /// Allows a registry to be prepared separately from the builder, and
+    /// combined with others using [`merge`](#method.merge).
+    pub fn with_module<'a, S, F>(mut self, module: S, func: F) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+        F: FnOnce(&mut ModuleBuilder),
+    {
+        let module_cow = module.into();
+        func(&mut ModuleBuilder::new(module_cow.borrow(), &mut self));
+        self
+    }
+
+    /// Moves all bindings from the other registry into this one.
+    ///
+    /// Bindings for the same class or method are replaced by the other's, as if
+    /// they were registered afterwards. A type registered in both keeps the
+    /// class it was first registered as in this registry.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the combined registries have more than
+    /// [`MAX_CLOSURE_METHODS`](constant.MAX_CLOSURE_METHODS.html) closure methods.
+    pub fn merge(&mut self, other: ForeignBindings) {
+        let ForeignBindings {
+            classes,
+            methods,
+            reverse,
+            closures,
+            closure_methods,
+        } = other;
+
+        // Closures are appended, so their trampolines are shifted along.
+        let offset = self.closures.len();
+        if offset + closures.len() > MAX_CLOSURE_METHODS {
+            panic!("Cannot register more than {} closure methods", MAX_CLOSURE_METHODS);
+        }
+        self.closures.extend(closures);
+
+        for (key, mut method) in methods {
+            match closure_methods.get(&key) {
+                Some(index) => {
+                    method.func = CLOSURE_TRAMPOLINES[offset + index];
+                    self.closure_methods.insert(key.clone(), offset + index);
+                }
+                None => {
+                    self.closure_methods.remove(&key);
+                }
+            }
+            self.methods.insert(key, method);
+        }
+
+        self.classes.extend(classes);
+
+        for (type_id, key) in reverse {
+            self.reverse.entry(type_id).or_insert(key);
         }
     }
 
@@ -79,18 +187,31 @@ impl ForeignBindings {
         let class = unsafe { CStr::from_ptr(class_name).to_owned().to_string_lossy().to_string() };
         trace!("bind_foreign_class {} {}", module, class);
 
-        let (allocate, finalize) = userdata
-            .foreign
-            .classes
-            .get(&ForeignClassKey { module, class })
-            .map(|foreign_class| {
-                let &ForeignClass { allocate, finalize } = foreign_class;
-                (Some(allocate), Some(finalize))
-            })
-            .unwrap_or_else(|| {
-                warn!("Warning: Foreign class not found. Did you forget to register it with the builder?");
-                (None, None)
-            });
+        let key = ForeignClassKey { module, class };
+        let (allocate, finalize) = match userdata.foreign.classes.get(&key) {
+            Some(&ForeignClass { allocate, finalize }) => (Some(allocate), Some(finalize)),
+            None => match userdata.strict_bindings {
+                Some(true) => {
+                    Self::record_missing_binding(
+                        userdata,
+                        WrenError::BindingNotFound {
+                            module: key.module,
+                            class: key.class,
+                            sig: None,
+                        },
+                    );
+
+                    // Without an allocator Wren can't construct the class safely.
+                    let allocate: unsafe extern "C" fn(*mut bindings::WrenVM) = missing_class_allocate;
+                    (Some(allocate), None)
+                }
+                Some(false) => (None, None),
+                None => {
+                    warn!("Warning: Foreign class not found. Did you forget to register it with the builder?");
+                    (None, None)
+                }
+            },
+        };
 
         bindings::WrenForeignClassMethods { allocate, finalize }
     }
@@ -122,21 +243,59 @@ impl ForeignBindings {
         let method = userdata.foreign.methods.get(&key).map(|m| m.func);
 
         if method.is_none() {
-            warn!(
-                "Warning: Foreign method not found {:?}. Did you forget to register it with the builder?",
-                key
-            );
+            match userdata.strict_bindings {
+                // Wren fails with a runtime error when the method isn't found,
+                // which will carry this as its foreign error.
+                Some(true) => Self::record_missing_binding(
+                    userdata,
+                    WrenError::BindingNotFound {
+                        module: key.module,
+                        class: key.class,
+                        sig: Some(key.sig),
+                    },
+                ),
+                Some(false) => {}
+                None => warn!(
+                    "Warning: Foreign method not found {:?}. Did you forget to register it with the builder?",
+                    key
+                ),
+            }
         }
 
         method
     }
 
+    /// Record a missing binding on the error queue, to be returned
+    /// as the foreign error of the current interpret.
+    ///
+    /// Only the first missing binding is kept, since a runtime error
+    /// can only carry one foreign error.
+    fn record_missing_binding(userdata: &UserData, err: WrenError) {
+        let mut errors = userdata.errors.borrow_mut();
+
+        if !errors.iter().any(|err| matches!(err, WrenVmError::Foreign(_))) {
+            errors.push(WrenVmError::Foreign(ForeignError::new(err)));
+        }
+    }
+
     /// Retrieve the module and class name of a registered foreign class.
     pub fn get_class_key<T: 'static>(&self) -> Option<&ForeignClassKey> {
         self.reverse.get(&TypeId::of::<T>())
     }
 }
 
+/// Allocator bound to foreign classes that aren't registered, when strict bindings
+/// are enabled. Aborts the fiber instead of constructing an instance.
+extern "C" fn missing_class_allocate(vm: *mut bindings::WrenVM) {
+    let message = CString::new("Foreign class is not registered").unwrap();
+
+    unsafe {
+        bindings::wrenEnsureSlots(vm, 1);
+        bindings::wrenSetSlotString(vm, 0, message.as_ptr());
+        bindings::wrenAbortFiber(vm, 0);
+    }
+}
+
 impl Default for ForeignBindings {
     fn default() -> Self {
         Self::new()
@@ -40,7 +40,8 @@
 //! ```
 //!
 //! **Important:** If the owned handle outlives the VM, as in the VM is dropped before the handle is dropped and
-//! released, the program will exit in debug mode and do nothing in release mode. A proper panic is to-be-implemented.
+//! released, the handle can't be released and an error is printed. With the `handle-backtrace` feature, each owned
+//! handle records where it was created, and dropping it after the VM panics with that backtrace instead.
 //!
 //! ```no_run
 //! # use rust_wren::prelude::*;
@@ -50,7 +51,8 @@
 //! let mut handle = vm.context_result(|ctx| {
 //!     ctx.get_var("my_module", "myVariable")?.leak()
 //! }).unwrap();
-//! drop(vm); // <-- processes exit
+//! drop(vm);
+//! drop(handle); // <-- handle outlived the VM
 //! ```
 //!
 //! The borrowed and owned flavours for handles are:
@@ -59,6 +61,7 @@
 //! - [`FnSymbolRef`](struct.FnSymbol.html) - Borrowed handle to a compiled function signature.
 //! - [`WrenCallRef`](struct.WrenCallRef.html) - Borrowed call handle for calling methods in Wren.
 //! - [`WrenHandle`](struct.WrenHandle.html) - Owned handle to a variable that can be stored outside a context scope.
+//! - [`OwnedHandle`](struct.OwnedHandle.html) - Owned handle that is leaked automatically when received as a foreign method argument.
 //! - [`FnSymbol`](struct.FnSymbol.html) - Owned handle to a compiled function signature that can be stored outside a contex scope.
 //! - [`WrenCallHandle`](struct.WrenCallHandle.html) - Owned call handle that can be stored outside a context scope.
 //!
@@ -156,6 +159,10 @@
 //!
 //!     // The context has a helper function for creating a call reference.
 //!     let call_instance: WrenCallRef = ctx.make_call_ref("example", "obj", "sayInstance(_)").unwrap();
+//!
+//!     // Or for pairing a signature with a receiver that's already at hand.
+//!     let obj_receiver = ctx.get_var("example", "obj").unwrap();
+//!     let call_instance: WrenCallRef = ctx.make_call_on(obj_receiver, "sayInstance(_)").unwrap();
 //! });
 //! ```
 //!
@@ -186,8 +193,9 @@
 use crate::{
     bindings,
     errors::{WrenError, WrenResult},
-    value::{FromWren, ToWren},
-    vm::WrenContext,
+    types::WrenType,
+    value::{FromWren, ToWren, Truthy},
+    vm::{enter_vm, WrenContext},
 };
 use regex::Regex;
 use std::{
@@ -196,6 +204,7 @@ use std::{
     fmt,
     marker::PhantomData,
     mem,
+    ops::Deref,
     ptr::NonNull,
     rc::Rc,
     sync::{mpsc::Sender, Arc},
@@ -245,7 +254,13 @@ impl<'wren> WrenRef<'wren> {
         //         Wren VM.
         mem::forget(self);
 
-        Ok(WrenHandle { handle, destructors })
+        Ok(WrenHandle::new(handle, destructors))
+    }
+
+    /// Retrieve the raw underlying pointer.
+    #[inline(always)]
+    pub(crate) unsafe fn raw_ptr(&self) -> NonNull<bindings::WrenHandle> {
+        NonNull::new_unchecked(self.handle)
     }
 }
 
@@ -270,6 +285,7 @@ impl<'wren> FromWren<'wren> for WrenRef<'wren> {
     fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
         // TODO: Null check
         let handle = unsafe { bindings::wrenGetSlotHandle(ctx.vm_ptr(), slot_num).as_mut().unwrap() };
+        ctx.record_handle();
         let destructors = ctx.destructor_sender();
         Ok(WrenRef::new(handle, destructors))
     }
@@ -321,6 +337,7 @@ impl<'wren> FnSymbolRef<'wren> {
                 .as_mut()
                 .unwrap()
         };
+        ctx.record_handle();
         let destructors = ctx.destructor_sender();
 
         Ok(FnSymbolRef {
@@ -431,6 +448,33 @@ impl<'wren> WrenCallRef<'wren> {
         wren_call::<A, R>(ctx, receiver, func, args)
     }
 
+    /// Calls the method, distinguishing a call that returned a value
+    /// from a call that aborted its fiber.
+    ///
+    /// A runtime error inside Wren is returned as [`CallOutcome::Aborted`](enum.CallOutcome.html#variant.Aborted)
+    /// instead of an `Err`, so schedulers can handle failing scripts
+    /// separately from errors in the embedding itself.
+    ///
+    /// Wren reports a call that yields or transfers out of its fiber the same
+    /// as a return, with the yielded value as the result. Check
+    /// [`WrenFiber::is_done`](../fiber/struct.WrenFiber.html#method.is_done)
+    /// to tell them apart.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handles are null, compilation fails, or the
+    /// returned value can't be converted to `R`.
+    pub fn call_full<'ctx, A, R>(&self, ctx: &'ctx mut WrenContext, args: A) -> WrenResult<CallOutcome<R::Output>>
+    where
+        A: ToWren,
+        R: FromWren<'wren>,
+    {
+        let receiver = unsafe { self.receiver.handle.as_mut().ok_or(WrenError::NullPtr)? };
+        let func = unsafe { self.func.handle.handle.as_mut().ok_or(WrenError::NullPtr)? };
+
+        wren_call_full::<A, R>(ctx, receiver, func, args)
+    }
+
     pub fn leak(self) -> WrenResult<WrenCallHandle> {
         let WrenCallRef { receiver, func } = self;
 
@@ -445,12 +489,15 @@ impl<'wren> WrenCallRef<'wren> {
 /// Owned handle to a variable stored in Wren.
 ///
 /// The handle does not implement [`FromWren`](../value/trait.FromWren.html). To accept a handle
-/// in a foreign method, use [`WrenRef`](struct.WrenRef.html) and explicitly leak it. This
-/// is a deliberate design choice so the library user is aware they take responsibility
-/// for dropping the handle before dropping the VM.
+/// in a foreign method, use [`WrenRef`](struct.WrenRef.html) and explicitly leak it, or declare
+/// the argument as an [`OwnedHandle`](struct.OwnedHandle.html). This is a deliberate design choice
+/// so the library user is aware they take responsibility for dropping the handle before dropping the VM.
 pub struct WrenHandle {
     handle: *mut bindings::WrenHandle,
     destructors: Sender<*mut bindings::WrenHandle>,
+    /// Where the handle was created, reported when it outlives the VM.
+    #[cfg(feature = "handle-backtrace")]
+    created: backtrace::Backtrace,
 }
 
 /// Our `WrenHandle` wrapper is designed to be only useful with the VM they belong to. The user can't use
@@ -467,7 +514,17 @@ impl WrenHandle {
         handle: *mut bindings::WrenHandle,
         destructors: Sender<*mut bindings::WrenHandle>,
     ) -> Self {
-        WrenHandle { handle, destructors }
+        WrenHandle::new(handle, destructors)
+    }
+
+    fn new(handle: *mut bindings::WrenHandle, destructors: Sender<*mut bindings::WrenHandle>) -> Self {
+        WrenHandle {
+            handle,
+            destructors,
+            // Symbols are only resolved when the backtrace is reported.
+            #[cfg(feature = "handle-backtrace")]
+            created: backtrace::Backtrace::new_unresolved(),
+        }
     }
 
     /// Retrieve the raw underlying pointer.
@@ -476,6 +533,49 @@ impl WrenHandle {
         // FIXME: WrenHandle internally must be NonNull to begin with
         NonNull::new_unchecked(self.handle)
     }
+
+    /// Checks whether both are the same handle.
+    ///
+    /// This is a cheap comparison of the handle pointers, which doesn't involve
+    /// the VM. Wren creates a new handle each time a value is retrieved, so two
+    /// handles separately retrieved for the same object are not the same handle.
+    /// Handles shared via `Rc` or `Arc`, on the other hand, are.
+    ///
+    /// To compare the values the handles point to, see [`equals`](#method.equals).
+    #[inline]
+    pub fn ptr_eq(&self, other: &WrenHandle) -> bool {
+        std::ptr::eq(self.handle, other.handle)
+    }
+
+    /// Compares the values the handles point to, using Wren's `==` operator.
+    ///
+    /// Numbers, strings, ranges and other value types are compared by value.
+    /// Objects are compared by identity, unless their class overrides `==`.
+    /// The result of an overridden `==` is converted to a bool by its truthiness.
+    ///
+    /// # Errors
+    ///
+    /// Returns a runtime error if the `==` operator aborts the fiber.
+    pub fn equals(&self, ctx: &mut WrenContext, other: &WrenHandle) -> WrenResult<bool> {
+        let mut func = ctx.cached_symbol("==(_)")?;
+        let mut receiver = unsafe { self.raw_ptr() };
+
+        unsafe { wren_call::<_, Truthy>(ctx, receiver.as_mut(), func.as_mut(), other) }.map(bool::from)
+    }
+
+    /// Retrieve the type of the value the handle points to.
+    ///
+    /// The handle is placed in a scratch slot past the caller's slots,
+    /// so values already in slots are left untouched. The same scratch
+    /// slot is reused for the rest of the context.
+    pub fn slot_type(&self, ctx: &WrenContext) -> WrenType {
+        let scratch = ctx.scratch_slot();
+
+        unsafe {
+            bindings::wrenSetSlotHandle(ctx.vm_ptr(), scratch, self.handle);
+            bindings::wrenGetSlotType(ctx.vm_ptr(), scratch).into()
+        }
+    }
 }
 
 impl fmt::Debug for WrenHandle {
@@ -487,9 +587,20 @@ impl fmt::Debug for WrenHandle {
 impl Drop for WrenHandle {
     fn drop(&mut self) {
         log::trace!("Dropping {:?}", self.handle);
-        self.destructors
-            .send(self.handle)
-            .unwrap_or_else(|err| eprintln!("{}", err));
+        if let Err(err) = self.destructors.send(self.handle) {
+            // The receiver is dropped along with the VM.
+            #[cfg(feature = "handle-backtrace")]
+            if !std::thread::panicking() {
+                let mut created = mem::replace(&mut self.created, backtrace::Backtrace::new_unresolved());
+                created.resolve();
+                panic!(
+                    "Wren handle {:?} outlived its VM. It was created at:\n{:?}",
+                    self.handle, created
+                );
+            }
+
+            eprintln!("{}", err);
+        }
     }
 }
 
@@ -527,6 +638,74 @@ impl ToWren for Arc<WrenHandle> {
     }
 }
 
+/// Owned handle that is leaked automatically when retrieved from a slot.
+///
+/// Intended for foreign method arguments that need to be stored beyond the
+/// call, like callbacks. The distinct type makes it explicit that the method
+/// takes responsibility for dropping the handle before the VM is dropped.
+///
+/// ```
+/// # use rust_wren::prelude::*;
+/// use rust_wren::handle::OwnedHandle;
+///
+/// #[wren_class]
+/// struct Events {
+///     callbacks: Vec<OwnedHandle>,
+/// }
+///
+/// #[wren_methods]
+/// impl Events {
+///     #[construct]
+///     fn new() -> Self {
+///         Self { callbacks: vec![] }
+///     }
+///
+///     #[method(name = onEvent)]
+///     fn on_event(&mut self, callback: OwnedHandle) {
+///         self.callbacks.push(callback);
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct OwnedHandle(WrenHandle);
+
+impl OwnedHandle {
+    /// Unwrap the inner [`WrenHandle`](struct.WrenHandle.html).
+    pub fn into_inner(self) -> WrenHandle {
+        self.0
+    }
+}
+
+impl Deref for OwnedHandle {
+    type Target = WrenHandle;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'wren> FromWren<'wren> for OwnedHandle {
+    type Output = Self;
+
+    fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        WrenRef::get_slot(ctx, slot_num)?.leak().map(OwnedHandle)
+    }
+}
+
+impl ToWren for OwnedHandle {
+    #[inline]
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        ToWren::put(self.0, ctx, slot)
+    }
+}
+
+impl ToWren for &OwnedHandle {
+    #[inline]
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        ToWren::put(&self.0, ctx, slot)
+    }
+}
+
 /// Owned handle to a compiled function signature stored in Wren.
 ///
 /// Create by leaking a [`FnSymbolRef`](struct.FnSymbolRef.html).
@@ -534,6 +713,14 @@ pub struct FnSymbol {
     handle: WrenHandle,
 }
 
+impl FnSymbol {
+    /// Retrieve the raw underlying pointer.
+    #[inline(always)]
+    pub(crate) unsafe fn raw_ptr(&self) -> NonNull<bindings::WrenHandle> {
+        self.handle.raw_ptr()
+    }
+}
+
 /// Owned call handle for calling methods in Wren.
 ///
 /// Combines a receiver variable and function symbol for convenience.
@@ -555,10 +742,58 @@ impl WrenCallHandle {
 
         wren_call::<A, R>(ctx, receiver, func, args)
     }
+
+    /// Calls the method, distinguishing a call that returned a value
+    /// from a call that aborted its fiber.
+    ///
+    /// See [`WrenCallRef::call_full`](struct.WrenCallRef.html#method.call_full).
+    pub fn call_full<'wren, 'ctx, A, R>(
+        &self,
+        ctx: &'ctx mut WrenContext,
+        args: A,
+    ) -> WrenResult<CallOutcome<R::Output>>
+    where
+        A: ToWren,
+        R: FromWren<'wren>,
+    {
+        let receiver = unsafe { self.receiver.handle.as_mut().ok_or(WrenError::NullPtr)? };
+        let func = unsafe { self.func.handle.handle.as_mut().ok_or(WrenError::NullPtr)? };
+
+        wren_call_full::<A, R>(ctx, receiver, func, args)
+    }
+}
+
+/// How a call into Wren finished.
+#[derive(Debug)]
+pub enum CallOutcome<T> {
+    /// The call completed and returned a value.
+    Returned(T),
+    /// The fiber was aborted with a runtime error.
+    Aborted(WrenError),
+}
+
+impl<T> CallOutcome<T> {
+    #[inline]
+    pub fn is_returned(&self) -> bool {
+        matches!(self, CallOutcome::Returned(_))
+    }
+
+    #[inline]
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, CallOutcome::Aborted(_))
+    }
+
+    /// Convert into a result, treating an aborted fiber as an error.
+    pub fn into_result(self) -> WrenResult<T> {
+        match self {
+            CallOutcome::Returned(value) => Ok(value),
+            CallOutcome::Aborted(err) => Err(err),
+        }
+    }
 }
 
 /// Perform Wren function call.
-fn wren_call<'wren, 'ctx, A, R>(
+pub(crate) fn wren_call<'wren, 'ctx, A, R>(
     ctx: &'ctx mut WrenContext,
     receiver: &mut bindings::WrenHandle,
     func: &mut bindings::WrenHandle,
@@ -568,22 +803,45 @@ where
     A: ToWren,
     R: FromWren<'wren>,
 {
-    // Receiver and arguments.
-    ctx.ensure_slots(1 + args.size_hint());
-
-    // FIXME: WrenHandle is moved via ToWren.
-    //        It shouldn't be clone because that would require us to
-    //        wrap it `Rc<T>` and introduce even more indirection.
-    //        Create `WrenHandle::clone(ctx)`.
-    unsafe {
-        bindings::wrenSetSlotHandle(ctx.vm_ptr(), 0, receiver);
-    }
+    // The slots are filled within the call, so a call made from within a
+    // foreign method fails before it replaces the foreign method's slots.
+    let vm = ctx.vm_ptr();
+    let result_id: bindings::WrenInterpretResult = enter_vm(vm, || {
+        // Receiver and arguments.
+        ctx.ensure_slots(1 + args.size_hint());
+
+        // FIXME: WrenHandle is moved via ToWren.
+        //        It shouldn't be clone because that would require us to
+        //        wrap it `Rc<T>` and introduce even more indirection.
+        //        Create `WrenHandle::clone(ctx)`.
+        unsafe {
+            bindings::wrenSetSlotHandle(vm, 0, receiver);
+        }
 
-    args.put(ctx, 1);
+        args.put(ctx, 1);
 
-    let result_id: bindings::WrenInterpretResult = unsafe { bindings::wrenCall(ctx.vm_ptr(), func) };
+        unsafe { bindings::wrenCall(vm, func) }
+    })?;
     ctx.take_errors(result_id)?;
 
     // Wren places the result in slot 0 if result was success.
     R::get_slot(ctx, 0)
 }
+
+/// Perform Wren function call, returning a runtime error as an outcome instead of an error.
+pub(crate) fn wren_call_full<'wren, 'ctx, A, R>(
+    ctx: &'ctx mut WrenContext,
+    receiver: &mut bindings::WrenHandle,
+    func: &mut bindings::WrenHandle,
+    args: A,
+) -> WrenResult<CallOutcome<R::Output>>
+where
+    A: ToWren,
+    R: FromWren<'wren>,
+{
+    match wren_call::<A, R>(ctx, receiver, func, args) {
+        Ok(value) => Ok(CallOutcome::Returned(value)),
+        Err(err) if err.is_runtime_error() => Ok(CallOutcome::Aborted(err)),
+        Err(err) => Err(err),
+    }
+}
@@ -32,13 +32,19 @@ extern crate lazy_static;
 #[doc(hidden)]
 pub mod bindings;
 
+pub mod allocator;
+pub mod callback;
 pub mod class;
 mod errors;
+pub mod fiber;
 pub mod foreign;
 pub mod handle;
 pub mod list;
 pub mod module;
+pub mod output;
 mod runtime;
+pub mod sequence;
+pub mod string;
 pub mod types;
 pub mod value;
 mod vm;
@@ -54,12 +60,13 @@ pub mod troubleshoot {
 
 pub mod prelude {
     pub use crate::class::{WrenCell, WrenForeignClass};
+    pub use crate::fiber::WrenFiber;
     pub use crate::handle::WrenRef;
     pub use crate::list::WrenList;
     pub use crate::module::{ModuleLoader, ModuleResolver};
     pub use crate::value::{FromWren, ToWren};
     pub use crate::vm::{WrenBuilder, WrenVm};
-    pub use rust_wren_derive::{foreign_error, wren_class, wren_methods};
+    pub use rust_wren_derive::{foreign_error, register_classes, wren_class, wren_methods, wren_module, WrenNumber};
 }
 
 /// Modules that are needed by generated code, but not meant to be part
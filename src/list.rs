@@ -1,12 +1,12 @@
 use crate::{
     bindings,
     errors::{WrenError, WrenResult},
-    handle::WrenHandle,
+    handle::{wren_call, WrenHandle, WrenRef},
     types::WrenType,
     value::{FromWren, ToWren},
     vm::WrenContext,
 };
-use std::{fmt, os::raw::c_int};
+use std::{cmp::min, fmt, mem::MaybeUninit, os::raw::c_int, ptr};
 
 /// Handle to a list in Wren.
 ///
@@ -26,6 +26,7 @@ impl WrenList {
         unsafe {
             bindings::wrenSetSlotNewList(ctx.vm_ptr(), 0);
             let handle_ptr = bindings::wrenGetSlotHandle(ctx.vm_ptr(), 0);
+            ctx.record_handle();
             let handle = WrenHandle::from_raw(handle_ptr, destructor_queue);
             WrenList(handle)
         }
@@ -54,6 +55,7 @@ impl WrenList {
         unsafe {
             bindings::wrenSetSlotNewList(ctx.vm_ptr(), 0);
             let handle_ptr = bindings::wrenGetSlotHandle(ctx.vm_ptr(), 0);
+            ctx.record_handle();
             let handle = WrenHandle::from_raw(handle_ptr, destructor_queue);
 
             for el in data.iter() {
@@ -65,6 +67,37 @@ impl WrenList {
         }
     }
 
+    /// Create a new list in Wren, passing references to the elements
+    /// of the given slice into it.
+    ///
+    /// Unlike [`from_slice`](#method.from_slice), elements are not cloned
+    /// first, which avoids an extra allocation per element for types like
+    /// `String`.
+    ///
+    /// Returns a handle to the created list.
+    pub fn from_slice_ref<T>(ctx: &mut WrenContext, data: &[T]) -> WrenResult<Self>
+    where
+        for<'a> &'a T: ToWren,
+    {
+        // Slot for list receiver and item
+        ctx.ensure_slots(2);
+        let destructor_queue = ctx.destructor_sender();
+
+        unsafe {
+            bindings::wrenSetSlotNewList(ctx.vm_ptr(), 0);
+            let handle_ptr = bindings::wrenGetSlotHandle(ctx.vm_ptr(), 0);
+            ctx.record_handle();
+            let handle = WrenHandle::from_raw(handle_ptr, destructor_queue);
+
+            for el in data.iter() {
+                ToWren::put(el, ctx, 1);
+                bindings::wrenInsertInList(ctx.vm_ptr(), 0, -1, 1);
+            }
+
+            Ok(WrenList::from_handle_unchecked(handle))
+        }
+    }
+
     /// Create a new list in Wren, copying the contents of the
     /// given vector into it.
     ///
@@ -77,6 +110,7 @@ impl WrenList {
         unsafe {
             bindings::wrenSetSlotNewList(ctx.vm_ptr(), 0);
             let handle_ptr = bindings::wrenGetSlotHandle(ctx.vm_ptr(), 0);
+            ctx.record_handle();
             let handle = WrenHandle::from_raw(handle_ptr, destructor_queue);
 
             for el in data.into_iter() {
@@ -114,6 +148,9 @@ impl WrenList {
 
     /// Get length of list without ensuring the number of slots.
     ///
+    /// The list is left in slot 0, so element accessors can
+    /// follow without setting the handle again.
+    ///
     /// # Safety
     ///
     /// If there are not enough slots, the value will be writing the length outside
@@ -128,6 +165,29 @@ impl WrenList {
         self.len(ctx) == 0
     }
 
+    /// Removes all elements from the list.
+    ///
+    /// Wren's API has no way to remove elements, so this calls the list's
+    /// `clear()` method. Because it calls into Wren, this can't be used while
+    /// Wren is calling a foreign method.
+    ///
+    /// An empty list is left as is, which also avoids Wren leaking memory
+    /// when clearing an empty list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the call into Wren fails.
+    pub fn clear(&mut self, ctx: &mut WrenContext) -> WrenResult<()> {
+        if self.is_empty(ctx) {
+            return Ok(());
+        }
+
+        let mut func = ctx.cached_symbol("clear()")?;
+        let mut receiver = unsafe { self.0.raw_ptr() };
+
+        unsafe { wren_call::<_, ()>(ctx, receiver.as_mut(), func.as_mut(), ()) }
+    }
+
     pub fn set<T: ToWren>(&mut self, ctx: &mut WrenContext, index: usize, item: T) {
         // Wren does not do bounds check
         if index >= self.len(ctx) {
@@ -144,7 +204,9 @@ impl WrenList {
         }
     }
 
-    // TODO: Result<Option<T::Output>> can be flattened by having an out-of-bounds WrenError variant
+    /// Retrieve the element at the given index, or `None` when the index is out of bounds.
+    ///
+    /// See [`try_get`](#method.try_get) for a variant that treats out of bounds as an error.
     pub fn get<'wren, T>(&self, ctx: &'wren mut WrenContext, index: usize) -> Result<Option<T::Output>, WrenError>
     where
         T: FromWren<'wren>,
@@ -164,6 +226,69 @@ impl WrenList {
         <Option<T> as FromWren>::get_slot(ctx, 1)
     }
 
+    /// Retrieve the element at the given index.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WrenError::IndexOutOfBounds`](../errors/enum.WrenError.html#variant.IndexOutOfBounds)
+    /// if the index is out of bounds, or `WrenError` if the element does not match the type of `T::Output`.
+    pub fn try_get<'wren, T>(&self, ctx: &'wren mut WrenContext, index: usize) -> WrenResult<T::Output>
+    where
+        T: FromWren<'wren>,
+    {
+        // Wren does not do bounds check
+        let len = self.len(ctx);
+        if index >= len {
+            return Err(WrenError::IndexOutOfBounds { index, len });
+        }
+
+        ctx.ensure_slots(2);
+
+        unsafe {
+            bindings::wrenSetSlotHandle(ctx.vm_ptr(), 0, self.0.raw_ptr().as_ptr());
+            bindings::wrenGetListElement(ctx.vm_ptr(), 0, index as c_int, 1);
+        }
+
+        <T as FromWren>::get_slot(ctx, 1)
+    }
+
+    /// Retrieve the elements at the given indices, in the order given.
+    ///
+    /// The list is set into a slot once for all reads, which is cheaper than
+    /// calling [`get`](#method.get) per index for scattered access. Like `get`,
+    /// an index that is out of bounds results in `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WrenError` if a requested element does not match the
+    /// type of `T::Output`.
+    pub fn get_many<'wren, T>(&self, ctx: &mut WrenContext, indices: &[usize]) -> WrenResult<Vec<Option<T::Output>>>
+    where
+        T: FromWren<'wren>,
+    {
+        ctx.ensure_slots(2);
+
+        // Leaves the list in slot 0 for the duration of the read loop.
+        let size = unsafe { self.len_unchecked(ctx) };
+        let mut result = Vec::with_capacity(indices.len());
+
+        for &index in indices {
+            // Wren does not do bounds check
+            if index >= size {
+                result.push(None);
+                continue;
+            }
+
+            unsafe {
+                bindings::wrenGetListElement(ctx.vm_ptr(), 0, index as c_int, 1);
+            }
+
+            result.push(<Option<T> as FromWren>::get_slot(ctx, 1)?);
+        }
+
+        Ok(result)
+    }
+
     /// Copies the contents of the list into a new `Vec`.
     ///
     /// # Errors
@@ -174,14 +299,14 @@ impl WrenList {
     where
         T: FromWren<'wren>,
     {
-        let mut result = vec![];
-
         ctx.ensure_slots(2);
+
+        // Leaves the list in slot 0 for the duration of the copy loop.
         let size = unsafe { self.len_unchecked(ctx) };
+        let mut result = Vec::with_capacity(size);
 
         for index in 0..size {
             unsafe {
-                bindings::wrenSetSlotHandle(ctx.vm_ptr(), 0, self.0.raw_ptr().as_ptr());
                 bindings::wrenGetListElement(ctx.vm_ptr(), 0, index as c_int, 1);
             }
 
@@ -192,6 +317,86 @@ impl WrenList {
         Ok(result)
     }
 
+    /// Compares the contents of the list to a slice, element by element,
+    /// without copying the list into a `Vec`.
+    ///
+    /// Lists of different lengths are never equal, and the comparison stops
+    /// at the first element that differs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WrenError` if an element read before the first difference
+    /// does not match the type of `T::Output`.
+    pub fn eq_slice<'wren, T>(&self, ctx: &mut WrenContext, other: &[T]) -> WrenResult<bool>
+    where
+        T: FromWren<'wren>,
+        T::Output: PartialEq<T>,
+    {
+        ctx.ensure_slots(2);
+
+        // Leaves the list in slot 0 for the duration of the loop.
+        let size = unsafe { self.len_unchecked(ctx) };
+        if size != other.len() {
+            return Ok(false);
+        }
+
+        for (index, expected) in other.iter().enumerate() {
+            unsafe {
+                bindings::wrenGetListElement(ctx.vm_ptr(), 0, index as c_int, 1);
+            }
+
+            if <T as FromWren>::get_slot(ctx, 1)? != *expected {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Calls the closure with each number in the list, in order, without
+    /// copying the list into a `Vec`.
+    ///
+    /// Wren stores list elements as tagged values, and doesn't expose its
+    /// storage through the embedding API, so a numeric list can't be borrowed
+    /// as a `&[f64]`. Each element is still read through a slot, but nothing
+    /// is allocated, which makes this cheaper than [`to_vec`](#method.to_vec)
+    /// for scanning large lists.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first element that is not a number, and returns a `WrenError`.
+    pub fn for_each_f64<F>(&self, ctx: &mut WrenContext, mut func: F) -> WrenResult<()>
+    where
+        F: FnMut(f64),
+    {
+        ctx.ensure_slots(2);
+
+        // Leaves the list in slot 0 for the duration of the loop.
+        let size = unsafe { self.len_unchecked(ctx) };
+
+        for index in 0..size {
+            unsafe {
+                bindings::wrenGetListElement(ctx.vm_ptr(), 0, index as c_int, 1);
+            }
+
+            func(<f64 as FromWren>::get_slot(ctx, 1)?);
+        }
+
+        Ok(())
+    }
+
+    /// Creates a handle to each element of the list, regardless of its type.
+    ///
+    /// Useful for lists holding mixed values, where each element can be
+    /// inspected and converted individually.
+    ///
+    /// The handles are scoped to the context, and each is released when
+    /// dropped. Keeping many of them around keeps their elements alive,
+    /// so prefer [`to_vec`](#method.to_vec) for lists of a single type.
+    pub fn to_handles<'wren>(&self, ctx: &mut WrenContext<'wren>) -> WrenResult<Vec<WrenRef<'wren>>> {
+        self.to_vec::<WrenRef<'wren>>(ctx)
+    }
+
     /// Clones the contents of the list to the given buffer.
     ///
     /// Returns the number of elements copied.
@@ -205,11 +410,12 @@ impl WrenList {
         T: FromWren<'wren>,
     {
         ctx.ensure_slots(2);
+
+        // Leaves the list in slot 0 for the duration of the copy loop.
         let list_size = unsafe { self.len_unchecked(ctx) };
 
         for (index, item) in buf.iter_mut().enumerate().take(list_size) {
             unsafe {
-                bindings::wrenSetSlotHandle(ctx.vm_ptr(), 0, self.0.raw_ptr().as_ptr());
                 bindings::wrenGetListElement(ctx.vm_ptr(), 0, index as c_int, 1);
             }
 
@@ -221,6 +427,62 @@ impl WrenList {
         Ok(::std::cmp::min(list_size, buf.len()))
     }
 
+    /// Copies the contents of the list into the given uninitialised buffer.
+    ///
+    /// Writes exactly `min(len, buf.len())` elements to the front of the
+    /// buffer, and returns that count. Only the elements within the returned
+    /// count are initialised.
+    ///
+    /// Unlike [`clone_to`](#method.clone_to), the buffer doesn't need to be
+    /// filled with throwaway values beforehand.
+    ///
+    /// # Errors
+    ///
+    /// Will abort the copy and return an error if an element in the list
+    /// cannot be converted to type `T`. Elements already written are
+    /// dropped, leaving the whole buffer uninitialised.
+    pub fn fill_uninit<'wren, T>(&self, ctx: &mut WrenContext, buf: &mut [MaybeUninit<T::Output>]) -> WrenResult<usize>
+    where
+        T: FromWren<'wren>,
+    {
+        ctx.ensure_slots(2);
+
+        // Leaves the list in slot 0 for the duration of the copy loop.
+        let count = min(unsafe { self.len_unchecked(ctx) }, buf.len());
+
+        let mut written = 0;
+        let mut result = Ok(());
+
+        for item in buf.iter_mut().take(count) {
+            unsafe {
+                bindings::wrenGetListElement(ctx.vm_ptr(), 0, written as c_int, 1);
+            }
+
+            match <T as FromWren>::get_slot(ctx, 1) {
+                Ok(element) => {
+                    *item = MaybeUninit::new(element);
+                    written += 1;
+                }
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+
+        if let Err(err) = result {
+            // Drop what was written, so the caller isn't left
+            // with partially initialised values to track.
+            for item in buf[..written].iter_mut() {
+                unsafe { ptr::drop_in_place(item.as_mut_ptr()) };
+            }
+
+            return Err(err);
+        }
+
+        Ok(count)
+    }
+
     // fn clone_from<T>(&self)
 
     // TODO: There is no remove element in Wren API
@@ -242,6 +504,7 @@ impl<'wren> FromWren<'wren> for WrenList {
             if list_handle.is_null() {
                 return Err(WrenError::NullPtr);
             }
+            ctx.record_handle();
 
             let destructors = ctx.destructor_sender();
 
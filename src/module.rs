@@ -1,5 +1,6 @@
 //! Module loader and resolver.
 use std::{
+    collections::HashMap,
     env,
     path::{Path, PathBuf},
 };
@@ -8,6 +9,19 @@ pub trait ModuleResolver {
     /// If the module name cannot be resolved, return `None` to
     /// abort the fiber.
     fn resolve(&mut self, importer: &str, module: &str) -> Option<String>;
+
+    /// Resolves the module name, or rejects the import with a reason.
+    ///
+    /// A rejected import aborts the fiber, and the reason is reported as a
+    /// [`WrenError::ImportRejected`](../errors/enum.WrenError.html#variant.ImportRejected)
+    /// in the foreign error of the runtime error. A module that can't be
+    /// resolved, `Ok(None)`, is reported by Wren as not found. Override to
+    /// explain why an import isn't allowed, ie. in a sandbox.
+    ///
+    /// Defaults to [`resolve`](#tymethod.resolve), which never rejects an import.
+    fn try_resolve(&mut self, importer: &str, module: &str) -> Result<Option<String>, String> {
+        Ok(self.resolve(importer, module))
+    }
 }
 
 pub trait ModuleLoader {
@@ -39,6 +53,63 @@ impl ModuleResolver for UnitModuleResolver {
     }
 }
 
+/// Module resolver that resolves relative imports against
+/// the importing module.
+///
+/// Names starting with `./` or `../` are joined to the directory of the
+/// importer, and normalised into a canonical module path using `/` as
+/// separator. Other names are considered absolute, and are returned as is.
+///
+/// ```
+/// # use rust_wren::{prelude::*, module::PathModuleResolver};
+/// let mut resolver = PathModuleResolver::new();
+/// assert_eq!(resolver.resolve("game/player", "./input"), Some("game/input".to_string()));
+/// assert_eq!(resolver.resolve("game/player", "../util"), Some("util".to_string()));
+/// assert_eq!(resolver.resolve("game/player", "math"), Some("math".to_string()));
+/// ```
+#[derive(Debug, Default)]
+pub struct PathModuleResolver;
+
+impl PathModuleResolver {
+    pub fn new() -> Self {
+        PathModuleResolver
+    }
+
+    fn is_relative(name: &str) -> bool {
+        name.starts_with("./") || name.starts_with("../")
+    }
+}
+
+impl ModuleResolver for PathModuleResolver {
+    fn resolve(&mut self, importer: &str, name: &str) -> Option<String> {
+        log::debug!("Resolve module: importer={} name={}", importer, name);
+
+        if !Self::is_relative(name) {
+            return Some(name.to_string());
+        }
+
+        // Directory of the importer is everything up to its last segment.
+        let mut segments: Vec<&str> = importer.split('/').collect();
+        segments.pop();
+
+        for segment in name.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => match segments.last() {
+                    // Can't go above the root, so leading parent segments are kept.
+                    Some(&"..") | None => segments.push(".."),
+                    Some(_) => {
+                        segments.pop();
+                    }
+                },
+                _ => segments.push(segment),
+            }
+        }
+
+        Some(segments.join("/"))
+    }
+}
+
 /// Simple module loader that reads source code from files.
 ///
 /// Module import names are used as file paths relative to
@@ -95,3 +166,63 @@ impl ModuleLoader for FileModuleLoader {
 
     fn on_complete(&mut self, _name: &str) {}
 }
+
+/// Module loader that serves source code from memory.
+///
+/// Useful for bundling scripts into the binary, without
+/// touching the file system.
+///
+/// ```
+/// # use rust_wren::{prelude::*, module::InMemoryModuleLoader};
+/// let mut loader = InMemoryModuleLoader::new();
+/// loader.add_module("math", "class Math {}");
+///
+/// let mut vm = WrenBuilder::new().with_module_loader(loader).build();
+/// vm.interpret("main", r#"import "math" for Math"#).expect("Interpret failed");
+/// ```
+#[derive(Debug, Default)]
+pub struct InMemoryModuleLoader {
+    modules: HashMap<String, String>,
+}
+
+impl InMemoryModuleLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the source code of a module under the given name.
+    ///
+    /// Replaces any source previously registered under the same name.
+    pub fn add_module<N, S>(&mut self, name: N, source: S)
+    where
+        N: Into<String>,
+        S: Into<String>,
+    {
+        self.modules.insert(name.into(), source.into());
+    }
+
+    /// Builder style variant of [`add_module`](#method.add_module).
+    pub fn with_module<N, S>(mut self, name: N, source: S) -> Self
+    where
+        N: Into<String>,
+        S: Into<String>,
+    {
+        self.add_module(name, source);
+        self
+    }
+}
+
+impl ModuleLoader for InMemoryModuleLoader {
+    fn load(&mut self, name: &str) -> Option<String> {
+        log::debug!("Importing from memory: {}", name);
+
+        let source = self.modules.get(name).cloned();
+        if source.is_none() {
+            log::error!("Module not found in memory: {}", name);
+        }
+
+        source
+    }
+
+    fn on_complete(&mut self, _name: &str) {}
+}
@@ -0,0 +1,113 @@
+//! Output channels for structured logging from Wren.
+//!
+//! When any channel is set on the builder, a class `Out` is declared in
+//! the module `rust_wren/out`, with static methods `log(_)`, `warn(_)` and
+//! `error(_)`. Each sends the message to its own Rust function, leaving
+//! `System.print()` to the write function.
+//!
+//! ```
+//! # use rust_wren::prelude::*;
+//! # use std::{cell::RefCell, rc::Rc};
+//! let warnings = Rc::new(RefCell::new(Vec::new()));
+//! let sink = warnings.clone();
+//!
+//! let mut vm = WrenBuilder::new()
+//!     .with_warn_fn(move |msg| sink.borrow_mut().push(msg.to_owned()))
+//!     .build();
+//!
+//! vm.interpret("example", r#"
+//! import "rust_wren/out" for Out
+//! Out.warn("Low on fuel")
+//! "#).unwrap();
+//!
+//! assert_eq!(*warnings.borrow(), vec!["Low on fuel"]);
+//! ```
+use crate::{
+    bindings, errors::ForeignError, foreign::ForeignMethod, value::ToWren, ModuleBuilder, WrenContext, WrenVm,
+};
+
+/// Well-known module containing the `Out` class.
+pub const OUT_MODULE: &str = "rust_wren/out";
+
+/// Name of the class in Wren.
+const OUT_CLASS: &str = "Out";
+
+/// Messages are converted to strings in Wren, so any value can be logged.
+const OUT_DECLARATION: &str = r#"class Out {
+    static log(message) { log_(message.toString) }
+    static warn(message) { warn_(message.toString) }
+    static error(message) { error_(message.toString) }
+
+    foreign static log_(message)
+    foreign static warn_(message)
+    foreign static error_(message)
+}
+"#;
+
+/// Function receiving the messages of one channel.
+pub(crate) type OutputFn = Box<dyn Fn(&str)>;
+
+/// Functions receiving the messages of each channel.
+///
+/// A channel without a function is sent to the `log` crate instead.
+#[derive(Default)]
+pub(crate) struct OutputFns {
+    pub(crate) log: Option<OutputFn>,
+    pub(crate) warn: Option<OutputFn>,
+    pub(crate) error: Option<OutputFn>,
+}
+
+/// Registers the bindings of the `Out` class.
+pub(crate) fn register(module: &mut ModuleBuilder) {
+    for (sig, func) in [
+        ("log_(_)", out_log as extern "C" fn(*mut bindings::WrenVM)),
+        ("warn_(_)", out_warn),
+        ("error_(_)", out_error),
+    ] {
+        module.add_method_binding(
+            OUT_CLASS,
+            ForeignMethod {
+                is_static: true,
+                arity: 1,
+                sig: sig.to_owned(),
+                func,
+                doc: None,
+            },
+        );
+    }
+}
+
+/// Wren source declaring the `Out` class.
+pub(crate) fn wren_declaration() -> &'static str {
+    OUT_DECLARATION
+}
+
+/// Passes the message in slot 1 to the function selected from the user data,
+/// or to the fallback when the channel has no function.
+fn dispatch(vm: *mut bindings::WrenVM, select: fn(&OutputFns) -> Option<&OutputFn>, fallback: fn(&str)) {
+    let vm_ref: &mut bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+    let mut ctx = WrenContext::new(vm_ref);
+
+    let message = match ctx.get_slot::<&str>(1) {
+        Ok(message) => message,
+        Err(err) => return ForeignError::new(err).put(&mut ctx, 0),
+    };
+
+    let userdata = unsafe { WrenVm::get_user_data(vm) };
+    match userdata.and_then(|userdata| userdata.output.as_ref()).and_then(select) {
+        Some(func) => func(message),
+        None => fallback(message),
+    }
+}
+
+extern "C" fn out_log(vm: *mut bindings::WrenVM) {
+    dispatch(vm, |fns| fns.log.as_ref(), |msg| log::info!("{}", msg));
+}
+
+extern "C" fn out_warn(vm: *mut bindings::WrenVM) {
+    dispatch(vm, |fns| fns.warn.as_ref(), |msg| log::warn!("{}", msg));
+}
+
+extern "C" fn out_error(vm: *mut bindings::WrenVM) {
+    dispatch(vm, |fns| fns.error.as_ref(), |msg| log::error!("{}", msg));
+}
@@ -1,5 +1,10 @@
 /// Callback functions passed to WrenVM.
-use crate::{bindings, errors::WrenVmError, vm::WrenVm, ForeignError};
+use crate::{
+    bindings,
+    errors::{parse_error_token, WrenError, WrenVmError},
+    vm::{UserData, WrenVm},
+    ForeignError,
+};
 use smol_str::SmolStr;
 use std::{
     alloc::{alloc_zeroed, dealloc, realloc, Layout},
@@ -8,8 +13,20 @@ use std::{
     ptr,
 };
 
-pub extern "C" fn wren_reallocate(memory: *mut c_void, new_size: usize, _userdata: *mut c_void) -> *mut c_void {
+/// Heap allocation for Wren.
+///
+/// Wren passes the user data from the configuration, which holds the
+/// custom allocator when one was given to the builder.
+pub extern "C" fn wren_reallocate(memory: *mut c_void, new_size: usize, userdata: *mut c_void) -> *mut c_void {
     unsafe {
+        // Only the allocator field is borrowed, because the rest of the
+        // user data may be borrowed elsewhere while Wren allocates.
+        let allocator = if userdata.is_null() {
+            None
+        } else {
+            (*(userdata as *const UserData)).allocator.as_deref()
+        };
+
         if memory.is_null() {
             if new_size == 0 {
                 // Called by Wren when an empty list is cleared.
@@ -18,33 +35,42 @@ pub extern "C" fn wren_reallocate(memory: *mut c_void, new_size: usize, _userdat
                 ptr::null_mut()
             } else {
                 // Allocate
-                record_alloc(
-                    alloc_zeroed(Layout::from_size_align(new_size, 8).unwrap()) as *mut _,
-                    new_size,
-                    1,
-                )
+                let new_memory = match allocator {
+                    Some(allocator) => allocator.alloc(new_size),
+                    None => alloc_zeroed(Layout::from_size_align(new_size, 8).unwrap()) as *mut _,
+                };
+                record_alloc(new_memory, new_size, 1)
             }
         } else {
             // Existing memory
             if new_size == 0 {
                 // Deallocate
-                dealloc(memory as *mut _, Layout::from_size_align(0, 8).unwrap());
+                match allocator {
+                    Some(allocator) => allocator.dealloc(memory),
+                    None => dealloc(memory as *mut _, Layout::from_size_align(0, 8).unwrap()),
+                }
                 record_alloc(memory, 0, -1);
                 ptr::null_mut()
             } else {
                 // Reallocate
-                record_alloc(memory, 0, -1);
                 // Rust realloc returns a new address if ownsership of
                 // the block has changed, or null when ownsership cannot be taken.
-                record_alloc(
-                    realloc(
+                let new_memory = match allocator {
+                    Some(allocator) => allocator.realloc(memory, new_size),
+                    None => realloc(
                         memory as *mut _,
                         Layout::from_size_align(new_size, 8).unwrap(),
                         new_size,
-                    ) as *mut _,
-                    new_size,
-                    1,
-                )
+                    ) as *mut c_void,
+                };
+
+                // On failure the old block is left untouched, and still live.
+                if !new_memory.is_null() {
+                    record_alloc(memory, 0, -1);
+                    record_alloc(new_memory, new_size, 1);
+                }
+
+                new_memory
             }
         }
     }
@@ -83,10 +109,12 @@ pub extern "C" fn error_function(
             bindings::WrenErrorType_WREN_ERROR_COMPILE => {
                 let c_module = unsafe { CStr::from_ptr(module) };
                 let c_message = unsafe { CStr::from_ptr(message) };
+                let message = c_message.to_str().expect("Failed to convert message to UTF-8");
                 userdata.errors.borrow_mut().push(WrenVmError::Compile {
                     module: SmolStr::new(c_module.to_str().expect("Failed to convert module name to UTF-8")),
-                    message: String::from(c_message.to_str().expect("Failed to convert message to UTF-8")),
+                    message: String::from(message),
                     line,
+                    token: parse_error_token(message),
                 });
             }
             bindings::WrenErrorType_WREN_ERROR_STACK_TRACE => {
@@ -124,18 +152,53 @@ pub extern "C" fn resolve_module(
     log::trace!("Runtime: resolving module name");
 
     if let Some(userdata) = unsafe { WrenVm::get_user_data(vm) } {
-        let importer = unsafe { CStr::from_ptr(importer) };
-        let name = unsafe { CStr::from_ptr(name) };
-
-        if let Some(resolved) = userdata
-            .resolver
-            .as_mut()
-            .and_then(|resolver| resolver.resolve(importer.to_string_lossy().as_ref(), name.to_string_lossy().as_ref()))
-        {
+        let importer = unsafe { CStr::from_ptr(importer) }.to_string_lossy();
+        let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+        let key = (importer.into_owned(), name.into_owned());
+
+        let cached = userdata
+            .resolve_cache
+            .as_ref()
+            .and_then(|cache| cache.borrow().get(&key).cloned());
+
+        let resolved = cached.or_else(|| {
+            let resolved = match userdata.resolver.as_mut()?.try_resolve(&key.0, &key.1) {
+                Ok(resolved) => resolved?,
+                Err(reason) => {
+                    // Wren aborts the fiber, and the reason is reported along with its runtime error.
+                    let (importer, module) = key;
+                    userdata
+                        .errors
+                        .borrow_mut()
+                        .push(WrenVmError::Foreign(ForeignError::new(WrenError::ImportRejected {
+                            importer,
+                            module,
+                            reason,
+                        })));
+                    return None;
+                }
+            };
+
+            if let Some(cache) = &userdata.resolve_cache {
+                cache.borrow_mut().insert(key, resolved.clone());
+            }
+
+            Some(resolved)
+        });
+
+        if let Some(resolved) = resolved {
             match CString::new(resolved) {
                 Ok(c_resolved) => {
-                    // Wren takes ownership of the resolved name and deallocates it.
-                    c_resolved.into_raw()
+                    // Wren takes ownership of the resolved name and deallocates it
+                    // through `wren_reallocate`, so it must come from the same allocator.
+                    let bytes = c_resolved.as_bytes_with_nul();
+                    unsafe {
+                        let name = wren_reallocate(ptr::null_mut(), bytes.len(), bindings::wrenGetUserData(vm));
+                        if !name.is_null() {
+                            ptr::copy_nonoverlapping(bytes.as_ptr(), name as *mut u8, bytes.len());
+                        }
+                        name as *const c_char
+                    }
                 }
                 Err(err) => {
                     log::error!("Resolved module name contains a null byte: {}", err);
@@ -154,18 +217,29 @@ pub extern "C" fn resolve_module(
 #[no_mangle]
 pub extern "C" fn load_module(vm: *mut bindings::WrenVM, name: *const c_char) -> bindings::WrenLoadModuleResult {
     if let Some(userdata) = unsafe { WrenVm::get_user_data(vm) } {
-        if let Some(source) = userdata.loader.as_mut().and_then(|loader| {
-            let name = unsafe { CStr::from_ptr(name) };
-            loader.load(name.to_string_lossy().as_ref())
-        }) {
+        let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+        if let Some(source) = userdata.loader.as_mut().and_then(|loader| loader.load(name.as_ref())) {
             // Length in bytes, not chars or graphmemes.
             let source_len = source.len();
 
             // Wren takes ownership of the source code string, then
             // passes it back by calling `load_module_complete`.
-            let c_source = CString::new(source).unwrap();
+            let c_source = match CString::new(source) {
+                Ok(c_source) => c_source,
+                Err(err) => {
+                    // Reported to Wren as a module that can't be found.
+                    log::error!("Source of module '{}' contains a null byte: {}", name, err);
+                    return bindings::WrenLoadModuleResult {
+                        source: ptr::null_mut(),
+                        onComplete: None,
+                        userData: ptr::null_mut(),
+                    };
+                }
+            };
             let source = c_source.into_raw();
 
+            userdata.loaded_modules.insert(name.into_owned());
+
             // Bookkeeping for string allocation, as we are responsible
             // for safely deallocating this string when Wren is done compiling.
             unsafe {
@@ -218,7 +292,7 @@ pub extern "C" fn load_module_complete(
     }
 }
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "alloc-tracking"))]
 mod alloc_debug {
     use std::{collections::HashMap, sync::RwLock};
 
@@ -249,10 +323,16 @@ mod alloc_debug {
 #[inline]
 #[allow(unused_variables)]
 unsafe fn record_alloc(address: *mut c_void, size: usize, diff: i64) -> *mut c_void {
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "alloc-tracking"))]
     {
         use log::warn;
 
+        // Null is returned for failed and zero sized allocations, following
+        // malloc semantics, and freeing null does nothing. Neither is a live block.
+        if address.is_null() {
+            return address;
+        }
+
         let key = address as usize;
 
         if let Ok(mut allocs) = alloc_debug::ALLOCS.write() {
@@ -286,19 +366,19 @@ unsafe fn record_alloc(address: *mut c_void, size: usize, diff: i64) -> *mut c_v
     }
     // Pass the address through so allocation calls
     // can be wrapped in this function.
-    // In a release build this function will be inlined away.
+    // In a release build without `alloc-tracking` this function will be inlined away.
     address
 }
 
 /// Assert that all Wren's heap memory has been deallocated.
 ///
-/// Requires `debug_assertions`, otherwise does nothing.
+/// Requires `debug_assertions` or the `alloc-tracking` feature, otherwise does nothing.
 ///
 /// # Panic
 ///
 /// Panics when there are allocations left in the debug registry.
 pub fn assert_all_deallocated() {
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "alloc-tracking"))]
     {
         use log::{info, warn};
 
@@ -317,11 +397,36 @@ pub fn assert_all_deallocated() {
     }
 }
 
+/// Total size in bytes of the blocks currently in the allocation registry.
+///
+/// The registry is shared by all VMs in the process.
+///
+/// Requires `debug_assertions` or the `alloc-tracking` feature, otherwise returns 0.
+pub(crate) fn allocated_bytes() -> usize {
+    #[cfg(any(debug_assertions, feature = "alloc-tracking"))]
+    {
+        alloc_debug::ALLOCS
+            .read()
+            .map(|allocs| {
+                allocs
+                    .values()
+                    .filter(|record| record.count > 0)
+                    .map(|record| record.size)
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+    #[cfg(not(any(debug_assertions, feature = "alloc-tracking")))]
+    {
+        0
+    }
+}
+
 /// Print current allocation registry to logs.
 ///
-/// Requires `debug_assertions`, otherwise does nothing.
+/// Requires `debug_assertions` or the `alloc-tracking` feature, otherwise does nothing.
 pub fn dump_allocations() {
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "alloc-tracking"))]
     {
         use log::info;
 
@@ -0,0 +1,222 @@
+//! Rust iterators passed lazily to Wren.
+//!
+//! A [`WrenSequence`](struct.WrenSequence.html) is passed into Wren as an
+//! instance of a hidden foreign class, which implements Wren's iterator
+//! protocol. Items are pulled from the Rust iterator one at a time as Wren
+//! iterates, so a `for` loop that stops early never produces the rest.
+//!
+//! ```
+//! # use rust_wren::{prelude::*, sequence::WrenSequence};
+//! let mut vm = WrenBuilder::new().build();
+//! vm.interpret("example", r#"
+//! class Example {
+//!   static firstOver(seq, limit) {
+//!     for (value in seq) {
+//!       if (value > limit) return value
+//!     }
+//!   }
+//! }
+//! "#).unwrap();
+//!
+//! vm.context(|ctx| {
+//!     // Endless, so it could never be copied into a list.
+//!     let squares = WrenSequence::new((1..).map(|n| (n * n) as f64));
+//!
+//!     let first_over = ctx.make_call_ref("example", "Example", "firstOver(_,_)").unwrap();
+//!     let result = first_over.call::<_, f64>(ctx, (squares, 50.0)).unwrap();
+//!     assert_eq!(result, 64.0);
+//! });
+//! ```
+use crate::{
+    bindings,
+    class::{WrenCell, WrenForeignClass},
+    errors::{ForeignError, WrenError},
+    foreign::{ForeignClass, ForeignMethod},
+    handle::WrenRef,
+    value::ToWren,
+    ModuleBuilder, WrenContext,
+};
+use std::os::raw::c_void;
+
+/// Hidden module containing the foreign class backing sequences.
+pub(crate) const SEQUENCE_MODULE: &str = "rust_wren/sequence";
+
+/// Rust iterator that can be iterated lazily from Wren.
+///
+/// In Wren the value is a `Sequence`, so besides `for` loops it supports
+/// methods like `map`, `where` and `take`, which are lazy as well.
+///
+/// The iterator is consumed as Wren iterates, and can't be restarted.
+/// Iterating the same sequence a second time continues where the first
+/// left off. Each item is moved into Wren by `iteratorValue`, so asking
+/// for the value of the same step twice gives `null` the second time.
+pub struct WrenSequence {
+    iter: Box<dyn LazyIter>,
+}
+
+impl WrenSequence {
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: IntoIterator,
+        I::IntoIter: 'static,
+        I::Item: ToWren,
+    {
+        WrenSequence {
+            iter: Box::new(LazyIterState {
+                iter: iter.into_iter(),
+                current: None,
+            }),
+        }
+    }
+}
+
+/// Type erased iterator, holding on to the item of the current step
+/// until Wren asks for it.
+trait LazyIter {
+    /// Moves to the next item, returning `false` when the iterator is done.
+    fn advance(&mut self) -> bool;
+
+    /// Moves the current item into the given slot.
+    fn put_current(&mut self, ctx: &mut WrenContext, slot: i32);
+}
+
+struct LazyIterState<I: Iterator> {
+    iter: I,
+    current: Option<I::Item>,
+}
+
+impl<I> LazyIter for LazyIterState<I>
+where
+    I: Iterator,
+    I::Item: ToWren,
+{
+    fn advance(&mut self) -> bool {
+        self.current = self.iter.next();
+        self.current.is_some()
+    }
+
+    fn put_current(&mut self, ctx: &mut WrenContext, slot: i32) {
+        ToWren::put(self.current.take(), ctx, slot);
+    }
+}
+
+impl WrenForeignClass for WrenSequence {
+    const NAME: &'static str = "LazySequence";
+
+    fn register(bindings: &mut ModuleBuilder) {
+        bindings.add_class_binding(
+            Self::NAME,
+            ForeignClass {
+                allocate: sequence_allocate,
+                finalize: sequence_finalize,
+            },
+        );
+        bindings.add_reverse_class_lookup::<Self>();
+
+        bindings.add_method_binding(
+            Self::NAME,
+            ForeignMethod {
+                is_static: false,
+                arity: 1,
+                sig: "iterate(_)".to_owned(),
+                func: sequence_iterate,
+                doc: None,
+            },
+        );
+        bindings.add_method_binding(
+            Self::NAME,
+            ForeignMethod {
+                is_static: false,
+                arity: 1,
+                sig: "iteratorValue(_)".to_owned(),
+                func: sequence_iterator_value,
+                doc: None,
+            },
+        );
+    }
+
+    fn wren_declaration() -> String {
+        format!(
+            "foreign class {} is Sequence {{\n    foreign iterate(iter)\n    foreign iteratorValue(iter)\n}}\n",
+            Self::NAME
+        )
+    }
+}
+
+/// Moves the sequence into Wren as an instance of the hidden foreign class.
+///
+/// # Errors
+///
+/// Aborts the current fiber with a foreign error if the hidden class
+/// can't be found.
+impl ToWren for WrenSequence {
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        if let Err(err) = ctx.put_foreign(self, slot) {
+            ForeignError::new(err).put(ctx, slot);
+        }
+    }
+}
+
+/// Sequences are only created from Rust. The class declares no
+/// constructor, so Wren has no way to call this.
+extern "C" fn sequence_allocate(vm: *mut bindings::WrenVM) {
+    let vm: &mut bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+    let mut ctx = WrenContext::new(vm);
+    ForeignError::new(WrenError::ForeignType).put(&mut ctx, 0);
+}
+
+unsafe extern "C" fn sequence_finalize(data: *mut c_void) {
+    // Contents were initialised by `put`, and are dropped in place
+    // before the garbage collector frees the memory.
+    std::ptr::drop_in_place(data as *mut WrenCell<WrenSequence>);
+}
+
+/// Advances the iterator. Wren passes `null` on the first step, and
+/// the previous return value after that. The step count is returned
+/// while there are items left, and `false` once the iterator is done.
+extern "C" fn sequence_iterate(vm: *mut bindings::WrenVM) {
+    let vm: &mut bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+    let mut ctx = WrenContext::new(vm);
+
+    let cell = match ctx.get_foreign_cell::<WrenSequence>(0) {
+        Ok(cell) => cell,
+        Err(err) => return ForeignError::new(err).put(&mut ctx, 0),
+    };
+
+    let mut sequence = match cell.try_borrow_mut() {
+        Ok(sequence) => sequence,
+        Err(err) => return ForeignError::new(err).put(&mut ctx, 0),
+    };
+
+    if sequence.iter.advance() {
+        let step = ctx.get_slot::<Option<f64>>(1).ok().flatten().map(|step| step + 1.0);
+        ToWren::put(step.unwrap_or(0.0), &mut ctx, 0);
+    } else {
+        ToWren::put(false, &mut ctx, 0);
+    }
+}
+
+/// Moves the item of the current step into Wren.
+extern "C" fn sequence_iterator_value(vm: *mut bindings::WrenVM) {
+    let vm: &mut bindings::WrenVM = unsafe { vm.as_mut().unwrap() };
+    let mut ctx = WrenContext::new(vm);
+
+    let cell = match ctx.get_foreign_cell::<WrenSequence>(0) {
+        Ok(cell) => cell,
+        Err(err) => return ForeignError::new(err).put(&mut ctx, 0),
+    };
+
+    // The item replaces the receiver in slot 0. The handle keeps the
+    // receiver alive in case the item allocations trigger garbage collection.
+    let _receiver = match ctx.get_slot::<WrenRef>(0) {
+        Ok(receiver) => receiver,
+        Err(err) => return ForeignError::new(err).put(&mut ctx, 0),
+    };
+
+    let mut sequence = match cell.try_borrow_mut() {
+        Ok(sequence) => sequence,
+        Err(err) => return ForeignError::new(err).put(&mut ctx, 0),
+    };
+
+    sequence.iter.put_current(&mut ctx, 0);
+}
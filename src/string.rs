@@ -0,0 +1,89 @@
+use crate::{
+    bindings,
+    errors::{WrenError, WrenResult},
+    handle::WrenHandle,
+    types::WrenType,
+    value::{slot_str, FromWren, ToWren},
+    vm::WrenContext,
+};
+use std::fmt;
+
+/// Handle to a string in Wren.
+///
+/// Unlike `String`, retrieving a `WrenString` from a slot doesn't copy the
+/// contents. Unlike `&str`, the handle keeps the string alive, so it can't
+/// be garbage collected while it's borrowed. Useful for large strings that
+/// are referenced repeatedly.
+///
+/// Requires the [`WrenContext`] that owns the string
+/// to borrow its contents.
+pub struct WrenString(WrenHandle);
+
+impl WrenString {
+    /// Borrows the contents of the string.
+    ///
+    /// Wren strings are immutable, and the handle keeps the string alive,
+    /// so the contents can be borrowed for as long as both the handle and
+    /// the context. The VM can be dropped or reset once the context ends,
+    /// which would free the string regardless of the handle.
+    ///
+    /// The string is put into slot 0 to retrieve its contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not valid UTF-8.
+    pub fn as_str<'a>(&'a self, ctx: &'a WrenContext<'_>) -> WrenResult<&'a str> {
+        ctx.ensure_slots(1);
+
+        unsafe {
+            bindings::wrenSetSlotHandle(ctx.vm_ptr(), 0, self.0.raw_ptr().as_ptr());
+            slot_str(ctx, 0)
+        }
+    }
+}
+
+impl<'wren> FromWren<'wren> for WrenString {
+    type Output = WrenString;
+
+    fn get_slot(ctx: &WrenContext, string_slot: i32) -> WrenResult<Self::Output> {
+        if ctx.slot_type(string_slot as usize) != Some(WrenType::String) {
+            return Err(match ctx.slot_type(string_slot as usize) {
+                Some(actual) => WrenError::SlotType {
+                    actual,
+                    expected: WrenType::String,
+                },
+                None => WrenError::SlotOutOfBounds(string_slot),
+            });
+        }
+
+        unsafe {
+            let string_handle = bindings::wrenGetSlotHandle(ctx.vm_ptr(), string_slot);
+            if string_handle.is_null() {
+                return Err(WrenError::NullPtr);
+            }
+            ctx.record_handle();
+
+            let destructors = ctx.destructor_sender();
+
+            Ok(WrenString(WrenHandle::from_raw(string_handle, destructors)))
+        }
+    }
+}
+
+impl fmt::Debug for WrenString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WrenString").field(unsafe { &self.0.raw_ptr() }).finish()
+    }
+}
+
+impl ToWren for WrenString {
+    fn put(self, ctx: &mut WrenContext, string_slot: i32) {
+        ToWren::put(self.0, ctx, string_slot)
+    }
+}
+
+impl ToWren for &WrenString {
+    fn put(self, ctx: &mut WrenContext, string_slot: i32) {
+        ToWren::put(&self.0, ctx, string_slot)
+    }
+}
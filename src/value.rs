@@ -1,13 +1,16 @@
 use crate::{
     bindings,
     class::WrenCell,
-    errors::{WrenError, WrenResult},
+    errors::{ForeignError, WrenError, WrenResult},
     types::WrenType,
     WrenContext,
 };
 use std::{
-    ffi::{CStr, CString},
-    os::raw::c_void,
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    os::raw::{c_char, c_int, c_void},
+    slice, str,
+    time::Duration,
 };
 
 /// Helper macro for common verifications.
@@ -46,6 +49,106 @@ impl<'wren> FromWren<'wren> for bool {
     }
 }
 
+/// Boolean read by Wren's truthiness rules, where `null` and `false`
+/// are falsey and every other value is truthy, including `0` and `""`.
+///
+/// Use as a foreign method argument type instead of `bool` to accept
+/// any value. Plain `bool` remains strict, and errors on non-bool values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Truthy(pub bool);
+
+impl<'wren> FromWren<'wren> for Truthy {
+    type Output = Self;
+
+    fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        if slot_num < 0 {
+            return Err(WrenError::SlotOutOfBounds(slot_num));
+        }
+
+        match ctx.slot_type(slot_num as usize) {
+            Some(WrenType::Bool) => Ok(Truthy(unsafe { bindings::wrenGetSlotBool(ctx.vm_ptr(), slot_num) })),
+            Some(WrenType::Null) => Ok(Truthy(false)),
+            Some(_) => Ok(Truthy(true)),
+            None => Err(WrenError::SlotOutOfBounds(slot_num)),
+        }
+    }
+}
+
+impl From<Truthy> for bool {
+    #[inline]
+    fn from(truthy: Truthy) -> Self {
+        truthy.0
+    }
+}
+
+/// Value that can be retrieved as either of two types.
+///
+/// Use as a foreign method argument type to accept more than one form of
+/// a value, ie. `Either<&WrenCell<Vec2>, WrenList>` accepts both a foreign
+/// `Vec2` and a plain list `[x, y]`.
+///
+/// The left type is tried first. When the slot holds a value of another
+/// type, the right type is tried instead. Any other error from the left
+/// type, like a value that's out of range, is returned without trying the
+/// right type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+
+impl<'wren, A, B> FromWren<'wren> for Either<A, B>
+where
+    A: FromWren<'wren>,
+    B: FromWren<'wren>,
+{
+    type Output = Either<A::Output, B::Output>;
+
+    fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        match A::get_slot(ctx, slot_num) {
+            Ok(value) => Ok(Either::Left(value)),
+            Err(WrenError::SlotType { .. }) | Err(WrenError::ForeignType) | Err(WrenError::ReceiverNotForeign) => {
+                B::get_slot(ctx, slot_num).map(Either::Right)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Value retrieved along with its dynamic type in Wren.
+///
+/// Use as a foreign method argument type to branch on the type of an
+/// argument, ie. `Typed<WrenRef>` accepts any value as a handle and
+/// reports whether it's a number, string, list and so on.
+///
+/// The type is read before the value, so a value that fails to convert
+/// still returns its error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Typed<T> {
+    pub ty: WrenType,
+    pub value: T,
+}
+
+impl<'wren, T> FromWren<'wren> for Typed<T>
+where
+    T: FromWren<'wren>,
+{
+    type Output = Typed<T::Output>;
+
+    fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        if slot_num < 0 {
+            return Err(WrenError::SlotOutOfBounds(slot_num));
+        }
+
+        let ty = ctx
+            .slot_type(slot_num as usize)
+            .ok_or(WrenError::SlotOutOfBounds(slot_num))?;
+        let value = T::get_slot(ctx, slot_num)?;
+
+        Ok(Typed { ty, value })
+    }
+}
+
 macro_rules! impl_from_wren_num {
     ($t:ty) => {
         impl<'wren> FromWren<'wren> for $t {
@@ -68,9 +171,41 @@ impl_from_wren_num!(u8);
 impl_from_wren_num!(u16);
 impl_from_wren_num!(u32);
 impl_from_wren_num!(u64);
-impl_from_wren_num!(f32);
 impl_from_wren_num!(f64);
 
+/// Precision loss is permitted, but a finite number that
+/// overflows to infinity is an error.
+impl<'wren> FromWren<'wren> for f32 {
+    type Output = Self;
+
+    #[inline]
+    fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        let value = <f64 as FromWren>::get_slot(ctx, slot_num)?;
+        let narrowed = value as f32;
+
+        if value.is_finite() && narrowed.is_infinite() {
+            Err(WrenError::NumberRange { value, target: "f32" })
+        } else {
+            Ok(narrowed)
+        }
+    }
+}
+
+/// Duration as a number of seconds, ie. `1.5` is one and a half seconds.
+///
+/// Negative, infinite and NaN numbers are out of range.
+impl<'wren> FromWren<'wren> for Duration {
+    type Output = Self;
+
+    fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        let value = <f64 as FromWren>::get_slot(ctx, slot_num)?;
+        Duration::try_from_secs_f64(value).map_err(|_| WrenError::NumberRange {
+            value,
+            target: "Duration",
+        })
+    }
+}
+
 impl<'wren> FromWren<'wren> for String {
     type Output = Self;
 
@@ -79,22 +214,44 @@ impl<'wren> FromWren<'wren> for String {
     }
 }
 
+impl<'wren> FromWren<'wren> for Box<str> {
+    type Output = Self;
+
+    fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        <String as FromWren>::get_slot(ctx, slot_num).map(String::into_boxed_str)
+    }
+}
+
 /// Pretty risky. If we borrow a Wren string that gets garbage collected...
+///
+/// See [`WrenString`](../string/struct.WrenString.html) for a handle that
+/// keeps the string alive while it's borrowed.
 impl<'wren> FromWren<'wren> for &'wren str {
     type Output = Self;
 
     fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
         verify_slot!(ctx, slot_num, WrenType::String);
 
-        unsafe {
-            let char_ptr = bindings::wrenGetSlotString(ctx.vm_ptr(), slot_num);
-            if char_ptr.is_null() {
-                Err(WrenError::NullPtr)
-            } else {
-                let c_str = CStr::from_ptr(char_ptr);
-                c_str.to_str().map_err(WrenError::Utf8)
-            }
-        }
+        unsafe { slot_str(ctx, slot_num) }
+    }
+}
+
+/// Borrows the contents of the string in the given slot.
+///
+/// Wren strings carry their length, and may contain null bytes, so the
+/// contents are read by byte length instead of up to the first null.
+///
+/// # Safety
+///
+/// The slot must contain a string, which must outlive the returned borrow.
+pub(crate) unsafe fn slot_str<'a>(ctx: &WrenContext, slot_num: i32) -> WrenResult<&'a str> {
+    let mut len: c_int = 0;
+    let char_ptr = bindings::wrenGetSlotBytes(ctx.vm_ptr(), slot_num, &mut len);
+    if char_ptr.is_null() {
+        Err(WrenError::NullPtr)
+    } else {
+        let bytes = slice::from_raw_parts(char_ptr as *const u8, len as usize);
+        str::from_utf8(bytes).map_err(WrenError::Utf8)
     }
 }
 
@@ -108,6 +265,30 @@ impl<'wren> FromWren<'wren> for () {
     }
 }
 
+/// Marker for a Wren `null`.
+///
+/// Use as a foreign method argument type to require an argument to be
+/// explicitly `null`. Unlike `Option<T>`, any other value is an error.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WrenNull;
+
+impl<'wren> FromWren<'wren> for WrenNull {
+    type Output = Self;
+
+    #[inline]
+    fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        verify_slot!(ctx, slot_num, WrenType::Null);
+        Ok(WrenNull)
+    }
+}
+
+impl ToWren for WrenNull {
+    #[inline]
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        ToWren::put((), ctx, slot)
+    }
+}
+
 /// Wrapped in two `Option`s. The first will be unwrapped before calling
 /// the foreign method, and must be replaced with a WrenResult in the
 /// future. The second is the actual value passed to the foreign method,
@@ -142,6 +323,7 @@ where
 
     #[inline]
     fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        verify_receiver(ctx, slot_num)?;
         verify_slot!(ctx, slot_num, WrenType::Foreign);
         let void_ptr: *const c_void = unsafe { bindings::wrenGetSlotForeign(ctx.vm_ptr(), slot_num) as _ };
         unsafe { WrenCell::<T>::from_ptr(void_ptr) }
@@ -163,7 +345,6 @@ where
 }
 
 // FIXME: Move to class.rs
-// FIXME: Check if slot 0 is type UNKNOWN and print helpful error. Can be instance method call on static Rust func, or missing `foreign` on class.
 /// Needs an explicit implementation, otherwise the type checker
 /// picks `WrenForeignClass` for some reason.
 impl<'wren, T> FromWren<'wren> for &mut WrenCell<T>
@@ -174,12 +355,26 @@ where
 
     #[inline]
     fn get_slot(ctx: &WrenContext, slot_num: i32) -> WrenResult<Self::Output> {
+        verify_receiver(ctx, slot_num)?;
         verify_slot!(ctx, slot_num, WrenType::Foreign);
         let void_ptr: *mut c_void = unsafe { bindings::wrenGetSlotForeign(ctx.vm_ptr(), slot_num) as _ };
         unsafe { WrenCell::<T>::from_ptr_mut(void_ptr) }
     }
 }
 
+/// An UNKNOWN value in the receiver slot, where a foreign cell is expected,
+/// is usually a mistake in binding the class rather than a wrong argument.
+///
+/// The Wren class is missing the `foreign` keyword, so an instance method is
+/// called on a plain Wren object instead of a foreign one.
+fn verify_receiver(ctx: &WrenContext, slot_num: i32) -> WrenResult<()> {
+    if slot_num == 0 && ctx.slot_type(0) == Some(WrenType::Unknown) {
+        Err(WrenError::ReceiverNotForeign)
+    } else {
+        Ok(())
+    }
+}
+
 /// A type that can be passed to a Wren VM via a slot.
 pub trait ToWren {
     /// Moves the value into a slot in the VM.
@@ -196,6 +391,13 @@ impl ToWren for bool {
     }
 }
 
+impl ToWren for &bool {
+    #[inline]
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        ToWren::put(*self, ctx, slot)
+    }
+}
+
 macro_rules! impl_to_wren_num {
     ($t:ty) => {
         impl ToWren for $t {
@@ -204,6 +406,13 @@ macro_rules! impl_to_wren_num {
                 unsafe { bindings::wrenSetSlotDouble(ctx.vm_ptr(), slot, self as f64) }
             }
         }
+
+        impl ToWren for &$t {
+            #[inline]
+            fn put(self, ctx: &mut WrenContext, slot: i32) {
+                ToWren::put(*self, ctx, slot)
+            }
+        }
     };
 }
 
@@ -218,20 +427,51 @@ impl_to_wren_num!(u64);
 impl_to_wren_num!(f32);
 impl_to_wren_num!(f64);
 
+/// Duration as a number of seconds, with nanosecond precision
+/// reduced to what fits in a `f64`.
+impl ToWren for Duration {
+    #[inline]
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        ToWren::put(self.as_secs_f64(), ctx, slot)
+    }
+}
+
 impl ToWren for String {
+    #[inline]
     fn put(self, ctx: &mut WrenContext, slot: i32) {
-        // Wren copies the contents of the given string.
-        let c_string = CString::new(self).expect("String contains a null byte");
-        unsafe { bindings::wrenSetSlotString(ctx.vm_ptr(), slot, c_string.as_ptr()) }
+        ToWren::put(self.as_str(), ctx, slot)
     }
 }
 
+/// Strings are written with their length, so embedded null bytes are kept.
 impl ToWren for &str {
     fn put(self, ctx: &mut WrenContext, slot: i32) {
         // Wren copies the contents of the given string.
-        // We have two copies here, first &str to CString, then Wren allocateString.
-        let c_string = CString::new(self).expect("String contains a null byte");
-        unsafe { bindings::wrenSetSlotString(ctx.vm_ptr(), slot, c_string.as_ptr()) }
+        unsafe { bindings::wrenSetSlotBytes(ctx.vm_ptr(), slot, self.as_ptr() as *const c_char, self.len()) }
+    }
+}
+
+impl ToWren for &String {
+    #[inline]
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        ToWren::put(self.as_str(), ctx, slot)
+    }
+}
+
+impl ToWren for Box<str> {
+    #[inline]
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        ToWren::put(self.into_string(), ctx, slot)
+    }
+}
+
+impl ToWren for Cow<'_, str> {
+    #[inline]
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        match self {
+            Cow::Borrowed(s) => ToWren::put(s, ctx, slot),
+            Cow::Owned(s) => ToWren::put(s, ctx, slot),
+        }
     }
 }
 
@@ -253,6 +493,62 @@ where
     }
 }
 
+/// Creates a new Wren map. Keys must be value types, like strings and
+/// numbers. Any other key, like a list or object, aborts the current fiber.
+impl<K, V, S> ToWren for HashMap<K, V, S>
+where
+    K: ToWren,
+    V: ToWren,
+{
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        put_map(ctx, slot, self)
+    }
+}
+
+/// Creates a new Wren map. Keys must be value types, like strings and
+/// numbers. Any other key, like a list or object, aborts the current fiber.
+impl<K, V> ToWren for BTreeMap<K, V>
+where
+    K: ToWren,
+    V: ToWren,
+{
+    fn put(self, ctx: &mut WrenContext, slot: i32) {
+        put_map(ctx, slot, self)
+    }
+}
+
+fn put_map<K, V, I>(ctx: &mut WrenContext, slot: i32, entries: I)
+where
+    K: ToWren,
+    V: ToWren,
+    I: IntoIterator<Item = (K, V)>,
+{
+    // Key and value go into scratch slots past both the existing
+    // slots and the map, so neither is clobbered.
+    let key_slot = (ctx.slot_count() as i32).max(slot + 1);
+    let value_slot = key_slot + 1;
+    ctx.ensure_slots(value_slot as usize + 1);
+
+    unsafe { bindings::wrenSetSlotNewMap(ctx.vm_ptr(), slot) };
+
+    for (key, value) in entries {
+        key.put(ctx, key_slot);
+
+        // Wren only asserts that keys are hashable in debug builds. Ranges and
+        // classes are hashable too, but can't be told apart from other objects.
+        match ctx.slot_type(key_slot as usize) {
+            Some(WrenType::Bool | WrenType::Number | WrenType::String | WrenType::Null) => {}
+            ty => {
+                let ty = ty.unwrap_or(WrenType::Unknown);
+                return ForeignError::new(WrenError::InvalidMapKey(ty)).put(ctx, slot);
+            }
+        }
+
+        value.put(ctx, value_slot);
+        unsafe { bindings::wrenSetMapValue(ctx.vm_ptr(), slot, key_slot, value_slot) };
+    }
+}
+
 // Wren maximum function arguments is 16
 rust_wren_derive::generate_tuple_to_wren!(A);
 rust_wren_derive::generate_tuple_to_wren!(A, B);
@@ -1,48 +1,228 @@
 //! Core virtual machine.
 use crate::{
+    allocator::WrenAllocator,
     bindings,
+    callback::{WrenCallback, CALLBACK_MODULE},
     class::{WrenCell, WrenForeignClass},
-    errors::{WrenCompileError, WrenError, WrenResult, WrenStackFrame, WrenVmError},
-    foreign::{ForeignBindings, ForeignClass, ForeignClassKey, ForeignMethod, ForeignMethodKey},
-    handle::{FnSymbolRef, WrenCallRef, WrenHandle, WrenRef},
+    errors::{to_c_string, WrenCompileError, WrenError, WrenResult, WrenStackFrame, WrenVmError},
+    fiber::WrenFiber,
+    foreign::{
+        ForeignBindings, ForeignClass, ForeignClassKey, ForeignMethod, ForeignMethodKey, CLOSURE_TRAMPOLINES,
+        MAX_CLOSURE_METHODS,
+    },
+    handle::{wren_call, FnSymbol, FnSymbolRef, WrenCallHandle, WrenCallRef, WrenHandle, WrenRef},
     list::WrenList,
     module::{ModuleLoader, ModuleResolver},
-    runtime, types,
-    value::FromWren,
+    output::{self, OutputFns, OUT_MODULE},
+    runtime,
+    sequence::{WrenSequence, SEQUENCE_MODULE},
+    types,
+    value::{FromWren, ToWren},
 };
 use log::trace;
 use std::{
     any::TypeId,
     borrow::{Borrow, Cow},
     cell::{Cell, RefCell},
-    ffi::CString,
+    collections::{HashMap, HashSet},
+    fs,
     marker::PhantomData,
     mem,
     os::raw::c_int,
+    path::Path,
     ptr::{self, NonNull},
-    sync::mpsc::{channel, Receiver, Sender},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{channel, Receiver, Sender, TryRecvError},
+    },
 };
 
+/// Hidden module used to import Wren's optional `meta` module.
+const META_IMPORT_MODULE: &str = "rust_wren/meta";
+
+/// Hidden module used to pass values into module variables.
+const SET_VAR_MODULE: &str = "rust_wren/set_var";
+
+/// Hidden module that expressions are evaluated in by [`WrenContext::eval_number`].
+const EVAL_MODULE: &str = "rust_wren/eval";
+
+/// Prefix of the hidden modules that source is compiled into by [`WrenVm::compile_only`].
+const COMPILE_MODULE: &str = "rust_wren/compile";
+
+/// Counter for naming compile modules, since module variables can't be
+/// declared twice.
+static COMPILE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
 pub struct WrenVm {
     vm: *mut bindings::WrenVM,
     handle_rx: Receiver<*mut bindings::WrenHandle>,
+    /// Receivers of VMs discarded by [`reset`](#method.reset), kept until their
+    /// handles are dropped, so dropping an old handle isn't mistaken for
+    /// dropping it after the VM.
+    retired_rx: Vec<Receiver<*mut bindings::WrenHandle>>,
 }
 
 impl WrenVm {
     #[must_use = "possible VM errors are contained in the returned result"]
     pub fn interpret(&mut self, module: &str, source: &str) -> WrenResult<()> {
+        // Wren copies these strings, so they are safe to free.
+        let c_module = to_c_string(module, "Module name")?;
+        let c_source = to_c_string(source, "Source")?;
+
         let result_id: bindings::WrenInterpretResult = {
             let vm = unsafe { self.vm.as_mut().unwrap() };
             let _guard = ContextGuard { vm: self };
 
-            // Wren copies these strings, so they are safe to free.
-            let c_module = CString::new(module).expect("Module name contains a null byte");
-            let c_source = CString::new(source).expect("Source contains a null byte");
-            unsafe { bindings::wrenInterpret(vm, c_module.as_ptr(), c_source.as_ptr()) }
+            enter_vm(vm, || unsafe {
+                bindings::wrenInterpret(vm, c_module.as_ptr(), c_source.as_ptr())
+            })?
         };
 
         // self.take_interpret_result(result)
-        Self::take_errors(self.vm, result_id)
+        Self::take_errors(self.vm, result_id).map_err(|err| err.locate_in_source(module, source))?;
+
+        self.preload_calls();
+        Ok(())
+    }
+
+    /// Retrieves a call handle preloaded with [`WrenBuilder::with_preloaded_calls`](struct.WrenBuilder.html#method.with_preloaded_calls),
+    /// by its module, and its variable and signature, ie. `"Game.update(_)"`.
+    ///
+    /// Returns `None` if the call wasn't preloaded, or its variable hasn't
+    /// been declared by an interpreted script yet. To make the call, use
+    /// [`WrenContext::cached_call`](struct.WrenContext.html#method.cached_call)
+    /// from within a context instead.
+    pub fn cached_call(&self, module: &str, name: &str) -> Option<&WrenCallHandle> {
+        unsafe { WrenVm::get_user_data(self.vm) }.and_then(|userdata| userdata.cached_call(module, name))
+    }
+
+    /// Creates the call handles of preloaded calls whose variables have been
+    /// declared since the last interpret.
+    fn preload_calls(&mut self) {
+        let pending = match unsafe { WrenVm::get_user_data(self.vm) } {
+            Some(userdata)
+                if userdata.calls.values().map(HashMap::len).sum::<usize>() < userdata.preloaded_calls.len() =>
+            {
+                userdata
+                    .preloaded_calls
+                    .iter()
+                    .filter(|call| userdata.cached_call(&call.module, &call.name()).is_none())
+                    .cloned()
+                    .collect::<Vec<_>>()
+            }
+            _ => return,
+        };
+
+        for call in pending {
+            let result = self.context_result(|ctx| {
+                if !ctx.has_var(&call.module, &call.variable) {
+                    return Ok(None);
+                }
+
+                let receiver = ctx.get_var(&call.module, &call.variable)?;
+                let func = FnSymbolRef::compile(ctx, &call.sig)?;
+                WrenCallRef::new(receiver, func).leak().map(Some)
+            });
+
+            let userdata = unsafe { WrenVm::get_user_data(self.vm) }.expect("User data in VM is null");
+            match result {
+                Ok(Some(handle)) => {
+                    userdata
+                        .calls
+                        .entry(call.module.clone())
+                        .or_default()
+                        .insert(call.name(), handle);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    // Not retried, so the error is only reported once.
+                    log::error!(
+                        "Preloading call {} in module {} failed: {}",
+                        call.name(),
+                        call.module,
+                        err
+                    );
+                    userdata.preloaded_calls.retain(|other| other != &call);
+                }
+            }
+        }
+    }
+
+    /// Reads a script file, and interprets it in the given module.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WrenError::Io`](enum.WrenError.html#variant.Io) when the
+    /// file can't be read, otherwise the same errors as [`interpret`](#method.interpret).
+    #[must_use = "possible VM errors are contained in the returned result"]
+    pub fn interpret_file<P: AsRef<Path>>(&mut self, module: &str, path: P) -> WrenResult<()> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|cause| WrenError::Io {
+            path: path.to_path_buf(),
+            cause,
+        })?;
+
+        self.interpret(module, &source)
+    }
+
+    /// Compiles source to check it for errors, without running it.
+    ///
+    /// Useful for validating scripts with side effects, for example in an editor.
+    /// Compile errors are returned the same way as [`interpret`](#method.interpret),
+    /// reported against the given module name.
+    ///
+    /// # Implementation
+    ///
+    /// Wren has no compile only entry point, so the source is compiled with
+    /// `Meta.compile` into a new hidden module. The given module is not created
+    /// or modified. Each call leaves a small module behind in the VM, which Wren
+    /// has no way of unloading.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// let mut vm = WrenBuilder::new().build();
+    /// vm.compile_only("example", r#"Fiber.abort("Never runs")"#).unwrap();
+    /// assert!(vm.compile_only("example", "var = 1").unwrap_err().is_compile_error());
+    /// ```
+    #[must_use = "possible compile errors are contained in the returned result"]
+    pub fn compile_only(&mut self, module: &str, source: &str) -> WrenResult<()> {
+        let compile_module = format!("{}/{}", COMPILE_MODULE, COMPILE_COUNT.fetch_add(1, Ordering::Relaxed));
+
+        // Meta compiles into the module of its caller, so the function calling
+        // it must be declared in the module that will contain the compiled source.
+        self.interpret(
+            &compile_module,
+            r#"
+            var rustWrenCompile = Fn.new {|source|
+              import "meta" for Meta
+              return Meta.compile(source) != null
+            }
+            "#,
+        )?;
+
+        let compiled = self.context_result(|ctx| {
+            ctx.make_call_ref(&compile_module, "rustWrenCompile", "call(_)")?
+                .call::<_, bool>(ctx, source)
+        })?;
+
+        if compiled {
+            return Ok(());
+        }
+
+        // Compile errors were reported to the error queue, while the call itself succeeded.
+        match Self::take_errors(self.vm, bindings::WrenInterpretResult_WREN_RESULT_COMPILE_ERROR) {
+            Err(WrenError::CompileError(mut errors)) => {
+                for err in &mut errors {
+                    err.module = module.into();
+                    err.locate(source);
+                }
+                Err(WrenError::CompileError(errors))
+            }
+            result => result,
+        }
     }
 
     pub fn context<F>(&mut self, func: F)
@@ -65,6 +245,35 @@ impl WrenVm {
         func(&mut ctx)
     }
 
+    /// Runs the closure with a shared context, for inspecting slots.
+    ///
+    /// Slots can only be accessed through a [`WrenContext`](struct.WrenContext.html),
+    /// which borrows the VM for the duration of the closure. This is the same
+    /// as [`context`](#method.context), except it returns the closure's result
+    /// and only hands out a shared context. The slots are read-only by
+    /// convention: a shared context can still grow the slots, and inspecting
+    /// a handle writes it into a scratch slot past the existing slots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::{prelude::*, WrenType};
+    /// # let mut vm = WrenBuilder::new().build();
+    /// vm.interpret("example", "var score = 42").unwrap();
+    /// vm.context(|ctx| {
+    ///     ctx.get_var("example", "score").unwrap();
+    /// });
+    ///
+    /// let slot_type = vm.with_slots(|ctx| ctx.slot_type(0));
+    /// assert_eq!(slot_type, Some(WrenType::Number));
+    /// ```
+    pub fn with_slots<R>(&mut self, func: impl FnOnce(&WrenContext) -> R) -> R {
+        let vm = unsafe { self.vm.as_mut().unwrap() };
+        let _guard = ContextGuard { vm: self };
+        let ctx = WrenContext::new(vm);
+        func(&ctx)
+    }
+
     /// Returns the number of allocated slots.
     #[inline]
     pub fn slot_count(&self) -> i32 {
@@ -99,7 +308,27 @@ impl WrenVm {
         let mut errors = userdata.errors.borrow_mut();
 
         match result_id {
-            bindings::WrenInterpretResult_WREN_RESULT_SUCCESS => Ok(()),
+            bindings::WrenInterpretResult_WREN_RESULT_SUCCESS => {
+                // Wren doesn't fail when a foreign class isn't found, so with strict
+                // bindings the recorded error is reported here instead.
+                let missing = errors.iter().position(|err| match err {
+                    WrenVmError::Foreign(err) => matches!(
+                        err.inner().downcast_ref::<WrenError>(),
+                        Some(WrenError::BindingNotFound { .. })
+                    ),
+                    _ => false,
+                });
+
+                match missing.map(|index| errors.remove(index)) {
+                    Some(WrenVmError::Foreign(err)) => Err(WrenError::RuntimeError {
+                        message: err.to_string(),
+                        foreign: Some(err.take_inner()),
+                        stack: vec![],
+                        result: result_id,
+                    }),
+                    _ => Ok(()),
+                }
+            }
             bindings::WrenInterpretResult_WREN_RESULT_COMPILE_ERROR => {
                 if errors.is_empty() {
                     return Err(WrenError::ErrorAbsent(result_id));
@@ -108,7 +337,18 @@ impl WrenVm {
                 let compile_errors = errors
                     .drain(..)
                     .map(|err| match err {
-                        WrenVmError::Compile { module, message, line } => WrenCompileError { module, message, line },
+                        WrenVmError::Compile {
+                            module,
+                            message,
+                            line,
+                            token,
+                        } => WrenCompileError {
+                            module,
+                            message,
+                            line,
+                            token,
+                            column: None,
+                        },
                         err => unreachable!("Unexpected VM error {:?}", err),
                     })
                     .collect::<Vec<_>>();
@@ -154,17 +394,231 @@ impl WrenVm {
                     message,
                     foreign,
                     stack,
+                    result: result_id,
                 })
             }
             _ => unreachable!("Unknown Wren result type: {}", result_id),
         }
     }
 
+    /// Replaces the function handling `System.print()` calls from Wren.
+    ///
+    /// The previous write function is dropped. A line buffered write function
+    /// flushes its incomplete line when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// let mut vm = WrenBuilder::new().build();
+    /// vm.set_write_fn(|s| eprint!("{}", s));
+    /// vm.interpret("example", r#"System.print("Printed to stderr")"#).unwrap();
+    /// ```
+    pub fn set_write_fn<F>(&mut self, write_fn: F)
+    where
+        F: Fn(&str) + 'static,
+    {
+        let userdata = unsafe { WrenVm::get_user_data(self.vm).expect("User data in VM is null") };
+        userdata.write_fn = Box::new(write_fn);
+    }
+
+    /// Discards all state in the VM, including every module, and creates
+    /// a fresh VM with the same configuration.
+    ///
+    /// Foreign bindings, the module resolver and loader, and the write
+    /// function are carried over to the new VM, so classes don't need to
+    /// be registered again.
+    ///
+    /// # Handles
+    ///
+    /// Handles created before the reset point into the freed VM, and become
+    /// dangling. They must not be used with the new VM. Dropping them is
+    /// safe, because they are not released into the new VM, and doesn't
+    /// count as outliving the VM for the `handle-backtrace` feature.
+    pub fn reset(&mut self) {
+        log::debug!("Resetting Wren VM: {:?}", self.vm);
+
+        let UserData {
+            foreign,
+            resolver,
+            loader,
+            strict_bindings,
+            resolve_cache,
+            write_fn,
+            allocator,
+            output,
+            preloaded_calls,
+            ..
+        } = *self.free().expect("User data in VM is null");
+
+        // New channel so handles from the old VM are never
+        // released into the new one.
+        let (handle_tx, handle_rx) = channel();
+
+        self.vm = WrenVm::new_raw(UserData {
+            foreign,
+            handle_tx,
+            resolver,
+            loader,
+            strict_bindings,
+            // Imports are resolved again by the new VM.
+            resolve_cache: resolve_cache.map(|_| RefCell::new(HashMap::new())),
+            loaded_modules: HashSet::new(),
+            errors: RefCell::new(Vec::new()),
+            symbols: RefCell::new(HashMap::new()),
+            executing: Cell::new(false),
+            #[cfg(any(debug_assertions, feature = "handle-tracking"))]
+            handle_count: Cell::new(0),
+            write_fn,
+            allocator,
+            output,
+            // Handles are created again once the variables are declared in the new VM.
+            preloaded_calls,
+            calls: HashMap::new(),
+        });
+        let retired = mem::replace(&mut self.handle_rx, handle_rx);
+        self.retired_rx.push(retired);
+        self.declare_builtins();
+    }
+
+    /// Clears the cached results of the module resolver, so the next
+    /// import of each module calls the resolver again.
+    ///
+    /// Does nothing when resolve caching is not enabled on the builder.
+    pub fn clear_resolve_cache(&mut self) {
+        if let Some(userdata) = unsafe { WrenVm::get_user_data(self.vm) } {
+            if let Some(cache) = &userdata.resolve_cache {
+                cache.borrow_mut().clear();
+            }
+        }
+    }
+
+    /// Declares the hidden classes the crate relies on, such as the foreign classes
+    /// backing [`WrenCallback`](callback/struct.WrenCallback.html) and
+    /// [`WrenSequence`](sequence/struct.WrenSequence.html).
+    ///
+    /// Their bindings must already be registered with the user data.
+    fn declare_builtins(&mut self) {
+        self.interpret(CALLBACK_MODULE, &WrenCallback::wren_declaration())
+            .expect("Declaring built in classes failed");
+        self.interpret(SEQUENCE_MODULE, &WrenSequence::wren_declaration())
+            .expect("Declaring built in classes failed");
+
+        let has_output = unsafe { WrenVm::get_user_data(self.vm) }
+            .map(|userdata| userdata.output.is_some())
+            .unwrap_or(false);
+        if has_output {
+            self.interpret(OUT_MODULE, output::wren_declaration())
+                .expect("Declaring built in classes failed");
+        }
+    }
+
+    /// Creates a Wren VM that takes ownership of the given user data.
+    fn new_raw(user_data: UserData) -> *mut bindings::WrenVM {
+        let mut config = unsafe {
+            let mut uninit_config = mem::MaybeUninit::<bindings::WrenConfiguration>::zeroed();
+            bindings::wrenInitConfiguration(uninit_config.as_mut_ptr());
+            uninit_config.assume_init()
+        };
+
+        config.resolveModuleFn = if user_data.resolver.is_some() {
+            Some(runtime::resolve_module)
+        } else {
+            None
+        };
+        config.loadModuleFn = if user_data.loader.is_some() {
+            Some(runtime::load_module)
+        } else {
+            None
+        };
+        config.reallocateFn = Some(runtime::wren_reallocate);
+        config.writeFn = Some(runtime::write_function);
+        config.errorFn = Some(runtime::error_function);
+        config.userData = Box::into_raw(Box::new(user_data)) as _;
+        config.bindForeignMethodFn = Some(ForeignBindings::bind_foreign_method);
+        config.bindForeignClassFn = Some(ForeignBindings::bind_foreign_class);
+
+        // WrenVM makes a copy of the configuration. We can
+        // discard our copy after creation.
+        let vm = unsafe { bindings::wrenNewVM(&mut config) };
+        if vm.is_null() {
+            panic!("Unexpected null result when creating WrenVM via C");
+        }
+
+        log::debug!("Created Wren VM: {:?}", vm);
+        vm
+    }
+
+    /// Frees the underlying Wren VM, returning ownership of its user data.
+    ///
+    /// Leaves the VM pointer null.
+    fn free(&mut self) -> Option<Box<UserData>> {
+        // Cached symbols are owned by the user data, and must be
+        // released before the VM is freed.
+        if let Some(userdata) = unsafe { WrenVm::get_user_data(self.vm) } {
+            userdata.symbols.borrow_mut().clear();
+            userdata.calls.clear();
+        }
+
+        self.maintain();
+
+        let user_data = unsafe {
+            let c_user_data = bindings::wrenGetUserData(self.vm);
+            if c_user_data.is_null() {
+                None
+            } else {
+                Some(Box::from_raw(c_user_data as *mut UserData))
+            }
+        };
+
+        unsafe { bindings::wrenFreeVM(self.vm) };
+        // VM is now deallocated.
+        self.vm = ptr::null_mut();
+
+        user_data
+    }
+
     fn maintain(&mut self) {
         trace!("Maintaining WrenVm");
         while let Ok(handle) = self.handle_rx.try_recv() {
             trace!("Release handle {:?}", handle);
             unsafe { bindings::wrenReleaseHandle(self.vm, handle) };
+
+            #[cfg(any(debug_assertions, feature = "handle-tracking"))]
+            if let Some(userdata) = unsafe { WrenVm::get_user_data(self.vm) } {
+                userdata.handle_count.set(userdata.handle_count.get().saturating_sub(1));
+            }
+        }
+
+        // Handles of a discarded VM point into freed memory, and are never released.
+        self.retired_rx.retain(|rx| loop {
+            match rx.try_recv() {
+                Ok(_) => continue,
+                Err(TryRecvError::Empty) => break true,
+                Err(TryRecvError::Disconnected) => break false,
+            }
+        });
+    }
+
+    /// Number of handles created in the VM that haven't been released yet.
+    ///
+    /// Handles are released in batches, when a context or interpret call
+    /// ends. A handle that's dropped is counted until then. Call handles
+    /// cached by the VM, ie. for [`WrenList::clear`](list/struct.WrenList.html#method.clear),
+    /// are kept for its lifetime and always counted.
+    ///
+    /// Requires `debug_assertions` or the `handle-tracking` feature, otherwise
+    /// always returns 0.
+    pub fn outstanding_handles(&self) -> usize {
+        #[cfg(any(debug_assertions, feature = "handle-tracking"))]
+        {
+            unsafe { WrenVm::get_user_data(self.vm) }
+                .map(|userdata| userdata.handle_count.get())
+                .unwrap_or(0)
+        }
+        #[cfg(not(any(debug_assertions, feature = "handle-tracking")))]
+        {
+            0
         }
     }
 }
@@ -174,24 +628,87 @@ impl Drop for WrenVm {
         if !self.vm.is_null() {
             log::debug!("Dropping Wren VM: {:?}", self.vm);
 
-            self.maintain();
-
             // Drop boxed user data
-            unsafe {
-                let c_user_data = bindings::wrenGetUserData(self.vm);
-                if !c_user_data.is_null() {
-                    let user_data = Box::from_raw(c_user_data as *mut UserData);
-                    drop(user_data);
-                }
-            };
-
-            unsafe { bindings::wrenFreeVM(self.vm) };
-            // VM is now deallocated.
-            self.vm = ptr::null_mut();
+            drop(self.free());
         }
     }
 }
 
+/// Wrapper allowing a [`WrenVm`](struct.WrenVm.html) to be moved to another thread.
+///
+/// Wren is not thread-safe, and a `WrenVm` holds raw pointers into the VM
+/// along with state that isn't `Send`, like `Rc` and `RefCell`. This wrapper
+/// only asserts that ownership of the whole VM moves between threads; it does
+/// not make the VM safe to use from more than one thread at a time, and it is
+/// deliberately not `Sync`.
+///
+/// Requires the `send` feature.
+///
+/// # Examples
+///
+/// ```
+/// # use rust_wren::{prelude::*, SendWrenVm};
+/// let vm = WrenBuilder::new().build();
+/// let send_vm = unsafe { SendWrenVm::new(vm) };
+///
+/// std::thread::spawn(move || {
+///     let mut vm = send_vm.into_inner();
+///     vm.interpret("example", r#"System.print("Hello from a thread")"#).unwrap();
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+#[cfg(feature = "send")]
+pub struct SendWrenVm(WrenVm);
+
+#[cfg(feature = "send")]
+impl SendWrenVm {
+    /// Wrap a VM so it can be sent to another thread.
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that:
+    ///
+    /// - The VM is only ever accessed from one thread at a time, and any hand
+    ///   over between threads is synchronised, for example through a channel
+    ///   or a mutex.
+    /// - Everything the VM owns is safe to move with it. This includes the
+    ///   write function, module resolver and module loader given to the
+    ///   builder, and every foreign object stored in the VM. None of them may
+    ///   share non-thread-safe state, like an `Rc`, with the original thread.
+    /// - No [`WrenHandle`](handle/struct.WrenHandle.html), call handle or other
+    ///   handle created by the VM is used or dropped on another thread than the
+    ///   one currently owning the VM. Dropping a handle queues its release on
+    ///   the VM.
+    pub unsafe fn new(vm: WrenVm) -> Self {
+        SendWrenVm(vm)
+    }
+
+    /// Unwrap the VM on the thread that now owns it.
+    pub fn into_inner(self) -> WrenVm {
+        self.0
+    }
+}
+
+#[cfg(feature = "send")]
+unsafe impl Send for SendWrenVm {}
+
+#[cfg(feature = "send")]
+impl std::ops::Deref for SendWrenVm {
+    type Target = WrenVm;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "send")]
+impl std::ops::DerefMut for SendWrenVm {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 /// Scope guard that ensures a [`WrenVm`](struct.WrenVm.html) is maintained
 /// when a context ends.
 struct ContextGuard<'wren> {
@@ -212,6 +729,12 @@ pub struct WrenBuilder {
     write_fn: Option<Box<dyn Fn(&str)>>,
     resolver: Option<Box<dyn ModuleResolver>>,
     loader: Option<Box<dyn ModuleLoader>>,
+    strict_bindings: Option<bool>,
+    resolve_cache: bool,
+    bootstrap: Vec<(String, String)>,
+    allocator: Option<Box<dyn WrenAllocator>>,
+    output: OutputFns,
+    preloaded_calls: Vec<PreloadedCall>,
 }
 
 impl WrenBuilder {
@@ -220,11 +743,43 @@ impl WrenBuilder {
     }
 
     /// Replaces the foreign bindings with the provided registry.
+    ///
+    /// Bindings registered with [`with_module`](#method.with_module) beforehand
+    /// are discarded. To keep them, use [`merge_foreign`](#method.merge_foreign).
     pub fn with_foreign(mut self, foreign_bindings: ForeignBindings) -> Self {
         self.foreign = foreign_bindings;
         self
     }
 
+    /// Adds the bindings of the provided registry to the ones already registered.
+    ///
+    /// See [`ForeignBindings::merge`](foreign/struct.ForeignBindings.html#method.merge).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::{prelude::*, foreign::ForeignBindings};
+    /// # #[wren_class] struct Foo;
+    /// # #[wren_methods] impl Foo { #[construct] fn new() -> Self { Self } }
+    /// # #[wren_class] struct Bar;
+    /// # #[wren_methods] impl Bar { #[construct] fn new() -> Self { Self } }
+    /// // Bindings provided by a library.
+    /// let library = ForeignBindings::new().with_module("library", |module| {
+    ///     module.register::<Foo>();
+    /// });
+    ///
+    /// let vm = WrenBuilder::new()
+    ///     .with_module("app", |module| {
+    ///         module.register::<Bar>();
+    ///     })
+    ///     .merge_foreign(library)
+    ///     .build();
+    /// ```
+    pub fn merge_foreign(mut self, foreign_bindings: ForeignBindings) -> Self {
+        self.foreign.merge(foreign_bindings);
+        self
+    }
+
     pub fn with_module<'a, S, F>(mut self, module: S, func: F) -> Self
     where
         S: Into<Cow<'a, str>>,
@@ -234,12 +789,30 @@ impl WrenBuilder {
         let module_name = module_cow.borrow();
         let mut module_builder = ModuleBuilder {
             module: module_name,
+            class_name: None,
             foreign: &mut self.foreign,
         };
         func(&mut module_builder);
         self
     }
 
+    /// Controls how foreign classes and methods declared in Wren, but
+    /// not registered with the builder, are reported.
+    ///
+    /// By default a warning is logged. When `false`, missing bindings
+    /// are silently ignored. When `true`, a missing binding is recorded
+    /// as a [`WrenError::BindingNotFound`](../errors/enum.WrenError.html#variant.BindingNotFound),
+    /// which is returned as the foreign error of the runtime error.
+    ///
+    /// Wren fails a missing foreign method when its class is defined. Wren
+    /// doesn't fail a missing foreign class, so the error is returned as a
+    /// runtime error once the `interpret` call that declared the class has
+    /// finished running.
+    pub fn with_strict_bindings(mut self, strict: bool) -> Self {
+        self.strict_bindings = Some(strict);
+        self
+    }
+
     pub fn with_write_fn<F>(mut self, write_fn: F) -> Self
     where
         F: Fn(&str) + 'static,
@@ -248,6 +821,78 @@ impl WrenBuilder {
         self
     }
 
+    /// Sets a write function that receives whole lines.
+    ///
+    /// Wren passes the output of `System.print()` to the write function in
+    /// chunks, with the trailing newline as a separate call. This buffers the
+    /// chunks, and invokes the given function once per line, without the
+    /// newline. Any incomplete line left when the VM is dropped is flushed.
+    pub fn with_line_buffered_write_fn<F>(self, write_fn: F) -> Self
+    where
+        F: Fn(&str) + 'static,
+    {
+        let line_buffer = LineBuffer {
+            buffer: RefCell::new(String::new()),
+            write_fn,
+        };
+        self.with_write_fn(move |s| line_buffer.write(s))
+    }
+
+    /// Sets a write function that appends everything Wren prints to a
+    /// shared buffer, including newlines, and returns the buffer.
+    ///
+    /// Useful for tests and tools inspecting the output of `System.print()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// let (builder, output) = WrenBuilder::new().capture_output();
+    /// let mut vm = builder.build();
+    ///
+    /// vm.interpret("example", r#"System.print("Hello")"#).unwrap();
+    /// assert_eq!(output.borrow().as_str(), "Hello\n");
+    /// ```
+    pub fn capture_output(self) -> (Self, Rc<RefCell<String>>) {
+        let output = Rc::new(RefCell::new(String::new()));
+        let buffer = output.clone();
+        (self.with_write_fn(move |s| buffer.borrow_mut().push_str(s)), output)
+    }
+
+    /// Sends messages from `Out.log(_)` in Wren to the given function.
+    ///
+    /// See [`output`](output/index.html) for the `Out` class. Channels
+    /// without a function are sent to the `log` crate.
+    pub fn with_log_fn<F>(mut self, log_fn: F) -> Self
+    where
+        F: Fn(&str) + 'static,
+    {
+        self.output.log = Some(Box::new(log_fn));
+        self
+    }
+
+    /// Sends messages from `Out.warn(_)` in Wren to the given function.
+    ///
+    /// See [`with_log_fn`](#method.with_log_fn).
+    pub fn with_warn_fn<F>(mut self, warn_fn: F) -> Self
+    where
+        F: Fn(&str) + 'static,
+    {
+        self.output.warn = Some(Box::new(warn_fn));
+        self
+    }
+
+    /// Sends messages from `Out.error(_)` in Wren to the given function.
+    ///
+    /// See [`with_log_fn`](#method.with_log_fn).
+    pub fn with_error_out_fn<F>(mut self, error_fn: F) -> Self
+    where
+        F: Fn(&str) + 'static,
+    {
+        self.output.error = Some(Box::new(error_fn));
+        self
+    }
+
     pub fn with_module_resolver<T>(mut self, resolver: T) -> Self
     where
         T: 'static + ModuleResolver,
@@ -256,6 +901,17 @@ impl WrenBuilder {
         self
     }
 
+    /// Caches the names returned by the module resolver, keyed by the
+    /// importing module and the imported name.
+    ///
+    /// Repeated imports of the same module then skip the resolver. The
+    /// cache can be cleared with [`WrenVm::clear_resolve_cache`](struct.WrenVm.html#method.clear_resolve_cache),
+    /// for example when the resolver's view of the modules changes.
+    pub fn with_resolve_cache(mut self, enabled: bool) -> Self {
+        self.resolve_cache = enabled;
+        self
+    }
+
     pub fn with_module_loader<T>(mut self, loader: T) -> Self
     where
         T: 'static + ModuleLoader,
@@ -264,63 +920,219 @@ impl WrenBuilder {
         self
     }
 
-    /// By default print to stdout.
-    fn default_write_fn() -> Box<dyn Fn(&str) + 'static> {
-        Box::new(|s| print!("{}", s))
-    }
-
-    pub fn build(self) -> WrenVm {
-        // Wren handle pointers that need to be released.
-        let (handle_tx, handle_rx) = channel();
-
-        let mut config = unsafe {
-            let mut uninit_config = mem::MaybeUninit::<bindings::WrenConfiguration>::zeroed();
-            bindings::wrenInitConfiguration(uninit_config.as_mut_ptr());
-            uninit_config.assume_init()
-        };
-
+    /// Preloads call handles for methods called often, like a per-frame `update()`,
+    /// so they're compiled once instead of on each call.
+    ///
+    /// Each call is given as the module and variable of the receiver, and the
+    /// method signature. A handle is created after the first interpret that
+    /// declares the variable, which includes the bootstrap scripts. Until then
+    /// the call isn't available. A call whose signature fails to compile is
+    /// logged and dropped.
+    ///
+    /// Handles are retrieved from within a context with
+    /// [`WrenContext::cached_call`](struct.WrenContext.html#method.cached_call),
+    /// by module, and named by variable and signature, ie. `"Game.update(_)"`.
+    /// They are released when the VM is dropped, or reset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// let mut vm = WrenBuilder::new()
+    ///     .with_preloaded_calls(&[("main", "Game", "update(_)")])
+    ///     .build();
+    ///
+    /// vm.interpret("main", r#"
+    /// class Game {
+    ///     static update(dt) { dt * 2 }
+    /// }
+    /// "#).unwrap();
+    ///
+    /// vm.context(|ctx| {
+    ///     let update = ctx.cached_call("main", "Game.update(_)").unwrap();
+    ///     assert_eq!(update.call::<_, f64>(ctx, 0.5).unwrap(), 1.0);
+    /// });
+    /// ```
+    pub fn with_preloaded_calls(mut self, calls: &[(&str, &str, &str)]) -> Self {
+        for &(module, variable, sig) in calls {
+            let call = PreloadedCall {
+                module: module.to_owned(),
+                variable: variable.to_owned(),
+                sig: sig.to_owned(),
+            };
+
+            // Calls given more than once share a handle.
+            if !self.preloaded_calls.contains(&call) {
+                self.preloaded_calls.push(call);
+            }
+        }
+        self
+    }
+
+    /// Interprets the given source in the module once the VM is built.
+    ///
+    /// Bootstrap scripts run in the order they were added, which is useful
+    /// for declaring the Wren side of registered foreign classes. They are
+    /// not run again when the VM is [reset](struct.WrenVm.html#method.reset).
+    ///
+    /// See [`try_build`](#method.try_build) to handle a failing script.
+    pub fn with_bootstrap<M, S>(mut self, module: M, source: S) -> Self
+    where
+        M: Into<String>,
+        S: Into<String>,
+    {
+        self.bootstrap.push((module.into(), source.into()));
+        self
+    }
+
+    /// Backs the VM's heap memory with the given allocator, instead of
+    /// Rust's global allocator.
+    ///
+    /// The allocator is kept when the VM is [reset](struct.WrenVm.html#method.reset).
+    pub fn with_allocator<A>(mut self, allocator: A) -> Self
+    where
+        A: 'static + WrenAllocator,
+    {
+        self.allocator = Some(Box::new(allocator));
+        self
+    }
+
+    /// By default print to stdout.
+    fn default_write_fn() -> Box<dyn Fn(&str) + 'static> {
+        Box::new(|s| print!("{}", s))
+    }
+
+    /// Builds the VM, and runs the bootstrap scripts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a bootstrap script fails. See [`try_build`](#method.try_build).
+    pub fn build(self) -> WrenVm {
+        self.try_build().expect("Bootstrap script failed")
+    }
+
+    /// Builds the VM, and runs the bootstrap scripts added with
+    /// [`with_bootstrap`](#method.with_bootstrap).
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first bootstrap script that fails to
+    /// interpret. The remaining scripts are not run.
+    pub fn try_build(self) -> WrenResult<WrenVm> {
+        // Wren handle pointers that need to be released.
+        let (handle_tx, handle_rx) = channel();
+
         let WrenBuilder {
-            foreign,
+            mut foreign,
             write_fn,
             resolver,
             loader,
+            strict_bindings,
+            resolve_cache,
+            bootstrap,
+            allocator,
+            output,
+            preloaded_calls,
         } = self;
 
-        config.resolveModuleFn = if resolver.is_some() {
-            Some(runtime::resolve_module)
-        } else {
-            None
-        };
-        config.loadModuleFn = if loader.is_some() {
-            Some(runtime::load_module)
+        // Hidden foreign class used to pass closures into Wren.
+        ModuleBuilder {
+            module: CALLBACK_MODULE,
+            class_name: None,
+            foreign: &mut foreign,
+        }
+        .register::<WrenCallback>();
+
+        // Hidden foreign class used to pass iterators into Wren.
+        ModuleBuilder {
+            module: SEQUENCE_MODULE,
+            class_name: None,
+            foreign: &mut foreign,
+        }
+        .register::<WrenSequence>();
+
+        // Output channels are only declared when one is used.
+        let output = if output.log.is_some() || output.warn.is_some() || output.error.is_some() {
+            output::register(&mut ModuleBuilder::new(OUT_MODULE, &mut foreign));
+            Some(output)
         } else {
             None
         };
-        config.reallocateFn = Some(runtime::wren_reallocate);
-        config.writeFn = Some(runtime::write_function);
-        config.errorFn = Some(runtime::error_function);
 
-        let user_data = UserData {
+        let vm = WrenVm::new_raw(UserData {
             foreign,
             handle_tx,
             resolver,
             loader,
+            strict_bindings,
+            resolve_cache: if resolve_cache {
+                Some(RefCell::new(HashMap::new()))
+            } else {
+                None
+            },
+            loaded_modules: HashSet::new(),
             errors: RefCell::new(Vec::new()),
+            symbols: RefCell::new(HashMap::new()),
+            executing: Cell::new(false),
+            #[cfg(any(debug_assertions, feature = "handle-tracking"))]
+            handle_count: Cell::new(0),
             write_fn: write_fn.unwrap_or_else(WrenBuilder::default_write_fn),
+            allocator,
+            output,
+            preloaded_calls,
+            calls: HashMap::new(),
+        });
+
+        let mut vm = WrenVm {
+            vm,
+            handle_rx,
+            retired_rx: Vec::new(),
         };
-        config.userData = Box::into_raw(Box::new(user_data)) as _;
-        config.bindForeignMethodFn = Some(ForeignBindings::bind_foreign_method);
-        config.bindForeignClassFn = Some(ForeignBindings::bind_foreign_class);
+        vm.declare_builtins();
 
-        // WrenVM makes a copy of the configuration. We can
-        // discard our copy after creation.
-        let vm = unsafe { bindings::wrenNewVM(&mut config) };
-        if vm.is_null() {
-            panic!("Unexpected null result when creating WrenVM via C");
+        for (module, source) in bootstrap {
+            vm.interpret(&module, &source)?;
         }
 
-        log::debug!("Created Wren VM: {:?}", vm);
-        WrenVm { vm, handle_rx }
+        Ok(vm)
+    }
+}
+
+/// Write function adapter that assembles chunks of output into whole lines.
+struct LineBuffer<F: Fn(&str)> {
+    buffer: RefCell<String>,
+    write_fn: F,
+}
+
+impl<F: Fn(&str)> LineBuffer<F> {
+    fn write(&self, s: &str) {
+        let mut lines = vec![];
+
+        {
+            let mut buffer = self.buffer.borrow_mut();
+            buffer.push_str(s);
+
+            while let Some(index) = buffer.find('\n') {
+                let mut line: String = buffer.drain(..=index).collect();
+                line.pop();
+                lines.push(line);
+            }
+        }
+
+        // Buffer is released before calling out, in case the
+        // write function ends up writing again.
+        for line in lines {
+            (self.write_fn)(&line);
+        }
+    }
+}
+
+impl<F: Fn(&str)> Drop for LineBuffer<F> {
+    fn drop(&mut self) {
+        let buffer = self.buffer.get_mut();
+        if !buffer.is_empty() {
+            (self.write_fn)(buffer);
+        }
     }
 }
 
@@ -328,6 +1140,8 @@ pub struct WrenContext<'wren> {
     pub(crate) vm: Cell<NonNull<bindings::WrenVM>>,
     /// Channel of Wren handles that need to be released in the VM.
     handle_tx: Sender<*mut bindings::WrenHandle>,
+    /// Slot used to inspect handles, taken on first use.
+    scratch: Cell<Option<usize>>,
     _marker: PhantomData<&'wren bindings::WrenVM>,
 }
 
@@ -339,6 +1153,7 @@ impl<'wren> WrenContext<'wren> {
         WrenContext {
             vm: unsafe { Cell::new(NonNull::new_unchecked(vm)) },
             handle_tx,
+            scratch: Cell::new(None),
             _marker: PhantomData,
         }
     }
@@ -360,14 +1175,49 @@ impl<'wren> WrenContext<'wren> {
         T::get_slot(self, index)
     }
 
+    /// Retrieve a number from a slot, skipping the bounds and type checks
+    /// performed by [`get_slot`](#method.get_slot).
+    ///
+    /// Intended as an escape hatch for tight numeric loops, where the slot
+    /// types have already been validated.
+    ///
+    /// # Safety
+    ///
+    /// The slot index must be within the current slot count, and the slot
+    /// must contain a number. Wren doesn't check either in release builds,
+    /// so violating this reads out of bounds or reinterprets another value.
+    #[inline(always)]
+    pub unsafe fn get_slot_double_unchecked(&self, index: i32) -> f64 {
+        bindings::wrenGetSlotDouble(self.vm_ptr(), index)
+    }
+
+    /// Retrieve a boolean from a slot, skipping the bounds and type checks
+    /// performed by [`get_slot`](#method.get_slot).
+    ///
+    /// # Safety
+    ///
+    /// The slot index must be within the current slot count, and the slot
+    /// must contain a boolean. Wren doesn't check either in release builds,
+    /// so violating this reads out of bounds or reinterprets another value.
+    #[inline(always)]
+    pub unsafe fn get_slot_bool_unchecked(&self, index: i32) -> bool {
+        bindings::wrenGetSlotBool(self.vm_ptr(), index)
+    }
+
+    /// Retrieve a foreign value from the given slot.
+    ///
+    /// The type tag of the foreign value is checked before the pointer is cast.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the slot is out of bounds, does not contain a foreign
+    /// value, or contains a foreign value of a type other than `T`.
     #[inline]
-    pub fn get_foreign_cell<T>(&self, index: i32) -> Option<&'wren WrenCell<T>>
+    pub fn get_foreign_cell<T>(&self, index: i32) -> WrenResult<&'wren WrenCell<T>>
     where
         T: 'static + WrenForeignClass,
     {
-        let foreign_ptr: *mut WrenCell<T> = unsafe { bindings::wrenGetSlotForeign(self.vm_ptr(), index) as _ };
-        let foreign_mut: &mut WrenCell<T> = unsafe { foreign_ptr.as_mut().unwrap() };
-        Some(foreign_mut)
+        <WrenCell<T> as FromWren<'wren>>::get_slot(self, index)
     }
 
     /// Retrieve the current number of slots.
@@ -390,12 +1240,110 @@ impl<'wren> WrenContext<'wren> {
         }
     }
 
+    /// Retrieve the length in bytes of the string in the given slot.
+    ///
+    /// Wren strings are not null terminated, and may contain null bytes.
+    /// The length covers the whole string, including any null bytes.
+    pub fn slot_string_len(&self, slot_num: i32) -> WrenResult<usize> {
+        match self.slot_type(slot_num as usize) {
+            _ if slot_num < 0 => Err(WrenError::SlotOutOfBounds(slot_num)),
+            Some(types::WrenType::String) => {
+                let mut len: c_int = 0;
+                unsafe { bindings::wrenGetSlotBytes(self.vm_ptr(), slot_num, &mut len) };
+                Ok(len as usize)
+            }
+            Some(actual) => Err(WrenError::SlotType {
+                actual,
+                expected: types::WrenType::String,
+            }),
+            None => Err(WrenError::SlotOutOfBounds(slot_num)),
+        }
+    }
+
+    /// Checks whether the given slot contains a number.
+    ///
+    /// Returns `false` when the slot is out of bounds.
+    #[inline]
+    pub fn slot_is_number(&self, slot_num: usize) -> bool {
+        self.slot_type(slot_num) == Some(types::WrenType::Number)
+    }
+
+    /// Checks whether the given slot contains a string.
+    ///
+    /// Returns `false` when the slot is out of bounds.
+    #[inline]
+    pub fn slot_is_string(&self, slot_num: usize) -> bool {
+        self.slot_type(slot_num) == Some(types::WrenType::String)
+    }
+
+    /// Checks whether the given slot contains a foreign object.
+    ///
+    /// Returns `false` when the slot is out of bounds.
+    #[inline]
+    pub fn slot_is_foreign(&self, slot_num: usize) -> bool {
+        self.slot_type(slot_num) == Some(types::WrenType::Foreign)
+    }
+
+    /// Retrieve a number from a slot, coercing a numeric string.
+    ///
+    /// Strings are trimmed of surrounding whitespace, similar to Wren's
+    /// `Num.fromString`, and parsed as an `f64`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the slot is out of bounds, the string can't
+    /// be parsed as a number, or the slot contains any other type.
+    pub fn slot_as_f64(&self, slot_num: usize) -> WrenResult<f64> {
+        match self.slot_type(slot_num) {
+            Some(types::WrenType::Number) => self.get_slot::<f64>(slot_num as i32),
+            Some(types::WrenType::String) => {
+                let value = self.get_slot::<&str>(slot_num as i32)?;
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| WrenError::NotNumeric(value.to_owned()))
+            }
+            Some(actual) => Err(WrenError::SlotType {
+                expected: types::WrenType::Number,
+                actual,
+            }),
+            None => Err(WrenError::SlotOutOfBounds(slot_num as i32)),
+        }
+    }
+
+    /// Slot past the caller's slots, for placing a handle in to inspect it.
+    ///
+    /// Taken from the slot count on first use, and reused while it's the last
+    /// slot, so repeated use doesn't keep growing the slots array. Moved past
+    /// the caller's slots when the caller has grown the slots in the meantime.
+    pub(crate) fn scratch_slot(&self) -> i32 {
+        let count = self.slot_count();
+        let scratch = match self.scratch.get() {
+            Some(scratch) if count <= scratch + 1 => scratch,
+            _ => {
+                self.scratch.set(Some(count));
+                count
+            }
+        };
+
+        // A call into Wren in the meantime may have shrunk the slots.
+        self.ensure_slots(scratch + 1);
+        scratch as i32
+    }
+
     /// Grow the slots array to match the given size.
+    ///
+    /// Returns the slot count from before the call. Wren can't shrink the
+    /// slots array, so the count can't be restored afterwards. Instead, slots
+    /// from the previous count onwards were added by this call, and can be
+    /// used as scratch space without clobbering the caller's slots.
     #[inline]
-    pub fn ensure_slots(&self, slot_size: usize) {
+    pub fn ensure_slots(&self, slot_size: usize) -> usize {
+        let previous = self.slot_count();
         unsafe {
             bindings::wrenEnsureSlots(self.vm_ptr(), slot_size as c_int);
         }
+        previous
     }
 
     /// Retrieves the value of a variable from the top level of module,
@@ -410,8 +1358,49 @@ impl<'wren> WrenContext<'wren> {
     /// - [#601 wrenGetVariable does not seem to return a sane value](https://github.com/wren-lang/wren/issues/601)
     pub fn get_var(&self, module: &str, name: &str) -> WrenResult<WrenRef<'wren>> {
         trace!("get_var({}, {})", module, name);
-        let c_module = CString::new(module).expect("Module name contains a null byte");
-        let c_name = CString::new(name).expect("Name name contains a null byte");
+        self.load_var(module, name, 0)?;
+
+        // If the module or variable don't exist, there's junk in the slot.
+        self.get_slot::<WrenRef<'wren>>(0)
+    }
+
+    /// Retrieves the value of a variable from the top level of a module,
+    /// and converts it to the given type.
+    ///
+    /// Shorthand for getting the variable and then getting it from its slot,
+    /// without creating a handle to the value in between.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either the module or variable don't exist, or
+    /// [`WrenError::SlotType`](../errors/enum.WrenError.html#variant.SlotType)
+    /// if the value is not of the requested type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// # let mut vm = WrenBuilder::new().build();
+    /// vm.interpret("example", "var score = 42").unwrap();
+    ///
+    /// vm.context(|ctx| {
+    ///     assert_eq!(ctx.get_var_as::<f64>("example", "score").unwrap(), 42.0);
+    /// });
+    /// ```
+    pub fn get_var_as<T>(&self, module: &str, name: &str) -> WrenResult<T::Output>
+    where
+        T: FromWren<'wren>,
+    {
+        trace!("get_var_as({}, {})", module, name);
+        self.load_var(module, name, 0)?;
+        self.get_slot::<T>(0)
+    }
+
+    /// Loads a variable from the top level of a module into the given slot,
+    /// checking that it exists first.
+    fn load_var(&self, module: &str, name: &str, slot: i32) -> WrenResult<()> {
+        let c_module = to_c_string(module, "Module name")?;
+        let c_name = to_c_string(name, "Variable name")?;
 
         let module_exists = unsafe { bindings::wrenHasModule(self.vm_ptr(), c_module.as_ptr()) };
         if !module_exists {
@@ -424,15 +1413,92 @@ impl<'wren> WrenContext<'wren> {
         }
         trace!("Module and variable exist {}.{}", module, name);
 
-        self.ensure_slots(1);
+        self.ensure_slots(slot as usize + 1);
 
         unsafe {
-            bindings::wrenGetVariable(self.vm_ptr(), c_module.as_ptr(), c_name.as_ptr(), 0);
+            bindings::wrenGetVariable(self.vm_ptr(), c_module.as_ptr(), c_name.as_ptr(), slot);
         }
-        trace!("Retrieved variable {}.{} of type {:?}", module, name, self.slot_type(0));
+        trace!(
+            "Retrieved variable {}.{} of type {:?}",
+            module,
+            name,
+            self.slot_type(slot as usize)
+        );
+
+        Ok(())
+    }
 
-        // If the module or variable don't exist, there's junk in the slot.
-        self.get_slot::<WrenRef<'wren>>(0)
+    /// Moves a value into Wren as a new instance of its foreign class, and
+    /// returns a handle to it.
+    ///
+    /// The instance is allocated into the given slot. Unlike putting the value,
+    /// which only leaves it in the slot, the handle keeps the instance alive for
+    /// as long as it's held, so a foreign method can create a sibling object
+    /// and keep a reference to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the slot is out of bounds, the foreign class binding
+    /// hasn't been registered, or the class hasn't been declared in Wren.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// vm.context(|ctx| {
+    ///     ctx.ensure_slots(1);
+    ///     let foo = ctx.new_foreign(Foo::new(), 0).unwrap();
+    /// });
+    /// ```
+    pub fn new_foreign<T>(&mut self, value: T, slot: i32) -> WrenResult<WrenRef<'wren>>
+    where
+        T: 'static + WrenForeignClass,
+    {
+        self.put_foreign(value, slot)?;
+        self.get_slot::<WrenRef<'wren>>(slot)
+    }
+
+    /// Moves a value into Wren as a new instance of its foreign class,
+    /// leaving it in the given slot.
+    ///
+    /// Used by the generated `ToWren` implementation of foreign classes.
+    /// Prefer [`new_foreign`](#method.new_foreign) or `ToWren::put`.
+    #[doc(hidden)]
+    pub fn put_foreign<T>(&mut self, value: T, slot: i32) -> WrenResult<()>
+    where
+        T: 'static + WrenForeignClass,
+    {
+        if slot < 0 || slot as usize >= self.slot_count() {
+            return Err(WrenError::SlotOutOfBounds(slot));
+        }
+
+        // To allocate a new foreign object, we must first lookup its class.
+        // The class may be registered under a different name than the type's.
+        let (module_name, class_name) = self
+            .user_data()
+            .ok_or(WrenError::UserDataNull)?
+            .foreign
+            .get_class_key::<T>()
+            .map(|key| (key.module.clone(), key.class.clone()))
+            .ok_or(WrenError::ClassNotRegistered(T::NAME))?;
+
+        // Class declarations are simple variables in Wren. Loading the class
+        // straight into the target slot leaves the other slots, like the
+        // receiver of a foreign method, untouched.
+        self.load_var(&module_name, &class_name, slot)?;
+
+        // All foreign classes are wrapped in WrenCell, because it's possible to
+        // borrow the value out of Wren multiple times.
+        let rust_val = WrenCell::new(value);
+
+        // Wren owns the memory, and the value is moved in without dropping
+        // the uninitialised contents.
+        unsafe {
+            let wren_ptr = bindings::wrenSetSlotNewForeign(self.vm_ptr(), slot, slot, mem::size_of::<WrenCell<T>>())
+                as *mut WrenCell<T>;
+            wren_ptr.write(rust_val);
+        }
+
+        Ok(())
     }
 
     /// Retrieve a list from the top level of the given module.
@@ -452,6 +1518,183 @@ impl<'wren> WrenContext<'wren> {
         Ok(list)
     }
 
+    /// Retrieve a fiber from the top level of the given module.
+    ///
+    /// # Errors
+    ///
+    /// Returns and error when:
+    ///
+    /// - Either the module or varable don't exist.
+    /// - The variable is not a fiber.
+    /// - Wren returned a null pointer as the handle.
+    ///
+    /// The variable is checked to be a fiber with Wren's `is` operator. Wren
+    /// can't be called while it's calling a foreign method, so then the
+    /// variable is only checked to be of an unknown type.
+    pub fn get_fiber(&mut self, module: &str, name: &str) -> WrenResult<WrenFiber> {
+        let fiber = self.get_var_as::<WrenFiber>(module, name)?;
+
+        if !self.is_executing() && !fiber.is_fiber(self)? {
+            return Err(WrenError::ForeignType);
+        }
+
+        Ok(fiber)
+    }
+
+    /// Assigns a value to a variable at the top level of the given module.
+    ///
+    /// Wren has no API for setting module variables, so the value is passed
+    /// through a hidden module and assigned by interpreting a small script in
+    /// the target module. The variable is declared if it doesn't exist yet,
+    /// and the module is created if it hasn't been interpreted yet. Because
+    /// it interprets a script, it fails with `WrenError::ReentrantCall` when
+    /// used while Wren is calling a foreign method.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not a valid Wren variable name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// # let mut vm = WrenBuilder::new().build();
+    /// vm.context(|ctx| {
+    ///     ctx.set_var("example", "answer", 42.0).unwrap();
+    ///     assert!(ctx.has_var("example", "answer"));
+    /// });
+    /// ```
+    pub fn set_var<T: ToWren>(&mut self, module: &str, name: &str, value: T) -> WrenResult<()> {
+        trace!("set_var({}, {})", module, name);
+
+        let mut chars = name.chars();
+        let is_valid = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_valid {
+            return Err(WrenError::InvalidVariableName(name.to_string()));
+        }
+
+        if !self.has_module(SET_VAR_MODULE) {
+            self.interpret(
+                SET_VAR_MODULE,
+                r#"
+                class SetVar {
+                  static value=(value) { __value = value }
+                  static take() {
+                    var value = __value
+                    __value = null
+                    return value
+                  }
+                }
+                "#,
+            )?;
+        }
+
+        let set_var = self.get_var(SET_VAR_MODULE, "SetVar")?.leak()?;
+        let mut func = self.cached_symbol("value=(_)")?;
+        let mut receiver = unsafe { set_var.raw_ptr() };
+        unsafe { wren_call::<_, ()>(self, receiver.as_mut(), func.as_mut(), value)? };
+
+        // Import is scoped to the block, so the helper class doesn't leak into the module.
+        let declare = if self.has_var(module, name) {
+            String::new()
+        } else {
+            format!("var {} = null\n", name)
+        };
+        let source = format!(
+            "{}{{\n  import \"{}\" for SetVar\n  {} = SetVar.take()\n}}\n",
+            declare, SET_VAR_MODULE, name
+        );
+
+        self.interpret(module, &source)
+    }
+
+    /// Retrieves a call handle preloaded with [`WrenBuilder::with_preloaded_calls`](struct.WrenBuilder.html#method.with_preloaded_calls),
+    /// by its module, and its variable and signature, ie. `"Game.update(_)"`.
+    ///
+    /// Returns `None` if the call wasn't preloaded, or its variable hasn't
+    /// been declared by an interpreted script yet.
+    pub fn cached_call(&self, module: &str, name: &str) -> Option<&'wren WrenCallHandle> {
+        // Handles are only added between contexts, so they outlive this one.
+        unsafe { WrenVm::get_user_data(self.vm_ptr()) }.and_then(|userdata| userdata.cached_call(module, name))
+    }
+
+    /// Evaluates a Wren expression, and returns its result as a number.
+    ///
+    /// The expression is parsed and run by Wren, so it follows Wren's own
+    /// number semantics, including hex literals like `0x1F`, scientific
+    /// notation like `1e3`, and arithmetic like `2 + 2`. It's evaluated in a
+    /// hidden module, so it can't see the variables of other modules unless
+    /// it imports them.
+    ///
+    /// The expression is compiled as the body of a function, so statements
+    /// are rejected. It still runs as Wren code, and can call any method, so
+    /// it isn't a sandbox for untrusted input.
+    ///
+    /// # Errors
+    ///
+    /// Returns the compile or runtime error of the expression, or
+    /// [`WrenError::SlotType`](../errors/enum.WrenError.html#variant.SlotType)
+    /// if the result is not a number. Because it interprets a script, it fails
+    /// with [`WrenError::ReentrantCall`](../errors/enum.WrenError.html#variant.ReentrantCall)
+    /// when used while Wren is calling a foreign method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// # let mut vm = WrenBuilder::new().build();
+    /// vm.context(|ctx| {
+    ///     assert_eq!(ctx.eval_number("0x10 * 2").unwrap(), 32.0);
+    /// });
+    /// ```
+    pub fn eval_number(&mut self, expr: &str) -> WrenResult<f64> {
+        trace!("eval_number({})", expr);
+
+        if !self.has_module(EVAL_MODULE) {
+            self.interpret(EVAL_MODULE, "var Result = null")?;
+        }
+
+        // A single line function body only accepts an expression.
+        self.interpret(EVAL_MODULE, &format!("Result = Fn.new {{ {} }}.call()", expr))?;
+        let result = self.get_var_as::<f64>(EVAL_MODULE, "Result");
+
+        // Releases a non-numeric result for garbage collection.
+        self.interpret(EVAL_MODULE, "Result = null")?;
+
+        result
+    }
+
+    /// Checks whether a value is an instance of a class, using Wren's `is` operator.
+    ///
+    /// Unlike [`get_foreign_cell`](#method.get_foreign_cell), this works for any
+    /// Wren class, including built in classes like `Num` and `String`, and takes
+    /// inheritance into account.
+    ///
+    /// # Errors
+    ///
+    /// Returns a runtime error if `class` doesn't refer to a class.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// # let mut vm = WrenBuilder::new().build();
+    /// vm.interpret("example", "var score = 42").unwrap();
+    ///
+    /// vm.context(|ctx| {
+    ///     let score = ctx.get_var("example", "score").unwrap();
+    ///     let num = ctx.get_var("example", "Num").unwrap();
+    ///     assert!(ctx.is_instance_of(&score, &num).unwrap());
+    /// });
+    /// ```
+    pub fn is_instance_of(&mut self, obj: &WrenRef, class: &WrenRef) -> WrenResult<bool> {
+        let mut func = self.cached_symbol("is(_)")?;
+        let mut receiver = unsafe { obj.raw_ptr() };
+
+        unsafe { wren_call::<_, bool>(self, receiver.as_mut(), func.as_mut(), class) }
+    }
+
     /// Checks whether a variable exists.
     ///
     /// # Performance
@@ -471,8 +1714,11 @@ impl<'wren> WrenContext<'wren> {
     /// ```
     pub fn has_var(&self, module: &str, name: &str) -> bool {
         trace!("has_var({}, {})", module, name);
-        let c_module = CString::new(module).expect("Module name contains a null byte");
-        let c_name = CString::new(name).expect("Name name contains a null byte");
+        // Names containing null bytes can't exist in Wren.
+        let (c_module, c_name) = match (to_c_string(module, "Module name"), to_c_string(name, "Variable name")) {
+            (Ok(c_module), Ok(c_name)) => (c_module, c_name),
+            _ => return false,
+        };
 
         let module_exists = unsafe { bindings::wrenHasModule(self.vm_ptr(), c_module.as_ptr()) };
         if !module_exists {
@@ -501,11 +1747,84 @@ impl<'wren> WrenContext<'wren> {
     /// ```
     pub fn has_module(&self, module: &str) -> bool {
         trace!("has_module({})", module);
-        let c_module = CString::new(module).expect("Module name contains a null byte");
+        // Names containing null bytes can't exist in Wren.
+        let c_module = match to_c_string(module, "Module name") {
+            Ok(c_module) => c_module,
+            Err(_) => return false,
+        };
 
         unsafe { bindings::wrenHasModule(self.vm_ptr(), c_module.as_ptr()) }
     }
 
+    /// Checks whether a module's source was provided by the
+    /// [`ModuleLoader`](module/trait.ModuleLoader.html) on import.
+    ///
+    /// Modules created by interpreting source directly, or that haven't
+    /// been imported yet, return `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::{prelude::*, module::InMemoryModuleLoader};
+    /// let mut vm = WrenBuilder::new()
+    ///     .with_module_loader(InMemoryModuleLoader::new().with_module("library", "class Library {}"))
+    ///     .build();
+    /// vm.interpret("main", r#"import "library" for Library"#).unwrap();
+    ///
+    /// vm.context(|ctx| {
+    ///     assert!(ctx.is_module_loaded_via_loader("library"));
+    ///     assert!(!ctx.is_module_loaded_via_loader("main"));
+    /// });
+    /// ```
+    pub fn is_module_loaded_via_loader(&self, module: &str) -> bool {
+        self.user_data()
+            .map(|userdata| userdata.loaded_modules.contains(module))
+            .unwrap_or(false)
+    }
+
+    /// Retrieve the names of all top-level variables in the given module.
+    ///
+    /// Every module implicitly imports the core module, so the result also
+    /// includes core classes like `Object` and `System`.
+    ///
+    /// Uses Wren's optional `meta` module, which is imported into a hidden
+    /// module on first use. Because it calls into Wren, it fails with
+    /// `WrenError::ReentrantCall` when used while Wren is calling a foreign method.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// # let mut vm = WrenBuilder::new().build();
+    /// # vm.interpret("example", r#"var variableName = 0.0"#).expect("Interpret failed");
+    /// vm.context(|ctx| {
+    ///     let names = ctx.module_variables("example").unwrap();
+    ///     assert!(names.iter().any(|name| name == "variableName"));
+    /// });
+    /// ```
+    pub fn module_variables(&mut self, module: &str) -> WrenResult<Vec<String>> {
+        trace!("module_variables({})", module);
+
+        if !self.has_module(module) {
+            return Err(WrenError::ModuleNotFound(module.to_string()));
+        }
+
+        if !self.has_module("meta") {
+            self.interpret(META_IMPORT_MODULE, r#"import "meta" for Meta"#)?;
+        }
+
+        let meta = self.get_var("meta", "Meta")?.leak()?;
+        let mut func = self.cached_symbol("getModuleVariables(_)")?;
+        let mut receiver = unsafe { meta.raw_ptr() };
+        let names = unsafe { wren_call::<_, WrenList>(self, receiver.as_mut(), func.as_mut(), module)? };
+
+        names.to_vec::<String>(self)
+    }
+
     /// Looks up a class or object instance method and returns a call handle reference.
     ///
     /// # Errors
@@ -518,11 +1837,102 @@ impl<'wren> WrenContext<'wren> {
         Ok(WrenCallRef::new(receiver, func))
     }
 
+    /// Compiles a method signature and pairs it with an existing receiver, returning a call
+    /// handle reference.
+    ///
+    /// Unlike [`make_call_ref`](#method.make_call_ref), the receiver is not looked up by
+    /// variable name, so it can be any object the caller already holds, like the result of
+    /// a previous call.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the function signature has an invalid format.
+    pub fn make_call_on(&self, receiver: WrenRef<'wren>, func_sig: &str) -> WrenResult<WrenCallRef<'wren>> {
+        let func = FnSymbolRef::compile(self, func_sig)?;
+        Ok(WrenCallRef::new(receiver, func))
+    }
+
+    /// Calls the method with the given signature on the receiver.
+    ///
+    /// The method's symbol is compiled once, and cached for the life of the VM.
+    /// The receiver and arguments are placed in slots starting at 0, and the
+    /// result is read from slot 0, so values already in those slots are replaced.
+    ///
+    /// # Re-entrancy
+    ///
+    /// Wren doesn't support calling into the VM while it's executing. A call
+    /// made from within a foreign method, or a closure called by Wren, would
+    /// reset the stack of the running fiber. Such calls fail with
+    /// [`WrenError::ReentrantCall`](enum.WrenError.html#variant.ReentrantCall),
+    /// instead of corrupting the VM. Callback driven APIs can store the
+    /// callback, and call it once control has returned to Rust.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// let mut vm = WrenBuilder::new().build();
+    /// vm.interpret("example", "var double = Fn.new {|n| n * 2 }").unwrap();
+    ///
+    /// vm.context(|ctx| {
+    ///     let double = ctx.get_var("example", "double").unwrap();
+    ///     let result = ctx.call_within::<_, f64>(&double, "call(_)", 21.0).unwrap();
+    ///     assert_eq!(result, 42.0);
+    /// });
+    /// ```
+    pub fn call_within<A, R>(&mut self, receiver: &WrenRef, signature: &str, args: A) -> WrenResult<R::Output>
+    where
+        A: ToWren,
+        R: FromWren<'wren>,
+    {
+        let mut func = self.cached_symbol(signature)?;
+        let mut receiver = unsafe { receiver.raw_ptr() };
+
+        unsafe { wren_call::<A, R>(self, receiver.as_mut(), func.as_mut(), args) }
+    }
+
+    /// Whether Wren is executing, which is the case while a foreign method runs.
+    #[inline]
+    pub(crate) fn is_executing(&self) -> bool {
+        self.user_data()
+            .map(|userdata| userdata.executing.get())
+            .unwrap_or(false)
+    }
+
+    /// Retrieve a compiled function symbol from the VM's cache.
+    ///
+    /// The symbol is compiled and cached on first use, and lives
+    /// until the VM is dropped.
+    pub(crate) fn cached_symbol(&self, signature: &str) -> WrenResult<NonNull<bindings::WrenHandle>> {
+        let userdata = self.user_data().ok_or(WrenError::UserDataNull)?;
+
+        if let Some(symbol) = userdata.symbols.borrow().get(signature) {
+            return Ok(unsafe { symbol.raw_ptr() });
+        }
+
+        let symbol = FnSymbolRef::compile(self, signature)?.leak()?;
+        let raw_ptr = unsafe { symbol.raw_ptr() };
+        userdata.symbols.borrow_mut().insert(signature.to_owned(), symbol);
+
+        Ok(raw_ptr)
+    }
+
     /// Retrieve the channel sender for Wren handles that need to be released.
     pub fn destructor_sender(&self) -> Sender<*mut bindings::WrenHandle> {
         self.handle_tx.clone()
     }
 
+    /// Counts a newly created handle towards [`WrenVm::outstanding_handles`](struct.WrenVm.html#method.outstanding_handles).
+    ///
+    /// Must be called once for each handle that will be released through the destructor queue.
+    #[inline]
+    pub(crate) fn record_handle(&self) {
+        #[cfg(any(debug_assertions, feature = "handle-tracking"))]
+        if let Some(userdata) = self.user_data() {
+            userdata.handle_count.set(userdata.handle_count.get() + 1);
+        }
+    }
+
     /// Trigger the VM garbage collector.
     pub fn collect_garbage(&mut self) {
         unsafe {
@@ -530,6 +1940,22 @@ impl<'wren> WrenContext<'wren> {
         }
     }
 
+    /// Trigger the VM garbage collector, and return an estimate of the
+    /// number of bytes it reclaimed.
+    ///
+    /// Wren doesn't expose its own heap size, so the estimate is the
+    /// difference in the allocation registry used for leak tracking. The
+    /// registry is shared by all VMs in the process, so allocations made
+    /// by other threads during the collection skew the figure.
+    ///
+    /// Requires `debug_assertions` or the `alloc-tracking` feature, otherwise
+    /// the garbage is collected and 0 is returned.
+    pub fn collect_garbage_stats(&mut self) -> usize {
+        let before = runtime::allocated_bytes();
+        self.collect_garbage();
+        before.saturating_sub(runtime::allocated_bytes())
+    }
+
     pub fn user_data(&self) -> Option<&UserData> {
         unsafe { WrenVm::get_user_data(self.vm_ptr()).map(|u| &*u) }
     }
@@ -556,6 +1982,69 @@ impl<'wren> WrenContext<'wren> {
     pub fn take_errors(&self, result_id: bindings::WrenInterpretResult) -> WrenResult<()> {
         WrenVm::take_errors(self.vm_ptr(), result_id)
     }
+
+    /// Interpret source into a module from within a context.
+    ///
+    /// Unlike [`WrenVm::interpret`](struct.WrenVm.html#method.interpret), this can be
+    /// used while the VM is borrowed by a context, for example to compile plugin
+    /// scripts from Rust code that only has access to the context.
+    ///
+    /// # Reentrancy
+    ///
+    /// Wren doesn't support interpreting while it's executing. Interpreting from
+    /// inside a foreign method would leave the calling foreign method without
+    /// its slots, so it fails with
+    /// [`WrenError::ReentrantCall`](../errors/enum.WrenError.html#variant.ReentrantCall)
+    /// instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns the compile or runtime error of the interpreted source, or
+    /// [`WrenError::ReentrantCall`](../errors/enum.WrenError.html#variant.ReentrantCall)
+    /// when called from inside a foreign method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// # let mut vm = WrenBuilder::new().build();
+    /// vm.context(|ctx| {
+    ///     ctx.interpret("example", r#"var answer = 42"#).unwrap();
+    ///     assert!(ctx.has_var("example", "answer"));
+    /// });
+    /// ```
+    pub fn interpret(&mut self, module: &str, source: &str) -> WrenResult<()> {
+        trace!("interpret({})", module);
+        let c_module = to_c_string(module, "Module name")?;
+        let c_source = to_c_string(source, "Source")?;
+        let result_id = enter_vm(self.vm_ptr(), || unsafe {
+            bindings::wrenInterpret(self.vm_ptr(), c_module.as_ptr(), c_source.as_ptr())
+        })?;
+        self.take_errors(result_id)
+            .map_err(|err| err.locate_in_source(module, source))
+    }
+}
+
+/// Runs the given call into Wren, marking the VM as executing
+/// for the duration of the call.
+///
+/// Foreign methods are run while the VM is executing. Wren resets the
+/// API stack of the running foreign method when it's entered again,
+/// so calls from within a foreign method fail with
+/// `WrenError::ReentrantCall` instead, without running the given call.
+pub(crate) fn enter_vm<R>(vm: *mut bindings::WrenVM, func: impl FnOnce() -> R) -> WrenResult<R> {
+    let previous = unsafe { WrenVm::get_user_data(vm) }.map(|userdata| userdata.executing.replace(true));
+    if previous == Some(true) {
+        return Err(WrenError::ReentrantCall);
+    }
+
+    let result = func();
+
+    if let (Some(userdata), Some(previous)) = (unsafe { WrenVm::get_user_data(vm) }, previous) {
+        userdata.executing.set(previous);
+    }
+
+    Ok(result)
 }
 
 /// Native functionality that needs to cross the boundary into
@@ -573,20 +2062,76 @@ pub struct UserData {
     pub resolver: Option<Box<dyn ModuleResolver>>,
     /// Loader for providing module source code on import.
     pub loader: Option<Box<dyn ModuleLoader>>,
+    /// How missing foreign bindings are reported. Warns when `None`,
+    /// ignores when `false`, and records an error when `true`.
+    pub strict_bindings: Option<bool>,
+    /// Canonical module names from the resolver, keyed by importing
+    /// module and imported name. Only present when caching is enabled.
+    pub resolve_cache: Option<RefCell<HashMap<(String, String), String>>>,
+    /// Names of the modules whose source was provided by the loader.
+    pub loaded_modules: HashSet<String>,
     /// Queue of errors recorded from VM execution.
     /// Drained and consolidated to build [`WrenError`](../errors/struct.WrenError.html).
     pub errors: RefCell<Vec<WrenVmError>>,
     /// Callback to function that can handle `System.print()` calls
     /// from Wren.
     pub write_fn: Box<dyn Fn(&str)>,
+    /// Cache of compiled function symbols, keyed by signature.
+    pub(crate) symbols: RefCell<HashMap<String, FnSymbol>>,
+    /// Whether Wren is currently executing, called from Rust.
+    pub(crate) executing: Cell<bool>,
+    /// Number of handles created and not yet released.
+    #[cfg(any(debug_assertions, feature = "handle-tracking"))]
+    pub(crate) handle_count: Cell<usize>,
+    /// Allocator for the VM's heap memory. Rust's global allocator is used when `None`.
+    pub(crate) allocator: Option<Box<dyn WrenAllocator>>,
+    /// Functions receiving the output channels of the `Out` class.
+    /// Only present when at least one channel was set.
+    pub(crate) output: Option<OutputFns>,
+    /// Calls to create handles for once their variables are declared.
+    pub(crate) preloaded_calls: Vec<PreloadedCall>,
+    /// Handles of preloaded calls, keyed by module, and then by variable and signature.
+    pub(crate) calls: HashMap<String, HashMap<String, WrenCallHandle>>,
+}
+
+impl UserData {
+    /// Handle of a preloaded call, if it has been created.
+    fn cached_call(&self, module: &str, name: &str) -> Option<&WrenCallHandle> {
+        self.calls.get(module).and_then(|calls| calls.get(name))
+    }
+}
+
+/// Method call preloaded with [`WrenBuilder::with_preloaded_calls`](struct.WrenBuilder.html#method.with_preloaded_calls).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PreloadedCall {
+    module: String,
+    variable: String,
+    sig: String,
+}
+
+impl PreloadedCall {
+    /// Name the call handle is retrieved by.
+    fn name(&self) -> String {
+        format!("{}.{}", self.variable, self.sig)
+    }
 }
 
 pub struct ModuleBuilder<'a> {
     module: &'a str,
+    /// Wren class name overriding the one generated for the type, while registering with [`register_as`](#method.register_as).
+    class_name: Option<String>,
     foreign: &'a mut ForeignBindings,
 }
 
 impl<'a> ModuleBuilder<'a> {
+    pub(crate) fn new(module: &'a str, foreign: &'a mut ForeignBindings) -> Self {
+        ModuleBuilder {
+            module,
+            class_name: None,
+            foreign,
+        }
+    }
+
     pub fn register<T>(&mut self)
     where
         T: WrenForeignClass,
@@ -594,6 +2139,121 @@ impl<'a> ModuleBuilder<'a> {
         T::register(self);
     }
 
+    /// Register a foreign class under a different Wren class name than
+    /// the one given to `wren_class`.
+    ///
+    /// The same type can be registered under multiple names, and in multiple
+    /// modules. When a value of the type is passed to Wren, it is created as
+    /// an instance of the class it was registered as first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// # #[wren_class] struct Foo;
+    /// # #[wren_methods] impl Foo { #[construct] fn new() -> Self { Self } }
+    /// let mut vm = WrenBuilder::new()
+    ///     .with_module("example", |module| {
+    ///         module.register::<Foo>();
+    ///         module.register_as::<Foo>("Bar");
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn register_as<T>(&mut self, class_name: &str)
+    where
+        T: WrenForeignClass,
+    {
+        self.class_name = Some(class_name.to_owned());
+        T::register(self);
+        self.class_name = None;
+    }
+
+    /// Register multiple foreign classes using their registration functions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// # #[wren_class] struct Foo;
+    /// # #[wren_methods] impl Foo { #[construct] fn new() -> Self { Self } }
+    /// # #[wren_class] struct Bar;
+    /// # #[wren_methods] impl Bar { #[construct] fn new() -> Self { Self } }
+    /// let mut vm = WrenBuilder::new()
+    ///     .with_module("example", |module| {
+    ///         module.register_all(&[Foo::register, Bar::register]);
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn register_all(&mut self, registers: &[fn(&mut ModuleBuilder)]) {
+        for register in registers {
+            register(self);
+        }
+    }
+
+    /// Register a foreign method implemented by a closure, without using the
+    /// `wren_methods` macro.
+    ///
+    /// The closure receives the context of the call. Arguments are read from
+    /// slots 1 and up, the receiver is in slot 0, and the return value must be
+    /// put in slot 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics when more than [`MAX_CLOSURE_METHODS`](foreign/constant.MAX_CLOSURE_METHODS.html)
+    /// closures are registered with the VM.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// let mut vm = WrenBuilder::new()
+    ///     .with_module("example", |module| {
+    ///         module.add_closure_method("Plugin", "double(_)", true, |ctx| {
+    ///             let value = ctx.get_slot::<f64>(1).unwrap_or_default();
+    ///             ToWren::put(value * 2.0, ctx, 0);
+    ///         });
+    ///     })
+    ///     .build();
+    ///
+    /// vm.interpret("example", r#"
+    /// class Plugin {
+    ///     foreign static double(value)
+    /// }
+    /// System.print(Plugin.double(21))
+    /// "#).unwrap();
+    /// ```
+    pub fn add_closure_method<S, F>(&mut self, class: S, sig: &str, is_static: bool, func: F)
+    where
+        S: Into<Cow<'a, str>>,
+        F: Fn(&mut WrenContext) + 'static,
+    {
+        let index = self.foreign.closures.len();
+        if index >= MAX_CLOSURE_METHODS {
+            panic!("Cannot register more than {} closure methods", MAX_CLOSURE_METHODS);
+        }
+        self.foreign.closures.push(Box::new(func));
+
+        let class = self.class_name(class);
+        self.add_method_binding(
+            class.clone(),
+            ForeignMethod {
+                is_static,
+                arity: sig.matches('_').count(),
+                sig: sig.to_owned(),
+                func: CLOSURE_TRAMPOLINES[index],
+                doc: None,
+            },
+        );
+
+        let key = ForeignMethodKey {
+            module: self.module.to_owned(),
+            class,
+            sig: sig.to_owned(),
+            is_static,
+        };
+        self.foreign.closure_methods.insert(key, index);
+    }
+
     /// Intended to be used by generated code.
     #[doc(hidden)]
     pub fn add_class_binding<S>(&mut self, class: S, binding: ForeignClass)
@@ -602,12 +2262,14 @@ impl<'a> ModuleBuilder<'a> {
     {
         let key = ForeignClassKey {
             module: self.module.to_owned(),
-            class: class.into().into_owned(),
+            class: self.class_name(class),
         };
         self.foreign.classes.insert(key, binding);
     }
 
     /// Intended to be used by generated code.
+    ///
+    /// The first registered class is the primary, used when passing values to Wren.
     #[doc(hidden)]
     pub fn add_reverse_class_lookup<T>(&mut self)
     where
@@ -615,9 +2277,9 @@ impl<'a> ModuleBuilder<'a> {
     {
         let key = ForeignClassKey {
             module: self.module.to_owned(),
-            class: T::NAME.to_owned(),
+            class: self.class_name(T::NAME),
         };
-        self.foreign.reverse.insert(TypeId::of::<T>(), key);
+        self.foreign.reverse.entry(TypeId::of::<T>()).or_insert(key);
     }
 
     /// Intended to be used by generated code.
@@ -628,10 +2290,62 @@ impl<'a> ModuleBuilder<'a> {
     {
         let key = ForeignMethodKey {
             module: self.module.to_owned(),
-            class: class.into().into_owned(),
+            class: self.class_name(class),
             sig: binding.sig.clone(),
             is_static: binding.is_static,
         };
+        // Replacing a closure method leaves its closure unused.
+        self.foreign.closure_methods.remove(&key);
         self.foreign.methods.insert(key, binding);
     }
+
+    /// Retrieve the method bindings registered for a class in this module,
+    /// for introspection like generating documentation.
+    ///
+    /// Methods are ordered by signature, with instance methods before
+    /// static methods of the same signature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_wren::prelude::*;
+    /// # #[wren_class] #[derive(Default)] struct Foo;
+    /// #[wren_methods]
+    /// impl Foo {
+    ///     /// Says hello.
+    ///     fn hello() -> &'static str { "hello" }
+    /// }
+    ///
+    /// let vm = WrenBuilder::new()
+    ///     .with_module("example", |module| {
+    ///         module.register::<Foo>();
+    ///
+    ///         let methods = module.methods_for("Foo");
+    ///         assert_eq!(methods[0].sig, "hello()");
+    ///         assert_eq!(methods[0].doc, Some("Says hello."));
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn methods_for(&self, class: &str) -> Vec<&ForeignMethod> {
+        let mut methods = self
+            .foreign
+            .methods
+            .iter()
+            .filter(|(key, _)| key.module == self.module && key.class == class)
+            .map(|(_, method)| method)
+            .collect::<Vec<_>>();
+        methods.sort_by(|a, b| (a.sig.as_str(), a.is_static).cmp(&(b.sig.as_str(), b.is_static)));
+        methods
+    }
+
+    /// Class name to register bindings under, taking the override into account.
+    fn class_name<S>(&self, class: S) -> String
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        match &self.class_name {
+            Some(class_name) => class_name.clone(),
+            None => class.into().into_owned(),
+        }
+    }
 }
@@ -0,0 +1,48 @@
+//! Allocation tracking is global to the process, so this is kept
+//! apart from other tests that could be running VMs in parallel.
+use rust_wren::{
+    module::{InMemoryModuleLoader, UnitModuleResolver},
+    prelude::*,
+    troubleshoot::assert_all_deallocated,
+};
+
+/// Every block Wren allocates must be recorded as freed once the VM is dropped.
+#[test]
+fn test_all_deallocated() {
+    let mut vm = WrenBuilder::new()
+        .with_module_resolver(UnitModuleResolver::new())
+        .with_module_loader(InMemoryModuleLoader::new().with_module("shapes", "class Rect {}"))
+        .build();
+
+    vm.interpret(
+        "test_alloc",
+        r#"
+    import "shapes" for Rect
+
+    // Clearing an empty list frees a null pointer.
+    var empty = []
+    for (i in 0...10) {
+      empty.clear()
+    }
+
+    // Growing a list reallocates its buffer.
+    var full = []
+    for (i in 0...100) {
+      full.add(i)
+    }
+    full.clear()
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        let mut list = WrenList::new(ctx);
+        list.push(ctx, 1.0);
+        list.clear(ctx)?;
+        list.clear(ctx)
+    })
+    .expect("Context failed");
+
+    drop(vm);
+    assert_all_deallocated();
+}
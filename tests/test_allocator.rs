@@ -0,0 +1,86 @@
+use rust_wren::{allocator::WrenAllocator, prelude::*};
+use std::{
+    alloc::{alloc, dealloc, realloc, Layout},
+    cell::Cell,
+    os::raw::c_void,
+    rc::Rc,
+};
+
+/// Bytes in front of each block, holding the block size.
+const HEADER: usize = 8;
+
+#[derive(Default)]
+struct Counts {
+    allocs: Cell<usize>,
+    reallocs: Cell<usize>,
+    deallocs: Cell<usize>,
+}
+
+/// Counts calls, and stores the size of each block in a header
+/// so it can be deallocated with the correct layout.
+struct CountingAllocator(Rc<Counts>);
+
+fn layout(size: usize) -> Layout {
+    Layout::from_size_align(size + HEADER, 8).unwrap()
+}
+
+unsafe impl WrenAllocator for CountingAllocator {
+    fn alloc(&self, size: usize) -> *mut c_void {
+        self.0.allocs.set(self.0.allocs.get() + 1);
+        unsafe {
+            let block = alloc(layout(size));
+            if block.is_null() {
+                return block as *mut _;
+            }
+            *(block as *mut usize) = size;
+            block.add(HEADER) as *mut _
+        }
+    }
+
+    unsafe fn realloc(&self, memory: *mut c_void, new_size: usize) -> *mut c_void {
+        self.0.reallocs.set(self.0.reallocs.get() + 1);
+        let block = (memory as *mut u8).sub(HEADER);
+        let size = *(block as *mut usize);
+        let block = realloc(block, layout(size), new_size + HEADER);
+        if block.is_null() {
+            return block as *mut _;
+        }
+        *(block as *mut usize) = new_size;
+        block.add(HEADER) as *mut _
+    }
+
+    unsafe fn dealloc(&self, memory: *mut c_void) {
+        self.0.deallocs.set(self.0.deallocs.get() + 1);
+        let block = (memory as *mut u8).sub(HEADER);
+        let size = *(block as *mut usize);
+        dealloc(block, layout(size));
+    }
+}
+
+#[test]
+fn test_custom_allocator() {
+    let counts = Rc::new(Counts::default());
+
+    let mut vm = WrenBuilder::new()
+        .with_allocator(CountingAllocator(counts.clone()))
+        .build();
+
+    vm.interpret(
+        "test_allocator",
+        r#"
+    var list = []
+    for (i in 0...100) {
+      list.add("item %(i)")
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    assert!(counts.allocs.get() > 0);
+    assert!(counts.reallocs.get() > 0);
+
+    drop(vm);
+
+    // Every block is freed through the same allocator.
+    assert_eq!(counts.allocs.get(), counts.deallocs.get());
+}
@@ -0,0 +1,79 @@
+use rust_wren::{callback::WrenCallback, prelude::*, WrenContext};
+use std::{cell::Cell, rc::Rc};
+
+const API: &str = r#"
+class Api {
+    static callNone(fn) { fn.call() }
+    static callTwo(fn, a, b) { fn.call(a, b) }
+    static callEach(fn, list) {
+        for (item in list) fn.call(item)
+    }
+
+    static store(fn) { __fn = fn }
+    static callStored(a) { __fn.call(a) }
+}
+"#;
+
+/// Wren should be able to call back into a Rust closure.
+#[test]
+fn test_callback() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret("test_callback", API).expect("Interpret failed");
+
+    vm.context(|ctx| {
+        let call_two = ctx.make_call_ref("test_callback", "Api", "callTwo(_,_,_)").unwrap();
+        let add = WrenCallback::new(|ctx: &mut WrenContext| {
+            let a = ctx.get_slot::<f64>(1).unwrap();
+            let b = ctx.get_slot::<f64>(2).unwrap();
+            ToWren::put(a + b, ctx, 0);
+        });
+        let result = call_two.call::<_, f64>(ctx, (add, 3.0, 4.0)).unwrap();
+        assert_eq!(result, 7.0);
+
+        let call_none = ctx.make_call_ref("test_callback", "Api", "callNone(_)").unwrap();
+        let hello = WrenCallback::new(|ctx: &mut WrenContext| ToWren::put("hello", ctx, 0));
+        let result = call_none.call::<_, String>(ctx, hello).unwrap();
+        assert_eq!(result, "hello");
+    });
+}
+
+/// Closures can capture state, and be stored in Wren to be called later.
+#[test]
+fn test_callback_state() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret("test_callback", API).expect("Interpret failed");
+
+    let sum = Rc::new(Cell::new(0.0));
+
+    vm.context(|ctx| {
+        let call_each = ctx.make_call_ref("test_callback", "Api", "callEach(_,_)").unwrap();
+        let mut list = WrenList::new(ctx);
+        for value in &[1.0, 2.0, 3.0] {
+            list.push(ctx, *value);
+        }
+
+        let sum_ref = sum.clone();
+        let accumulate = WrenCallback::new(move |ctx: &mut WrenContext| {
+            sum_ref.set(sum_ref.get() + ctx.get_slot::<f64>(1).unwrap());
+        });
+        call_each.call::<_, ()>(ctx, (accumulate, list)).unwrap();
+    });
+    assert_eq!(sum.get(), 6.0);
+
+    vm.context(|ctx| {
+        let store = ctx.make_call_ref("test_callback", "Api", "store(_)").unwrap();
+        let double = WrenCallback::new(|ctx: &mut WrenContext| {
+            let value = ctx.get_slot::<f64>(1).unwrap();
+            ToWren::put(value * 2.0, ctx, 0);
+        });
+        store.call::<_, ()>(ctx, double).unwrap();
+    });
+
+    vm.interpret(
+        "test_callback",
+        r#"
+    if (Api.callStored(21) != 42) Fiber.abort("Unexpected stored callback result")
+    "#,
+    )
+    .expect("Interpret failed");
+}
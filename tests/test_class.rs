@@ -1,4 +1,5 @@
-use rust_wren::prelude::*;
+use rust_wren::{class::ForeignRef, handle::WrenHandle, prelude::*, WrenContext, WrenError};
+use std::rc::Rc;
 
 #[wren_class]
 #[derive(Debug, Clone, Copy)]
@@ -124,6 +125,50 @@ fn test_wren_class() {
     println!("After vm drop");
 }
 
+/// Generated declaration should match the hand written one.
+#[test]
+fn test_wren_declaration() {
+    let decl = <Vector2 as WrenForeignClass>::wren_declaration();
+    assert_eq!(
+        decl,
+        r#"foreign class Vector2 {
+    construct new(x, y) {}
+    foreign x()
+    foreign y()
+    foreign static zero()
+    foreign static test()
+    foreign static fooBar()
+    foreign static fooBar(a)
+    foreign static fooBar(a, b)
+    foreign magnitude()
+    foreign dot(rhs)
+    foreign x
+    foreign x=(value)
+    foreign y
+    foreign y=(value)
+}
+"#
+    );
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test", |m| {
+            m.register::<Vector2>();
+        })
+        .build();
+    vm.interpret("test", &decl).expect("Interpret error");
+    vm.interpret(
+        "test",
+        r#"
+            var a = Vector2.new(7.0, 11.0)
+            a.x = 3
+            if (a.x != 3 || a.dot(Vector2.new(1, 0)) != 3) {
+                Fiber.abort("Unexpected Vector2(%(a.x), %(a.y))")
+            }
+    "#,
+    )
+    .expect("Interpret error");
+}
+
 #[test]
 fn test_property() {
     let mut vm = WrenBuilder::new()
@@ -154,3 +199,940 @@ fn test_property() {
     )
     .expect("Interpret error");
 }
+
+#[wren_class]
+struct Foo;
+
+#[wren_methods]
+impl Foo {
+    #[construct]
+    fn new() -> Self {
+        Foo
+    }
+
+    fn value() -> f64 {
+        1.0
+    }
+}
+
+#[wren_class]
+struct Bar;
+
+#[wren_methods]
+impl Bar {
+    #[construct]
+    fn new() -> Self {
+        Bar
+    }
+
+    fn value() -> f64 {
+        2.0
+    }
+}
+
+const FOO_BAR: &str = r#"
+foreign class Foo {
+    construct new() {}
+    foreign static value()
+}
+
+foreign class Bar {
+    construct new() {}
+    foreign static value()
+}
+"#;
+
+#[test]
+fn test_register_multiple() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_macro", |m| {
+            register_classes!(m, Vector2, Foo, Bar);
+        })
+        .with_module("test_slice", |m| {
+            m.register_all(&[Vector2::register, Foo::register, Bar::register]);
+        })
+        .build();
+
+    for module in &["test_macro", "test_slice"] {
+        vm.interpret(module, VECTOR).expect("Interpret error");
+        vm.interpret(module, FOO_BAR).expect("Interpret error");
+        vm.interpret(
+            module,
+            r#"
+        var a = Vector2.new(3, 4)
+        var b = Foo.new()
+        var c = Bar.new()
+        if (a.magnitude() != 5 || Foo.value() != 1 || Bar.value() != 2) {
+            Fiber.abort("Unexpected foreign call results")
+        }
+        "#,
+        )
+        .expect("Interpret error");
+    }
+}
+
+#[wren_class]
+struct Counter(f64);
+
+#[wren_methods]
+impl Counter {
+    #[construct]
+    fn new() -> Self {
+        Counter(0.0)
+    }
+
+    fn add(&mut self, #[ctx] ctx: &mut WrenContext, value: f64) -> rust_wren::Result<ForeignRef<Self>> {
+        self.0 += value;
+        ForeignRef::receiver(ctx).map_err(|err| foreign_error!(err))
+    }
+
+    fn total(&self) -> f64 {
+        self.0
+    }
+}
+
+/// Should return references to existing foreign objects instead of copies.
+#[test]
+fn test_foreign_ref() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test", |m| {
+            register_classes!(m, Counter, Foo);
+        })
+        .build();
+
+    vm.interpret("test", &Counter::wren_declaration())
+        .expect("Interpret error");
+    vm.interpret("test", &Foo::wren_declaration()).expect("Interpret error");
+    vm.interpret(
+        "test",
+        r#"
+        var counter = Counter.new()
+        var same = counter.add(2).add(3)
+        if (!(same == counter) || counter.total() != 5) {
+            Fiber.abort("Expected the same counter")
+        }
+        var foo = Foo.new()
+        "#,
+    )
+    .expect("Interpret error");
+
+    vm.context_result(|ctx| {
+        ctx.get_var("test", "counter")?;
+        let counter = ctx.get_slot::<ForeignRef<Counter>>(0)?;
+        assert_eq!(counter.cell(ctx)?.borrow().0, 5.0);
+
+        ctx.get_var("test", "foo")?;
+        assert!(matches!(
+            ctx.get_slot::<ForeignRef<Counter>>(0),
+            Err(WrenError::ForeignType)
+        ));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Should register the same type under multiple Wren class names.
+#[test]
+fn test_register_as() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_alias", |m| {
+            m.register_as::<Vector2>("Vec2");
+            m.register::<Vector2>();
+        })
+        .with_module("test_other", |m| {
+            m.register_as::<Vector2>("Point");
+        })
+        .build();
+
+    vm.interpret("test_alias", VECTOR).expect("Interpret error");
+    vm.interpret("test_alias", &VECTOR.replace("Vector2", "Vec2"))
+        .expect("Interpret error");
+    vm.interpret("test_other", &VECTOR.replace("Vector2", "Point"))
+        .expect("Interpret error");
+
+    vm.interpret(
+        "test_alias",
+        r#"
+        var a = Vec2.new(3, 4)
+        var b = Vector2.new(1, 0)
+        if (a.magnitude() != 5 || a.dot(b) != 3) {
+            Fiber.abort("Unexpected foreign call results")
+        }
+
+        // Returned values are instances of the primary class.
+        if (!(Vector2.zero() is Vec2)) {
+            Fiber.abort("Expected primary class Vec2")
+        }
+        "#,
+    )
+    .expect("Interpret error");
+
+    vm.interpret(
+        "test_other",
+        r#"
+        var p = Point.new(6, 8)
+        if (p.magnitude() != 10) {
+            Fiber.abort("Unexpected foreign call results")
+        }
+        "#,
+    )
+    .expect("Interpret error");
+}
+
+/// Closures should be callable as foreign methods, each through its own binding.
+#[test]
+fn test_closure_method() {
+    use std::{cell::Cell, rc::Rc};
+
+    let calls = Rc::new(Cell::new(0));
+    let calls_add = calls.clone();
+    let calls_greet = calls.clone();
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_closure", |m| {
+            m.add_closure_method("Plugin", "add(_,_)", true, move |ctx| {
+                calls_add.set(calls_add.get() + 1);
+                let a = ctx.get_slot::<f64>(1).unwrap();
+                let b = ctx.get_slot::<f64>(2).unwrap();
+                ToWren::put(a + b, ctx, 0);
+            });
+            m.add_closure_method("Plugin", "greet(_)", false, move |ctx| {
+                calls_greet.set(calls_greet.get() + 1);
+                let name = ctx.get_slot::<String>(1).unwrap();
+                ToWren::put(format!("Hello, {}", name), ctx, 0);
+            });
+        })
+        .build();
+
+    vm.interpret(
+        "test_closure",
+        r#"
+    class Plugin {
+        construct new() {}
+        foreign static add(a, b)
+        foreign greet(name)
+    }
+
+    if (Plugin.add(3, 4) != 7) {
+        Fiber.abort("Unexpected sum")
+    }
+    var greeting = Plugin.new().greet("Wren")
+    if (greeting != "Hello, Wren") {
+        Fiber.abort("Unexpected greeting %(greeting)")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+
+    assert_eq!(calls.get(), 2);
+}
+
+/// Bindings from separate registries should be combined, keeping closures apart.
+#[test]
+fn test_merge_foreign() {
+    use rust_wren::foreign::ForeignBindings;
+
+    let library = ForeignBindings::new().with_module("test_library", |m| {
+        m.register::<Vector2>();
+        m.add_closure_method("Library", "name()", true, |ctx| {
+            ToWren::put("library", ctx, 0);
+        });
+    });
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_app", |m| {
+            m.add_closure_method("App", "name()", true, |ctx| {
+                ToWren::put("app", ctx, 0);
+            });
+        })
+        .merge_foreign(library)
+        .build();
+
+    vm.interpret("test_library", VECTOR).expect("Interpret error");
+    vm.interpret(
+        "test_library",
+        r#"
+    class Library {
+        foreign static name()
+    }
+
+    if (Vector2.new(3, 4).magnitude() != 5) {
+        Fiber.abort("Unexpected magnitude")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+
+    vm.interpret(
+        "test_app",
+        r#"
+    import "test_library" for Library
+
+    class App {
+        foreign static name()
+    }
+
+    if (App.name() != "app") {
+        Fiber.abort("Unexpected app name %(App.name())")
+    }
+    if (Library.name() != "library") {
+        Fiber.abort("Unexpected library name %(Library.name())")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+}
+
+/// Method names given as string literals can be Rust keywords.
+#[test]
+fn test_string_method_name() {
+    #[wren_class]
+    struct Token;
+
+    #[wren_methods]
+    impl Token {
+        #[construct]
+        fn new() -> Self {
+            Token
+        }
+
+        #[method(name = "type")]
+        fn kind(&self) -> &'static str {
+            "identifier"
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Token>();
+        })
+        .build();
+
+    vm.interpret("test_class", &Token::wren_declaration())
+        .expect("Interpret error");
+    vm.interpret(
+        "test_class",
+        r#"
+    var kind = Token.new().type()
+    if (kind != "identifier") {
+        Fiber.abort("Unexpected token type %(kind)")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+}
+
+/// Constructors returning a `Result` should abort the fiber on failure.
+#[test]
+fn test_fallible_construct() {
+    #[wren_class]
+    #[derive(Debug)]
+    struct Color(u8, u8, u8);
+
+    #[wren_methods]
+    impl Color {
+        #[construct]
+        fn new(r: f64, g: f64, b: f64) -> rust_wren::Result<Self> {
+            let channel = |value: f64| {
+                if (0.0..=255.0).contains(&value) {
+                    Ok(value as u8)
+                } else {
+                    Err(foreign_error!(WrenError::NumberRange { value, target: "u8" }))
+                }
+            };
+
+            Ok(Color(channel(r)?, channel(g)?, channel(b)?))
+        }
+
+        fn total(&self) -> u32 {
+            self.0 as u32 + self.1 as u32 + self.2 as u32
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Color>();
+        })
+        .build();
+
+    vm.interpret("test_class", &Color::wren_declaration())
+        .expect("Interpret error");
+    vm.interpret(
+        "test_class",
+        r#"
+    if (Color.new(255, 128, 0).total() != 383) {
+        Fiber.abort("Unexpected channel total")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+
+    match vm.interpret("test_class", "var bad = Color.new(0, 256, 0)") {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => assert!(matches!(
+            foreign.downcast_ref::<WrenError>(),
+            Some(WrenError::NumberRange { value, target: "u8" }) if *value == 256.0
+        )),
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    // The failed instance was never allocated, so collecting garbage is safe.
+    vm.context(|ctx| ctx.collect_garbage());
+}
+
+/// Constructors returning a `Result` with an explicit `ForeignError`
+/// should fail with a runtime error, instead of evaluating to the error.
+#[test]
+fn test_fallible_construct_foreign_error() {
+    #[wren_class]
+    struct Locked;
+
+    #[wren_methods]
+    impl Locked {
+        #[construct]
+        fn new() -> Result<Self, rust_wren::ForeignError> {
+            Err(foreign_error!(WrenError::ForeignType))
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Locked>();
+        })
+        .build();
+
+    vm.interpret("test_class", &Locked::wren_declaration())
+        .expect("Interpret error");
+
+    match vm.interpret("test_class", "var locked = Locked.new()") {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => assert!(matches!(
+            foreign.downcast_ref::<WrenError>(),
+            Some(WrenError::ForeignType)
+        )),
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+/// Optional foreign classes should be returned as a new instance or null.
+#[test]
+fn test_return_option() {
+    #[wren_class]
+    struct Item {
+        weight: f64,
+    }
+
+    #[wren_methods]
+    impl Item {
+        #[construct]
+        fn new(weight: f64) -> Self {
+            Item { weight }
+        }
+
+        fn find(weight: f64) -> Option<Item> {
+            if weight > 0.0 {
+                Some(Item { weight })
+            } else {
+                None
+            }
+        }
+
+        fn weight(&self) -> f64 {
+            self.weight
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Item>();
+        })
+        .build();
+
+    vm.interpret("test_class", &Item::wren_declaration())
+        .expect("Interpret error");
+    vm.interpret(
+        "test_class",
+        r#"
+    var found = Item.find(3)
+    if (!(found is Item) || found.weight() != 3) {
+        Fiber.abort("Expected an item, found %(found)")
+    }
+
+    var missing = Item.find(0)
+    if (missing != null) {
+        Fiber.abort("Expected null, found %(missing)")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+}
+
+/// Associated consts marked `#[wren_const]` should be readable from Wren.
+#[test]
+fn test_wren_const() {
+    #[wren_class]
+    struct Limits;
+
+    #[wren_methods]
+    impl Limits {
+        #[construct]
+        fn new() -> Self {
+            Limits
+        }
+
+        #[wren_const]
+        const MAX: f64 = 100.0;
+
+        #[wren_const(name = unit)]
+        const UNIT: &'static str = "meters";
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Limits>();
+        })
+        .build();
+
+    vm.interpret("test_class", &Limits::wren_declaration())
+        .expect("Interpret error");
+    vm.interpret(
+        "test_class",
+        r#"
+    if (Limits.MAX != 100 || Limits.unit != "meters") {
+        Fiber.abort("Unexpected consts %(Limits.MAX) %(Limits.unit)")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+
+    // Consts remain usable from Rust.
+    assert_eq!(Limits::MAX, 100.0);
+}
+
+/// Classes without a constructor should be constructed from their `Default`.
+#[test]
+fn test_default_construct() {
+    #[wren_class]
+    #[derive(Default)]
+    struct Tally {
+        count: f64,
+    }
+
+    #[wren_methods]
+    impl Tally {
+        fn increment(&mut self) -> f64 {
+            self.count += 1.0;
+            self.count
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Tally>();
+        })
+        .build();
+
+    vm.interpret("test_class", &Tally::wren_declaration())
+        .expect("Interpret error");
+    vm.interpret(
+        "test_class",
+        r#"
+    var tally = Tally.new()
+    tally.increment()
+    if (tally.increment() != 2) {
+        Fiber.abort("Unexpected tally")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+}
+
+/// Bootstrap scripts should declare classes as part of building the VM.
+#[test]
+fn test_bootstrap() {
+    #[wren_class]
+    struct Greeter;
+
+    #[wren_methods]
+    impl Greeter {
+        #[construct]
+        fn new() -> Self {
+            Greeter
+        }
+
+        fn greet(&self, name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Greeter>();
+        })
+        .with_bootstrap("test_class", Greeter::wren_declaration())
+        .with_bootstrap("test_class", r#"var greeter = Greeter.new()"#)
+        .try_build()
+        .expect("Bootstrap failed");
+
+    vm.interpret(
+        "test_class",
+        r#"
+    if (greeter.greet("Wren") != "Hello, Wren!") {
+        Fiber.abort("Unexpected greeting")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+
+    let result = WrenBuilder::new().with_bootstrap("test_class", "var = 1").try_build();
+    assert!(matches!(result, Err(WrenError::CompileError(_))));
+}
+
+/// Foreign methods should be able to create new instances and keep a handle to them.
+#[test]
+fn test_new_foreign() {
+    #[wren_class]
+    struct Node {
+        value: f64,
+        next: Option<Rc<WrenHandle>>,
+    }
+
+    #[wren_methods]
+    impl Node {
+        #[construct]
+        fn new(value: f64) -> Self {
+            Node { value, next: None }
+        }
+
+        fn link(&mut self, #[ctx] ctx: &mut WrenContext, value: f64) -> rust_wren::Result<()> {
+            // Slot 0 holds the receiver, so the new instance goes into slot 1.
+            ctx.ensure_slots(2);
+            let next = ctx
+                .new_foreign(Node::new(value), 1)
+                .and_then(|next| next.leak())
+                .map_err(|err| foreign_error!(err))?;
+            self.next = Some(Rc::new(next));
+            Ok(())
+        }
+
+        fn next(&self) -> Option<Rc<WrenHandle>> {
+            self.next.clone()
+        }
+
+        fn value(&self) -> f64 {
+            self.value
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Node>();
+        })
+        .build();
+
+    vm.interpret("test_class", &Node::wren_declaration())
+        .expect("Interpret error");
+    vm.interpret(
+        "test_class",
+        r#"
+    var head = Node.new(1)
+    head.link(2)
+    System.gc()
+    var next = head.next()
+    if (!(next is Node) || next.value() != 2 || head.value() != 1) {
+        Fiber.abort("Unexpected linked node")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+
+    vm.context(|ctx| {
+        ctx.ensure_slots(1);
+        let node = ctx.new_foreign(Node::new(3.0), 0).expect("New foreign failed");
+        let class = ctx.get_var("test_class", "Node").expect("Get class failed");
+        assert!(ctx.is_instance_of(&node, &class).expect("Instance check failed"));
+    });
+}
+
+/// Methods marked `#[manual_return]` put their own result into slot 0.
+#[test]
+fn test_manual_return() {
+    #[wren_class]
+    struct Entry {
+        name: String,
+        count: f64,
+    }
+
+    #[wren_methods]
+    impl Entry {
+        #[construct]
+        fn new(name: String, count: f64) -> Self {
+            Entry { name, count }
+        }
+
+        #[manual_return]
+        fn pair(&self, #[ctx] ctx: &mut WrenContext) {
+            // The list replaces the receiver in slot 0.
+            let (name, count) = (self.name.clone(), self.count);
+            let mut list = WrenList::new(ctx);
+            list.push(ctx, name);
+            list.push(ctx, count);
+            ctx.ensure_slots(1);
+            ToWren::put(list, ctx, 0);
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Entry>();
+        })
+        .build();
+
+    vm.interpret("test_class", &Entry::wren_declaration())
+        .expect("Interpret error");
+    vm.interpret(
+        "test_class",
+        r#"
+    var pair = Entry.new("apples", 3).pair()
+    if (pair.count != 2 || pair[0] != "apples" || pair[1] != 3) {
+        Fiber.abort("Unexpected pair %(pair)")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+}
+
+/// Foreign classes should be able to provide their own string conversion.
+#[test]
+fn test_to_string() {
+    use std::fmt;
+
+    #[wren_class]
+    struct Point(f64, f64);
+
+    #[wren_methods]
+    impl Point {
+        #[construct]
+        fn new(x: f64, y: f64) -> Self {
+            Point(x, y)
+        }
+
+        #[to_string]
+        fn to_wren_string(&self) -> String {
+            self.to_string()
+        }
+    }
+
+    impl fmt::Display for Point {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "({}, {})", self.0, self.1)
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_to_string", |m| {
+            m.register::<Point>();
+        })
+        .build();
+
+    assert!(Point::wren_declaration().contains("    foreign toString\n"));
+
+    vm.interpret("test_to_string", &Point::wren_declaration())
+        .expect("Interpret error");
+    vm.interpret(
+        "test_to_string",
+        r#"
+        var point = Point.new(1.5, 2)
+        var text = "point %(point)"
+        if (text != "point (1.5, 2)") {
+            Fiber.abort("Unexpected interpolation %(text)")
+        }
+        if (point.toString != "(1.5, 2)") {
+            Fiber.abort("Unexpected toString %(point.toString)")
+        }
+        "#,
+    )
+    .expect("Interpret error");
+}
+
+wren_module! {
+    mod shapes {
+        use rust_wren::prelude::*;
+
+        #[wren_class]
+        #[derive(Debug)]
+        pub struct Rect {
+            #[getset]
+            width: f64,
+            #[get]
+            height: f64,
+        }
+
+        #[wren_methods]
+        impl Rect {
+            #[construct]
+            fn new(width: f64, height: f64) -> Self {
+                Rect { width, height }
+            }
+
+            fn area(&self) -> f64 {
+                self.width * self.height
+            }
+
+            #[method(name = square)]
+            fn square(size: f64) -> Rect {
+                Rect {
+                    width: size,
+                    height: size,
+                }
+            }
+        }
+
+        #[wren_class(name = Circle)]
+        #[derive(Debug)]
+        pub struct Round {
+            #[get]
+            radius: f64,
+        }
+
+        #[wren_methods]
+        impl Round {
+            #[construct]
+            fn new(radius: f64) -> Self {
+                Round { radius }
+            }
+        }
+    }
+}
+
+/// Module macro should generate the registration and declaration of its classes together.
+#[test]
+fn test_wren_module() {
+    assert_eq!(
+        shapes::DECLARATION,
+        format!(
+            "{}{}",
+            <shapes::Rect as WrenForeignClass>::wren_declaration(),
+            <shapes::Round as WrenForeignClass>::wren_declaration()
+        )
+    );
+
+    let mut vm = WrenBuilder::new().with_module("shapes", shapes::register).build();
+    vm.interpret("shapes", shapes::DECLARATION).expect("Interpret failed");
+    vm.interpret("test", include_str!("test.wren"))
+        .expect("Interpret failed");
+
+    vm.interpret(
+        "shapes",
+        r#"
+    import "test" for Test
+
+    var rect = Rect.new(2, 3)
+    Test.assertEq(rect.area(), 6, "Rect.area()")
+    rect.width = 4
+    Test.assertEq(rect.area(), 12, "Rect.area()")
+    Test.assertEq(Rect.square(5).height, 5, "Rect.square(_)")
+    Test.assertEq(Circle.new(7).radius, 7, "Circle.radius")
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+/// Methods compiled out by `#[cfg]` should not be bound or declared.
+#[test]
+fn test_cfg_method() {
+    #[wren_class]
+    #[derive(Default)]
+    struct Build;
+
+    #[wren_methods]
+    impl Build {
+        #[cfg(feature = "send")]
+        fn threading() -> String {
+            "send".to_owned()
+        }
+
+        #[cfg(not(feature = "send"))]
+        fn threading() -> String {
+            "local".to_owned()
+        }
+
+        #[cfg(feature = "send")]
+        fn share(&self) -> bool {
+            true
+        }
+    }
+
+    let declaration = Build::wren_declaration();
+    assert_eq!(declaration.contains("share"), cfg!(feature = "send"));
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Build>();
+        })
+        .with_bootstrap("test_class", declaration)
+        .build();
+
+    let expected = if cfg!(feature = "send") { "send" } else { "local" };
+    vm.interpret(
+        "test_class",
+        &format!(
+            r#"
+    if (Build.threading() != "{}") {{
+        Fiber.abort("Unexpected build %(Build.threading())")
+    }}
+    "#,
+            expected
+        ),
+    )
+    .expect("Interpret failed");
+}
+
+/// Method bindings should be enumerable along with their doc comments.
+#[test]
+fn test_methods_for() {
+    #[wren_class]
+    #[derive(Default)]
+    struct Documented;
+
+    #[wren_methods]
+    impl Documented {
+        /// Adds two numbers.
+        ///
+        /// Both must be finite.
+        fn add(a: f64, b: f64) -> f64 {
+            a + b
+        }
+
+        fn undocumented(&self) {}
+
+        /// Largest accepted number.
+        #[wren_const]
+        const MAX: f64 = 100.0;
+    }
+
+    let _vm = WrenBuilder::new()
+        .with_module("test_class", |m| {
+            m.register::<Documented>();
+
+            let methods = m
+                .methods_for("Documented")
+                .into_iter()
+                .map(|method| (method.sig.as_str(), method.is_static, method.doc))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                methods,
+                vec![
+                    ("MAX", true, Some("Largest accepted number.")),
+                    ("add(_,_)", true, Some("Adds two numbers.\n\nBoth must be finite.")),
+                    ("undocumented()", false, None),
+                ]
+            );
+
+            assert!(m.methods_for("Missing").is_empty());
+        })
+        .build();
+}
@@ -1,5 +1,5 @@
-use rust_wren::{prelude::*, WrenError, WrenResult};
-use std::{cell::RefCell, error::Error};
+use rust_wren::{prelude::*, WrenContext, WrenError, WrenResult, WrenType};
+use std::{cell::RefCell, error::Error, rc::Rc};
 
 /// Should check whether a variable exists or not.
 #[test]
@@ -85,6 +85,148 @@ fn test_variable_foreign_type() {
     });
 }
 
+/// Should discard all modules, while keeping foreign bindings.
+#[test]
+fn test_reset() {
+    #[wren_class]
+    struct Foo(u32);
+
+    #[wren_methods]
+    impl Foo {
+        #[construct]
+        fn new(val: u32) -> Self {
+            Self(val)
+        }
+
+        fn val(&self) -> u32 {
+            self.0
+        }
+    }
+
+    const FOO: &str = r#"
+    foreign class Foo {
+      construct new(val) {}
+      foreign val()
+    }
+    var foo = Foo.new(7)
+    "#;
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_context", |module| {
+            module.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret("test_context", FOO).expect("Interpret failed");
+
+    let mut handle = None;
+    vm.context(|ctx| {
+        handle = Some(ctx.get_var("test_context", "foo").unwrap().leak().unwrap());
+    });
+
+    vm.reset();
+
+    // Handle from the old VM must not be released into the new VM.
+    drop(handle);
+
+    vm.context(|ctx| {
+        assert!(!ctx.has_module("test_context"));
+    });
+
+    // Foreign bindings survive the reset.
+    vm.interpret("test_context", FOO).expect("Interpret failed");
+    vm.interpret(
+        "test_context",
+        r#"
+    if (foo.val() != 7) {
+        Fiber.abort("Unexpected value %(foo.val())")
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+/// Should interpret scripts while the VM is borrowed by a context.
+#[test]
+fn test_context_interpret() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.context_result(|ctx| {
+        ctx.interpret(
+            "test_context",
+            r#"
+        class Plugin {
+          static name { "plugin" }
+        }
+        "#,
+        )?;
+
+        let name = ctx.make_call_ref("test_context", "Plugin", "name")?;
+        assert_eq!(name.call::<_, String>(ctx, ())?, "plugin");
+
+        assert!(matches!(
+            ctx.interpret("test_context", "var broken = ("),
+            Err(WrenError::CompileError(_))
+        ));
+        assert!(matches!(
+            ctx.interpret("test_context", r#"Fiber.abort("failed")"#),
+            Err(WrenError::RuntimeError { .. })
+        ));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Interpreting from within a foreign method must fail cleanly,
+/// because Wren can't be re-entered while it's executing.
+#[test]
+fn test_context_interpret_foreign_method() {
+    #[wren_class]
+    struct Plugins;
+
+    #[wren_methods]
+    impl Plugins {
+        #[construct]
+        fn new() -> Self {
+            Plugins
+        }
+
+        fn load(#[ctx] ctx: &mut WrenContext) -> rust_wren::Result<bool> {
+            match ctx.interpret("test_context", "var loaded = true") {
+                Err(WrenError::ReentrantCall) => Ok(false),
+                result => result.map(|_| true).map_err(|err| foreign_error!(err)),
+            }
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_context", |module| {
+            module.register::<Plugins>();
+        })
+        .build();
+
+    vm.interpret(
+        "test_context",
+        r#"
+    foreign class Plugins {
+        construct new() {}
+        foreign static load()
+    }
+
+    var result = Plugins.load()
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        assert!(!ctx.get_var_as::<bool>("test_context", "result")?);
+        assert!(!ctx.has_var("test_context", "loaded"));
+        Ok(())
+    })
+    .unwrap();
+}
+
 #[test]
 fn test_has_module() {
     let mut vm = WrenBuilder::new().build();
@@ -97,6 +239,36 @@ fn test_has_module() {
     });
 }
 
+/// Should list the top-level variables of a module.
+#[test]
+fn test_module_variables() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_context",
+        r#"
+    var first = 1
+    class Second {}
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context(|ctx| {
+        let names = ctx.module_variables("test_context").expect("Module variables failed");
+        assert!(names.iter().any(|name| name == "first"));
+        assert!(names.iter().any(|name| name == "Second"));
+        assert!(!names.iter().any(|name| name == "doesNotExist"));
+
+        // Subsequent calls reuse the imported meta module.
+        assert_eq!(ctx.module_variables("test_context").unwrap(), names);
+
+        assert!(matches!(
+            ctx.module_variables("does_not_exist"),
+            Err(WrenError::ModuleNotFound(_))
+        ));
+    });
+}
+
 #[test]
 fn test_write_fn() {
     thread_local! {static CALL_COUNT: RefCell<usize> = RefCell::new(0); }
@@ -126,6 +298,78 @@ fn test_write_fn() {
     });
 }
 
+/// Captured output should accumulate everything printed, newlines included.
+#[test]
+fn test_capture_output() {
+    let (builder, output) = WrenBuilder::new().capture_output();
+    let mut vm = builder.build();
+
+    vm.interpret(
+        "test_context",
+        r#"
+    System.print("a")
+    System.write("b")
+    System.print(1 + 2)
+    "#,
+    )
+    .expect("Interpret failed");
+
+    assert_eq!(output.borrow().as_str(), "a\nb3\n");
+}
+
+#[test]
+fn test_line_buffered_write_fn() {
+    let lines = Rc::new(RefCell::new(Vec::<String>::new()));
+    let lines_write = lines.clone();
+
+    let mut vm = WrenBuilder::new()
+        .with_line_buffered_write_fn(move |s| lines_write.borrow_mut().push(s.to_string()))
+        .build();
+
+    vm.interpret(
+        "test_context",
+        r#"
+    System.print("a")
+    System.write("b")
+    System.write("c")
+    System.print("d")
+    System.print([1, 2])
+    System.write("unfinished")
+    "#,
+    )
+    .expect("Interpret failed");
+
+    assert_eq!(*lines.borrow(), vec!["a", "bcd", "[1, 2]"]);
+
+    // Incomplete line is flushed when the VM is dropped.
+    drop(vm);
+    assert_eq!(*lines.borrow(), vec!["a", "bcd", "[1, 2]", "unfinished"]);
+}
+
+/// Output should go to whichever write function is set at the time.
+#[test]
+fn test_set_write_fn() {
+    let first = Rc::new(RefCell::new(String::new()));
+    let second = Rc::new(RefCell::new(String::new()));
+
+    let first_write = first.clone();
+    let mut vm = WrenBuilder::new()
+        .with_write_fn(move |s| first_write.borrow_mut().push_str(s))
+        .build();
+
+    vm.interpret("test_context", r#"System.print("one")"#)
+        .expect("Interpret failed");
+
+    let second_write = second.clone();
+    vm.set_write_fn(move |s| second_write.borrow_mut().push_str(s));
+
+    vm.interpret("test_context", r#"System.print("two")"#)
+        .expect("Interpret failed");
+
+    assert_eq!(first.borrow().as_str(), "one\n");
+    assert_eq!(second.borrow().as_str(), "two\n");
+}
+
 #[test]
 fn test_context_result() -> WrenResult<()> {
     let mut vm = WrenBuilder::new().build();
@@ -189,3 +433,376 @@ fn test_context_result_fail() -> Result<(), Box<dyn Error>> {
         }
     }
 }
+
+/// Retrieving a foreign cell must check the type of the foreign value.
+#[test]
+fn test_get_foreign_cell() {
+    #[wren_class]
+    #[derive(Debug)]
+    struct Foo(u32);
+
+    #[wren_methods]
+    impl Foo {
+        #[construct]
+        fn new(val: u32) -> Self {
+            Self(val)
+        }
+    }
+
+    #[wren_class]
+    #[derive(Debug)]
+    struct Bar;
+
+    #[wren_methods]
+    impl Bar {
+        #[construct]
+        fn new() -> Self {
+            Self
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_context", |module| {
+            module.register::<Foo>();
+            module.register::<Bar>();
+        })
+        .build();
+
+    vm.interpret(
+        "test_context",
+        r#"
+    foreign class Foo {
+      construct new(val) {}
+    }
+
+    foreign class Bar {
+      construct new() {}
+    }
+
+    var foo = Foo.new(7)
+    var num = 42
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context(|ctx| {
+        let foo = ctx.get_var("test_context", "foo").unwrap();
+        ctx.ensure_slots(1);
+        ToWren::put(&foo, ctx, 0);
+
+        let cell = ctx.get_foreign_cell::<Foo>(0).expect("Foreign cell of correct type");
+        assert_eq!(cell.borrow().0, 7);
+
+        let result = ctx.get_foreign_cell::<Bar>(0);
+        assert!(matches!(result, Err(WrenError::ForeignType)));
+
+        let num = ctx.get_var("test_context", "num").unwrap();
+        ToWren::put(&num, ctx, 0);
+
+        let result = ctx.get_foreign_cell::<Foo>(0);
+        assert!(matches!(result, Err(WrenError::SlotType { .. })));
+    });
+}
+
+/// Unchecked slot getters should read the same values as the checked ones.
+#[test]
+fn test_get_slot_unchecked() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_context",
+        r#"
+    var num = 42.5
+    var flag = true
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        ctx.get_var("test_context", "num")?;
+        assert_eq!(unsafe { ctx.get_slot_double_unchecked(0) }, ctx.get_slot::<f64>(0)?);
+        assert_eq!(unsafe { ctx.get_slot_double_unchecked(0) }, 42.5);
+
+        ctx.get_var("test_context", "flag")?;
+        assert_eq!(unsafe { ctx.get_slot_bool_unchecked(0) }, ctx.get_slot::<bool>(0)?);
+        assert!(unsafe { ctx.get_slot_bool_unchecked(0) });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Should assign module variables from Rust, declaring them when missing.
+#[test]
+fn test_set_variable() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.context_result(|ctx| {
+        // Module doesn't exist yet.
+        ctx.set_var("test_context", "speed", 4.5)?;
+        assert!(ctx.has_var("test_context", "speed"));
+        assert!(!ctx.has_var("test_context", "SetVar"));
+
+        // Existing variable is reassigned.
+        ctx.set_var("test_context", "speed", 7.0)?;
+        ctx.set_var("test_context", "name", "player")?;
+
+        assert!(matches!(
+            ctx.set_var("test_context", "not valid", 1.0),
+            Err(WrenError::InvalidVariableName(_))
+        ));
+
+        Ok(())
+    })
+    .unwrap();
+
+    vm.interpret(
+        "test_context",
+        r#"
+    if (speed != 7) {
+        Fiber.abort("Unexpected speed %(speed)")
+    }
+    if (name != "player") {
+        Fiber.abort("Unexpected name %(name)")
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+/// Slot type helpers should check the type, and numbers should be coerced from strings.
+#[test]
+fn test_slot_helpers() {
+    #[wren_class]
+    #[derive(Debug)]
+    struct Foo;
+
+    #[wren_methods]
+    impl Foo {
+        #[construct]
+        fn new() -> Self {
+            Self
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_context", |module| {
+            module.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret(
+        "test_context",
+        r#"
+    foreign class Foo {
+      construct new() {}
+    }
+
+    var foo = Foo.new()
+    var num = 42.5
+    var numeric = " 7.25 "
+    var text = "seven"
+    var flag = true
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        ctx.get_var("test_context", "num")?;
+        assert!(ctx.slot_is_number(0));
+        assert!(!ctx.slot_is_string(0));
+        assert!(!ctx.slot_is_foreign(0));
+        assert_eq!(ctx.slot_as_f64(0)?, 42.5);
+
+        ctx.get_var("test_context", "numeric")?;
+        assert!(!ctx.slot_is_number(0));
+        assert!(ctx.slot_is_string(0));
+        assert_eq!(ctx.slot_as_f64(0)?, 7.25);
+
+        ctx.get_var("test_context", "text")?;
+        assert!(matches!(ctx.slot_as_f64(0), Err(WrenError::NotNumeric(ref s)) if s == "seven"));
+
+        ctx.get_var("test_context", "foo")?;
+        assert!(ctx.slot_is_foreign(0));
+        assert!(!ctx.slot_is_number(0));
+
+        ctx.get_var("test_context", "flag")?;
+        assert!(matches!(ctx.slot_as_f64(0), Err(WrenError::SlotType { .. })));
+
+        let out_of_bounds = ctx.slot_count();
+        assert!(!ctx.slot_is_number(out_of_bounds));
+        assert!(!ctx.slot_is_string(out_of_bounds));
+        assert!(!ctx.slot_is_foreign(out_of_bounds));
+        assert!(matches!(
+            ctx.slot_as_f64(out_of_bounds),
+            Err(WrenError::SlotOutOfBounds(_))
+        ));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Variables should be retrieved and converted in one step.
+#[test]
+fn test_get_var_as() {
+    #[wren_class]
+    #[derive(Debug)]
+    struct Foo(u32);
+
+    #[wren_methods]
+    impl Foo {
+        #[construct]
+        fn new(val: u32) -> Self {
+            Self(val)
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_context", |module| {
+            module.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret(
+        "test_context",
+        r#"
+    foreign class Foo {
+      construct new(val) {}
+    }
+
+    var foo = Foo.new(7)
+    var score = 42
+    var name = "wren"
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        assert_eq!(ctx.get_var_as::<f64>("test_context", "score")?, 42.0);
+        assert_eq!(ctx.get_var_as::<String>("test_context", "name")?, "wren");
+        assert_eq!(ctx.get_var_as::<WrenCell<Foo>>("test_context", "foo")?.borrow().0, 7);
+
+        assert!(matches!(
+            ctx.get_var_as::<f64>("test_context", "name"),
+            Err(WrenError::SlotType { .. })
+        ));
+        assert!(matches!(
+            ctx.get_var_as::<WrenCell<Foo>>("test_context", "score"),
+            Err(WrenError::SlotType { .. })
+        ));
+        assert!(matches!(
+            ctx.get_var_as::<f64>("test_context", "missing"),
+            Err(WrenError::VariableNotFound(_))
+        ));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Slots should be inspectable from the VM without entering a full context.
+#[test]
+fn test_with_slots() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret("test_context", "var name = \"wren\"")
+        .expect("Interpret failed");
+
+    vm.context(|ctx| {
+        ctx.get_var("test_context", "name").unwrap();
+    });
+
+    let (count, slot_type, value) =
+        vm.with_slots(|ctx| (ctx.slot_count(), ctx.slot_type(0), ctx.get_slot::<String>(0).unwrap()));
+    assert!(count >= 1);
+    assert_eq!(slot_type, Some(rust_wren::WrenType::String));
+    assert_eq!(value, "wren");
+}
+
+/// Growing the slots should report the count from before.
+#[test]
+fn test_ensure_slots_previous() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.context(|ctx| {
+        let before = ctx.slot_count();
+        assert_eq!(ctx.ensure_slots(before + 2), before);
+        assert_eq!(ctx.slot_count(), before + 2);
+
+        // Slots are never shrunk.
+        assert_eq!(ctx.ensure_slots(1), before + 2);
+        assert_eq!(ctx.slot_count(), before + 2);
+    });
+}
+
+/// Class checks should work for built in and script classes, including inheritance.
+#[test]
+fn test_is_instance_of() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret(
+        "test_context",
+        r#"
+    class Animal {}
+    class Cat is Animal {
+      construct new() {}
+    }
+
+    var num = 42
+    var text = "wren"
+    var cat = Cat.new()
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        let num = ctx.get_var("test_context", "num")?;
+        let text = ctx.get_var("test_context", "text")?;
+        let cat = ctx.get_var("test_context", "cat")?;
+
+        let num_class = ctx.get_var("test_context", "Num")?;
+        let string_class = ctx.get_var("test_context", "String")?;
+        let animal_class = ctx.get_var("test_context", "Animal")?;
+        let cat_class = ctx.get_var("test_context", "Cat")?;
+
+        assert!(ctx.is_instance_of(&num, &num_class)?);
+        assert!(!ctx.is_instance_of(&num, &string_class)?);
+        assert!(ctx.is_instance_of(&text, &string_class)?);
+        assert!(!ctx.is_instance_of(&text, &num_class)?);
+        assert!(ctx.is_instance_of(&cat, &cat_class)?);
+        assert!(ctx.is_instance_of(&cat, &animal_class)?);
+        assert!(!ctx.is_instance_of(&num, &animal_class)?);
+
+        // Right hand side must be a class.
+        assert!(ctx.is_instance_of(&num, &text).unwrap_err().is_runtime_error());
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Numeric expressions should be evaluated with Wren's number syntax.
+#[test]
+fn test_eval_number() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.context(|ctx| {
+        assert_eq!(ctx.eval_number("0x1F").unwrap(), 31.0);
+        assert_eq!(ctx.eval_number("1e3").unwrap(), 1000.0);
+        assert_eq!(ctx.eval_number("2.5e-1 + 0xff").unwrap(), 255.25);
+        assert_eq!(ctx.eval_number("2 + 2").unwrap(), 4.0);
+
+        assert!(matches!(
+            ctx.eval_number("\"ten\""),
+            Err(WrenError::SlotType {
+                expected: WrenType::Number,
+                actual: WrenType::String,
+            })
+        ));
+        assert!(matches!(ctx.eval_number("1 +"), Err(WrenError::CompileError(_))));
+        assert!(matches!(ctx.eval_number("var a = 1"), Err(WrenError::CompileError(_))));
+        assert!(matches!(
+            ctx.eval_number(r#"1) System.print("x") Result = (2"#),
+            Err(WrenError::CompileError(_))
+        ));
+    });
+}
@@ -0,0 +1,93 @@
+use rust_wren::{prelude::*, WrenError};
+
+#[wren_class]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Idle,
+    Walking,
+    Running = 10,
+}
+
+#[wren_class]
+struct Machine {
+    state: State,
+}
+
+#[wren_methods]
+impl Machine {
+    #[construct]
+    fn new() -> Self {
+        Machine { state: State::Idle }
+    }
+
+    fn state(&self) -> State {
+        self.state
+    }
+
+    fn set_state(&mut self, state: State) {
+        self.state = state;
+    }
+}
+
+#[test]
+fn test_enum_to_from_wren() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_enum", |m| {
+            m.register::<Machine>();
+        })
+        .build();
+
+    vm.interpret("test_enum", &Machine::wren_declaration())
+        .expect("Interpret failed");
+    vm.interpret(
+        "test_enum",
+        r#"
+    var machine = Machine.new()
+    if (machine.state() != 0) {
+        Fiber.abort("Unexpected initial state %(machine.state())")
+    }
+
+    machine.set_state(1)
+    if (machine.state() != 1) {
+        Fiber.abort("Unexpected state from discriminant %(machine.state())")
+    }
+
+    machine.set_state("Running")
+    if (machine.state() != 10) {
+        Fiber.abort("Unexpected state from name %(machine.state())")
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    let result = vm.interpret("test_enum", r#"machine.set_state("Flying")"#);
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => match foreign.downcast_ref::<WrenError>() {
+            Some(WrenError::ForeignCall { cause, .. }) => match &**cause {
+                WrenError::GetArg { cause, .. } => {
+                    assert!(matches!(**cause, WrenError::UnknownVariant { ty: "State", .. }))
+                }
+                err => panic!("Unexpected cause {:?}", err),
+            },
+            err => panic!("Unexpected foreign error {:?}", err),
+        },
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    vm.context_result(|ctx| {
+        ctx.ensure_slots(1);
+        ToWren::put(State::Running, ctx, 0);
+        assert_eq!(ctx.get_slot::<State>(0)?, State::Running);
+
+        ToWren::put(5.0, ctx, 0);
+        assert!(matches!(
+            ctx.get_slot::<State>(0),
+            Err(WrenError::UnknownVariant { .. })
+        ));
+
+        Ok(())
+    })
+    .unwrap();
+}
@@ -30,6 +30,21 @@ impl Foo {
 
         Ok(())
     }
+
+    #[method(name = unregisteredReturn)]
+    fn unregistered_return() -> Baz {
+        Baz
+    }
+
+    #[method(name = undeclaredReturn)]
+    fn undeclared_return() -> Bar {
+        Bar::new()
+    }
+
+    #[method(name = nulString)]
+    fn nul_string() -> String {
+        "null\0byte".to_string()
+    }
 }
 
 const FOO: &str = r#"
@@ -39,6 +54,9 @@ foreign class Foo {
   foreign static badReturn()
   foreign badArgs(a, b, c)
   foreign badBorrow(other)
+  foreign static unregisteredReturn()
+  foreign static undeclaredReturn()
+  foreign static nulString()
   static giveBool() { true }
   static eatme() { Fiber.abort("eatme") }
 }
@@ -68,6 +86,18 @@ foreign class Bar {
 }
 "#;
 
+/// Never registered with a module.
+#[wren_class]
+struct Baz;
+
+#[wren_methods]
+impl Baz {
+    #[construct]
+    fn new() -> Self {
+        Baz
+    }
+}
+
 /// Utility for determining if the result is a foreign error from within native Rust inside the Wren VM.
 fn is_runtime_foreign_err<T>(result: &WrenResult<T>) -> bool {
     match result {
@@ -243,3 +273,435 @@ fn test_prop_type_error() {
 
     println!("{}", result.unwrap_err());
 }
+
+/// Returning a foreign value whose class can't be found should
+/// abort the fiber instead of panicking.
+#[test]
+fn test_foreign_class_not_found() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_error", |module| {
+            module.register::<Foo>();
+            module.register::<Bar>();
+        })
+        .build();
+
+    // Bar is registered, but deliberately not declared in Wren.
+    vm.interpret("test_error", FOO).expect("Interpret failed");
+
+    let result = vm.interpret("test_error", "Foo.unregisteredReturn()");
+    assert!(is_runtime_foreign_err(&result));
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => assert!(matches!(
+            foreign.downcast_ref::<WrenError>(),
+            Some(WrenError::ClassNotRegistered("Baz"))
+        )),
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let result = vm.interpret("test_error", "Foo.undeclaredReturn()");
+    assert!(is_runtime_foreign_err(&result));
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => assert!(matches!(
+            foreign.downcast_ref::<WrenError>(),
+            Some(WrenError::VariableNotFound(_))
+        )),
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    // VM is still usable afterwards.
+    vm.interpret("test_error", "Foo.new(1)").expect("Interpret failed");
+}
+
+/// Names and sources with embedded null bytes should result in errors, not panics.
+#[test]
+fn test_nul_byte() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_error", |module| {
+            module.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret("test_error", FOO).expect("Interpret failed");
+
+    assert!(matches!(
+        vm.interpret("test_error", "var a = 1\0"),
+        Err(WrenError::NulByte { context: "Source" })
+    ));
+    assert!(matches!(
+        vm.interpret("test\0error", "var a = 1"),
+        Err(WrenError::NulByte { context: "Module name" })
+    ));
+
+    vm.context(|ctx| {
+        assert!(matches!(
+            ctx.get_var("test_error", "F\0oo"),
+            Err(WrenError::NulByte { .. })
+        ));
+        assert!(matches!(
+            ctx.get_var("test\0error", "Foo"),
+            Err(WrenError::NulByte { .. })
+        ));
+        assert!(!ctx.has_var("test_error", "F\0oo"));
+        assert!(!ctx.has_module("test\0error"));
+    });
+
+    // Strings returned to Wren keep their embedded null bytes.
+    vm.interpret(
+        "test_error",
+        r#"if (Foo.nulString() != "null\0byte") Fiber.abort("truncated")"#,
+    )
+    .expect("Interpret failed");
+}
+
+/// Calling a Rust instance method on a plain Wren object should
+/// explain that the class is probably missing `foreign`.
+#[test]
+fn test_receiver_not_foreign() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_error", |module| {
+            module.register::<Foo>();
+        })
+        .build();
+
+    let result = vm.interpret(
+        "test_error",
+        r#"
+    class Foo {
+      construct new(val) {}
+      foreign badArgs(a, b, c)
+    }
+    Foo.new(0).badArgs(1, true, "test")
+    "#,
+    );
+
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => match foreign.downcast_ref::<WrenError>() {
+            Some(WrenError::ForeignCall { cause, .. }) => match &**cause {
+                WrenError::GetArg { slot: 0, cause } => {
+                    assert!(matches!(**cause, WrenError::ReceiverNotForeign))
+                }
+                err => panic!("Unexpected cause {:?}", err),
+            },
+            err => panic!("Unexpected foreign error {:?}", err),
+        },
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+/// Strict bindings should report foreign classes and methods that aren't registered.
+#[test]
+fn test_strict_bindings() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_error", |module| {
+            module.register::<Foo>();
+        })
+        .with_strict_bindings(true)
+        .build();
+
+    vm.interpret("test_error", FOO).expect("Interpret failed");
+
+    let result = vm.interpret(
+        "test_error",
+        r#"
+    foreign class Missing {
+      construct new() {}
+    }
+    "#,
+    );
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => match foreign.downcast_ref::<WrenError>() {
+            Some(WrenError::BindingNotFound { module, class, sig }) => {
+                assert_eq!(module, "test_error");
+                assert_eq!(class, "Missing");
+                assert_eq!(sig, &None);
+            }
+            err => panic!("Unexpected foreign error {:?}", err),
+        },
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let result = vm.interpret(
+        "test_error",
+        r#"
+    class Partial {
+      static pureWren() { 1 }
+      foreign static missing()
+    }
+    "#,
+    );
+    assert!(is_runtime_foreign_err(&result));
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => match foreign.downcast_ref::<WrenError>() {
+            Some(WrenError::BindingNotFound { class, sig, .. }) => {
+                assert_eq!(class, "Partial");
+                assert_eq!(sig.as_deref(), Some("missing()"));
+            }
+            err => panic!("Unexpected foreign error {:?}", err),
+        },
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    // Constructing the unregistered class aborts instead of allocating.
+    let result = vm.interpret("test_error", "Missing.new()");
+    assert!(result.unwrap_err().is_runtime_error());
+
+    // VM is still usable afterwards.
+    vm.interpret("test_error", "Foo.new(1)").expect("Interpret failed");
+}
+
+/// Missing bindings should not be recorded as errors when strict bindings are disabled.
+#[test]
+fn test_lenient_bindings() {
+    let mut vm = WrenBuilder::new().with_strict_bindings(false).build();
+
+    vm.interpret(
+        "test_error",
+        r#"
+    foreign class Missing {}
+    "#,
+    )
+    .expect("Interpret failed");
+
+    // Wren still fails on a missing foreign method, but without a foreign error.
+    let result = vm.interpret(
+        "test_error",
+        r#"
+    class Partial {
+      foreign static missing()
+    }
+    "#,
+    );
+    assert!(matches!(result, Err(WrenError::RuntimeError { foreign: None, .. })));
+}
+
+/// Compiling only should report syntax errors without running any code.
+#[test]
+fn test_compile_only() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.compile_only(
+        "test_error",
+        r#"
+    class A {
+      static make() { B.new() }
+    }
+    class B {
+      construct new() {}
+    }
+    var a = A.make()
+    Fiber.abort("Should not run")
+    "#,
+    )
+    .expect("Compile failed");
+    vm.context(|ctx| assert!(!ctx.has_module("test_error")));
+
+    let result = vm.compile_only(
+        "test_error",
+        r#"
+    var ok = 1
+    var = 2
+    "#,
+    );
+    match result {
+        Err(WrenError::CompileError(errors)) => {
+            assert!(!errors.is_empty());
+            assert_eq!(errors[0].module, "test_error");
+            assert_eq!(errors[0].line, 3);
+        }
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    // Compiling the same top level variables again doesn't conflict.
+    vm.compile_only("test_error", "var ok = 1").expect("Compile failed");
+    vm.compile_only("test_error", "var ok = 1").expect("Compile failed");
+}
+
+/// Errors should expose their underlying cause through `source()`.
+#[test]
+fn test_error_source_chain() {
+    use std::error::Error;
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_error", |module| {
+            module.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret("test_error", FOO).expect("Interpret failed");
+
+    let result = vm.interpret(
+        "test_error",
+        r#"
+    var foo = Foo.new(1)
+    foo.badArgs(true, true, "test")
+    "#,
+    );
+    let err = result.unwrap_err();
+
+    // Walk from the runtime error, through the foreign call and argument,
+    // down to the slot type error that caused it.
+    let mut chain: Vec<&(dyn Error + 'static)> = vec![];
+    let mut source = err.source();
+    while let Some(cause) = source {
+        chain.push(cause);
+        source = cause.source();
+    }
+
+    let causes = chain
+        .iter()
+        .map(|cause| cause.downcast_ref::<WrenError>())
+        .collect::<Vec<_>>();
+    assert!(matches!(
+        causes[..],
+        [
+            Some(WrenError::ForeignCall { .. }),
+            Some(WrenError::GetArg { slot: 1, .. }),
+            Some(WrenError::SlotType { .. }),
+        ]
+    ));
+
+    // Errors from a context closure keep the original error as their source.
+    let ctx_err = WrenError::Ctx(Box::new(WrenError::VariableNotFound("missing".to_owned())));
+    assert!(matches!(
+        ctx_err.source().and_then(|cause| cause.downcast_ref::<WrenError>()),
+        Some(WrenError::VariableNotFound(_))
+    ));
+
+    // Errors without an underlying cause end the chain.
+    assert!(WrenError::NullPtr.source().is_none());
+}
+
+/// Errors from the VM should retain the raw result code.
+#[test]
+fn test_interpret_result_code() {
+    use rust_wren::bindings;
+
+    let mut vm = WrenBuilder::new().build();
+
+    let err = vm.interpret("test_error", "var = 1").unwrap_err();
+    assert_eq!(
+        err.interpret_result(),
+        Some(bindings::WrenInterpretResult_WREN_RESULT_COMPILE_ERROR)
+    );
+
+    let err = vm.interpret("test_error", "Fiber.abort(\"oops\")").unwrap_err();
+    assert_eq!(
+        err.interpret_result(),
+        Some(bindings::WrenInterpretResult_WREN_RESULT_RUNTIME_ERROR)
+    );
+
+    assert_eq!(WrenError::VariableNotFound("foo".to_string()).interpret_result(), None);
+}
+
+/// Type errors for UNKNOWN slots should hint at the likely cause.
+#[test]
+fn test_unknown_slot_type_hint() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret("test_error", "class Plain {}").expect("Interpret error");
+
+    vm.context(|ctx| {
+        // Plain Wren classes and their instances have no slot type of their own.
+        let err = ctx.get_var_as::<f64>("test_error", "Plain").unwrap_err();
+        assert!(matches!(
+            err,
+            WrenError::SlotType {
+                actual: rust_wren::types::WrenType::Unknown,
+                ..
+            }
+        ));
+        assert!(err
+            .to_string()
+            .contains("static class receiver or a class missing the `foreign` keyword"));
+    });
+
+    // Other type errors are unchanged.
+    let err = WrenError::SlotType {
+        expected: rust_wren::types::WrenType::Number,
+        actual: rust_wren::types::WrenType::Bool,
+    };
+    assert_eq!(err.to_string(), "Expected slot type 'Number', actual 'Bool'");
+}
+
+/// Nested argument errors should be displayed one cause per line.
+#[test]
+fn test_foreign_call_display() {
+    let err = WrenError::new_foreign_call(
+        "badArgs",
+        Box::new(WrenError::GetArg {
+            slot: 2,
+            cause: Box::new(WrenError::SlotType {
+                expected: rust_wren::types::WrenType::Number,
+                actual: rust_wren::types::WrenType::Bool,
+            }),
+        }),
+    );
+
+    assert_eq!(
+        err.to_string(),
+        "Invalid call to foreign 'badArgs'\n  \
+         Getting argument from slot 2 failed\n    \
+         Expected slot type 'Number', actual 'Bool'"
+    );
+}
+
+/// Compile errors should carry the token they were reported at, and its column.
+#[test]
+fn test_compile_error_column() {
+    let mut vm = WrenBuilder::new().build();
+
+    let result = vm.interpret("test_error", "var a = 1\n  var = 2");
+    match result {
+        Err(WrenError::CompileError(errors)) => {
+            let err = &errors[0];
+            assert_eq!(err.line, 2);
+            assert_eq!(err.token.as_deref(), Some("="));
+            assert_eq!(err.column, Some(7));
+        }
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let result = vm.compile_only("test_error", "var a = 1\n  var = 2");
+    match result {
+        Err(WrenError::CompileError(errors)) => {
+            let err = &errors[0];
+            assert_eq!(err.module, "test_error");
+            assert_eq!(err.line, 2);
+            assert_eq!(err.column, Some(7));
+        }
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+/// The abort message should be available without the stack trace.
+#[test]
+fn test_abort_message() {
+    let mut vm = WrenBuilder::new().build();
+
+    let err = vm
+        .interpret(
+            "test_error",
+            r#"
+    class Checker {
+        static check() { Fiber.abort("Out of fuel") }
+    }
+    Checker.check()
+    "#,
+        )
+        .unwrap_err();
+
+    assert_eq!(err.abort_message(), Some("Out of fuel"));
+    assert!(err.to_string().contains("check()"));
+
+    let err = vm.interpret("test_error", "var = 1").unwrap_err();
+    assert_eq!(err.abort_message(), None);
+}
@@ -0,0 +1,129 @@
+use rust_wren::{prelude::*, WrenError};
+
+#[test]
+fn test_fiber_call() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_fiber",
+        r#"
+    var fiber = Fiber.new {|x|
+      var y = Fiber.yield(x * 2)
+      return "done %(y)"
+    }
+    "#,
+    )
+    .unwrap();
+
+    vm.context_result(|ctx| {
+        let fiber = ctx.get_fiber("test_fiber", "fiber")?;
+        assert!(!fiber.is_done(ctx)?);
+
+        let yielded = fiber.call::<_, f64>(ctx, 3.0)?;
+        assert_eq!(yielded, 6.0);
+        assert!(!fiber.is_done(ctx)?);
+
+        let returned = fiber.call::<_, String>(ctx, 7.0)?;
+        assert_eq!(returned, "done 7");
+        assert!(fiber.is_done(ctx)?);
+        assert_eq!(fiber.error(ctx)?, None);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_fiber_error() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_fiber",
+        r#"
+    var fiber = Fiber.new {
+      Fiber.abort("oops")
+    }
+    var notFiber = 42
+    "#,
+    )
+    .unwrap();
+
+    vm.context_result(|ctx| {
+        let fiber = ctx.get_fiber("test_fiber", "fiber")?;
+
+        let result = fiber.call::<_, ()>(ctx, ());
+        assert!(matches!(result, Err(WrenError::RuntimeError { .. })));
+        assert!(fiber.is_done(ctx)?);
+        assert_eq!(fiber.error(ctx)?, Some("oops".to_string()));
+
+        let not_fiber = ctx.get_fiber("test_fiber", "notFiber");
+        assert!(matches!(not_fiber, Err(WrenError::SlotType { .. })));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Values of an unknown type that aren't fibers should be rejected.
+#[test]
+fn test_not_a_fiber() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_fiber",
+        r#"
+    class Foo {
+        construct new() {}
+    }
+    var instance = Foo.new()
+    var function = Fn.new { 1 }
+    var fiber = Fiber.new { 1 }
+    "#,
+    )
+    .unwrap();
+
+    vm.context(|ctx| {
+        assert!(matches!(
+            ctx.get_fiber("test_fiber", "Foo"),
+            Err(WrenError::ForeignType)
+        ));
+        assert!(matches!(
+            ctx.get_fiber("test_fiber", "instance"),
+            Err(WrenError::ForeignType)
+        ));
+        assert!(matches!(
+            ctx.get_fiber("test_fiber", "function"),
+            Err(WrenError::ForeignType)
+        ));
+        assert!(ctx.get_fiber("test_fiber", "fiber").is_ok());
+    });
+}
+
+/// Reading fibers must leave the other slots intact, so a list of fibers
+/// can be read element by element.
+#[test]
+fn test_fiber_list() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_fiber",
+        r#"
+    var fibers = [Fiber.new { 1 }, Fiber.new { 2 }, Fiber.new { 3 }]
+    "#,
+    )
+    .unwrap();
+
+    vm.context_result(|ctx| {
+        let fibers = ctx.get_list("test_fiber", "fibers")?.to_vec::<WrenFiber>(ctx)?;
+        assert_eq!(fibers.len(), 3);
+
+        let results = fibers
+            .iter()
+            .map(|fiber| fiber.call::<_, f64>(ctx, ()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(results, vec![1.0, 2.0, 3.0]);
+
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,29 @@
+use rust_wren::{prelude::*, WrenError};
+
+const SCRIPT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/script.wren");
+
+#[test]
+fn test_interpret_file() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret_file("script", SCRIPT).expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        assert_eq!(ctx.get_var_as::<String>("script", "greeting")?, "Hello from a file");
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// A file that can't be read should be reported with its path.
+#[test]
+fn test_interpret_missing_file() {
+    let mut vm = WrenBuilder::new().build();
+
+    match vm.interpret_file("script", "tests/missing.wren") {
+        Err(WrenError::Io { path, cause }) => {
+            assert_eq!(path.to_str(), Some("tests/missing.wren"));
+            assert_eq!(cause.kind(), std::io::ErrorKind::NotFound);
+        }
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
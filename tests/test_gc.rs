@@ -0,0 +1,28 @@
+//! Allocation tracking is global to the process, so this is kept
+//! apart from other tests that could be running VMs in parallel.
+use rust_wren::prelude::*;
+
+/// Collecting garbage should report the bytes it reclaimed.
+#[test]
+fn test_collect_garbage_stats() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_gc",
+        r#"
+    for (i in 0...1000) {
+        var garbage = List.filled(10, "item %(i)")
+    }
+    "#,
+    )
+    .expect("Interpret error");
+
+    vm.context(|ctx| {
+        let reclaimed = ctx.collect_garbage_stats();
+        if cfg!(any(debug_assertions, feature = "alloc-tracking")) {
+            assert!(reclaimed > 0, "Expected garbage to be reclaimed");
+        } else {
+            assert_eq!(reclaimed, 0);
+        }
+    });
+}
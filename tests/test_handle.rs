@@ -1,6 +1,8 @@
 use rust_wren::{
-    handle::{FnSymbolRef, WrenCallRef},
+    handle::{CallOutcome, FnSymbolRef, OwnedHandle, WrenCallRef},
     prelude::*,
+    types::WrenType,
+    WrenContext, WrenError,
 };
 use std::{rc::Rc, thread};
 
@@ -210,6 +212,62 @@ fn test_fiber_handle() {
     .unwrap();
 }
 
+/// Should report the type of the value a leaked handle points to.
+#[test]
+fn test_handle_slot_type() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_handle", |module| {
+            module.register::<MoveMe>();
+        })
+        .build();
+
+    vm.interpret("test_handle", MOVE_ME).expect("Interpret failed");
+    vm.interpret(
+        "test_handle",
+        r#"
+    var list = [1, 2, 3]
+    var string = "Foo"
+    var foreign = MoveMe.new(1)
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        let list = ctx.get_var("test_handle", "list")?.leak()?;
+        let string = ctx.get_var("test_handle", "string")?.leak()?;
+        let foreign = ctx.get_var("test_handle", "foreign")?.leak()?;
+
+        // Existing slots are preserved.
+        ctx.ensure_slots(1);
+        ToWren::put(7.0, ctx, 0);
+
+        assert_eq!(list.slot_type(ctx), WrenType::List);
+        assert_eq!(string.slot_type(ctx), WrenType::String);
+        assert_eq!(foreign.slot_type(ctx), WrenType::Foreign);
+
+        assert_eq!(ctx.get_slot::<f64>(0)?, 7.0);
+
+        // Scratch slot is reused, so the slots don't keep growing.
+        let count = ctx.slot_count();
+        for _ in 0..10 {
+            list.slot_type(ctx);
+        }
+        assert_eq!(ctx.slot_count(), count);
+
+        // Slots grown by the caller are moved past, instead of reused as scratch.
+        ctx.ensure_slots(count + 2);
+        ToWren::put(8.0, ctx, count as i32);
+        ToWren::put(9.0, ctx, count as i32 + 1);
+        assert_eq!(list.slot_type(ctx), WrenType::List);
+        assert_eq!(string.slot_type(ctx), WrenType::String);
+        assert_eq!(ctx.get_slot::<f64>(count as i32)?, 8.0);
+        assert_eq!(ctx.get_slot::<f64>(count as i32 + 1)?, 9.0);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
 #[test]
 fn test_wren_ref_leak() {
     let mut vm = WrenBuilder::new().build();
@@ -354,3 +412,391 @@ fn test_property_as_receiver() {
         assert_eq!(callme.call::<_, i32>(ctx, ()).ok(), Some(7));
     });
 }
+
+/// Should pair a method signature with a receiver returned from a previous call.
+#[test]
+fn test_make_call_on() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret(
+        "test_handle",
+        r#"
+    class Inner {
+      construct new() {}
+      add(a, b) { a + b }
+    }
+
+    class Outer {
+      static inner { Inner.new() }
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context(|ctx| {
+        let prop_call = ctx.make_call_ref("test_handle", "Outer", "inner").unwrap();
+        let receiver = prop_call.call::<_, WrenRef>(ctx, ()).unwrap();
+
+        let add = ctx.make_call_on(receiver, "add(_,_)").unwrap();
+        assert_eq!(add.call::<_, i32>(ctx, (3, 4)).ok(), Some(7));
+        assert_eq!(add.call::<_, i32>(ctx, (5, 6)).ok(), Some(11));
+    });
+}
+
+/// Handles received as `OwnedHandle` can be stored and used after the foreign call.
+#[test]
+fn test_owned_handle_arg() {
+    #[wren_class]
+    struct Events {
+        callbacks: Vec<OwnedHandle>,
+    }
+
+    #[wren_methods]
+    impl Events {
+        #[construct]
+        fn new() -> Self {
+            Events { callbacks: vec![] }
+        }
+
+        fn subscribe(&mut self, callback: OwnedHandle) {
+            self.callbacks.push(callback);
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_handle", |module| module.register::<Events>())
+        .build();
+
+    vm.interpret(
+        "test_handle",
+        r#"
+    foreign class Events {
+        construct new() {}
+        foreign subscribe(callback)
+    }
+
+    class Invoker {
+        static invoke(fn, arg) { fn.call(arg) }
+    }
+
+    var events = Events.new()
+    events.subscribe(Fn.new {|x| x * 2 })
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        let events = ctx.get_var("test_handle", "events")?;
+        ctx.ensure_slots(1);
+        ToWren::put(&events, ctx, 0);
+        let callback = ctx
+            .get_foreign_cell::<Events>(0)?
+            .borrow_mut()
+            .callbacks
+            .pop()
+            .expect("Callback was not stored");
+
+        let invoke = ctx.make_call_ref("test_handle", "Invoker", "invoke(_,_)")?;
+        assert_eq!(invoke.call::<_, f64>(ctx, (&callback, 4.0))?, 8.0);
+        assert_eq!(invoke.call::<_, f64>(ctx, (&callback, 7.0))?, 14.0);
+
+        Ok(())
+    })
+    .expect("Context failed");
+}
+
+/// Aborted fibers should be reported as an outcome, not an error.
+#[test]
+fn test_call_full() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret(
+        "test_handle",
+        r#"
+    class Task {
+      static ok() { 7 }
+      static fail() { Fiber.abort("task failed") }
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        let ok = ctx.make_call_ref("test_handle", "Task", "ok()")?;
+        let outcome = ok.call_full::<_, f64>(ctx, ())?;
+        assert!(matches!(outcome, CallOutcome::Returned(value) if value == 7.0));
+
+        let fail = ctx.make_call_ref("test_handle", "Task", "fail()")?;
+        let outcome = fail.call_full::<_, f64>(ctx, ())?;
+        assert!(outcome.is_aborted());
+        assert!(matches!(
+            outcome.into_result(),
+            Err(WrenError::RuntimeError { message, .. }) if message == "task failed"
+        ));
+
+        // Leaked call handles behave the same.
+        let fail = fail.leak()?;
+        assert!(fail.call_full::<_, f64>(ctx, ())?.is_aborted());
+
+        // Errors converting the return value are still errors.
+        assert!(ok.call_full::<_, String>(ctx, ()).is_err());
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Handles should be comparable by handle identity, and by Wren's `==` operator.
+#[test]
+fn test_handle_equality() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_handle",
+        r#"
+    class Point {
+        construct new(x) { _x = x }
+        x { _x }
+        ==(other) { other is Point && _x == other.x }
+    }
+
+    var a = Point.new(1)
+    var alias = a
+    var b = Point.new(1)
+    var c = Point.new(2)
+    var list = []
+    var number = 42
+    var sameNumber = 42
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        let a = ctx.get_var("test_handle", "a")?.leak()?;
+        let a_again = ctx.get_var("test_handle", "a")?.leak()?;
+        let alias = ctx.get_var("test_handle", "alias")?.leak()?;
+        let b = ctx.get_var("test_handle", "b")?.leak()?;
+        let c = ctx.get_var("test_handle", "c")?.leak()?;
+
+        // Identity of the handles themselves.
+        assert!(a.ptr_eq(&a));
+        assert!(!a.ptr_eq(&a_again));
+        let shared = Rc::new(a_again);
+        let shared_clone = shared.clone();
+        assert!(shared.ptr_eq(&shared_clone));
+
+        // Wren equality, including the overridden operator.
+        assert!(a.equals(ctx, &alias)?);
+        assert!(a.equals(ctx, &b)?);
+        assert!(!a.equals(ctx, &c)?);
+
+        // Objects without an overridden operator are compared by identity.
+        let list = ctx.get_var("test_handle", "list")?.leak()?;
+        assert!(list.equals(ctx, &list)?);
+        assert!(!list.equals(ctx, &a)?);
+
+        // Value types are compared by value.
+        let number = ctx.get_var("test_handle", "number")?.leak()?;
+        let same_number = ctx.get_var("test_handle", "sameNumber")?.leak()?;
+        assert!(!number.ptr_eq(&same_number));
+        assert!(number.equals(ctx, &same_number)?);
+
+        Ok(())
+    })
+    .expect("Context failed");
+}
+
+/// Handles should be counted until they're released at the end of a context.
+#[cfg(any(debug_assertions, feature = "handle-tracking"))]
+#[test]
+fn test_outstanding_handles() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret("test_handle", "var items = [1, 2, 3]")
+        .expect("Interpret error");
+    assert_eq!(vm.outstanding_handles(), 0);
+
+    vm.context(|ctx| {
+        let _items = ctx.get_list("test_handle", "items").expect("Get list failed");
+        let _count = ctx.get_var("test_handle", "items").expect("Get var failed");
+    });
+    assert_eq!(vm.outstanding_handles(), 0);
+
+    // Handles outliving the context are counted until dropped and released.
+    let items = vm
+        .context_result(|ctx| ctx.get_list("test_handle", "items"))
+        .expect("Get list failed");
+    assert_eq!(vm.outstanding_handles(), 1);
+
+    drop(items);
+    vm.context(|_| {});
+    assert_eq!(vm.outstanding_handles(), 0);
+}
+
+/// Calling a Wren closure from within a foreign method must fail cleanly,
+/// because Wren can't be re-entered while it's executing.
+#[test]
+fn test_call_within_foreign_method() {
+    #[wren_class]
+    struct Caller;
+
+    #[wren_methods]
+    impl Caller {
+        #[construct]
+        fn new() -> Self {
+            Caller
+        }
+
+        fn invoke(#[ctx] ctx: &mut WrenContext, callback: WrenRef<'_>, value: f64) -> rust_wren::Result<f64> {
+            ctx.call_within::<_, f64>(&callback, "call(_)", value)
+                .map_err(|err| foreign_error!(err))
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_handle", |module| {
+            module.register::<Caller>();
+        })
+        .build();
+
+    vm.interpret(
+        "test_handle",
+        r#"
+    foreign class Caller {
+        construct new() {}
+        foreign static invoke(callback, value)
+    }
+
+    var double = Fn.new {|n| n * 2 }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    let result = vm.interpret("test_handle", "Caller.invoke(double, 4)");
+    match result {
+        Err(WrenError::RuntimeError { message, .. }) => {
+            assert!(message.contains(&WrenError::ReentrantCall.to_string()));
+        }
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    // The VM is left intact, and the closure can be called once
+    // control has returned to Rust.
+    vm.context_result(|ctx| {
+        let double = ctx.get_var("test_handle", "double")?;
+        assert_eq!(ctx.call_within::<_, f64>(&double, "call(_)", 4.0)?, 8.0);
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// An owned handle dropped after its VM should panic, pointing
+/// to where the handle was created.
+#[cfg(feature = "handle-backtrace")]
+#[test]
+#[should_panic(expected = "outlived its VM")]
+fn test_handle_outlives_vm() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret("test_handle", r#"var value = 1234"#)
+        .expect("Interpret failed");
+
+    let handle = vm
+        .context_result(|ctx| ctx.get_var("test_handle", "value")?.leak())
+        .expect("Leak failed");
+
+    drop(vm);
+    drop(handle);
+}
+
+/// References to primitives should be accepted as call arguments.
+#[test]
+fn test_call_with_primitive_refs() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_handle",
+        r#"
+    class Calc {
+        static add(a, b) { a + b }
+        static not(a) { !a }
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context(|ctx| {
+        let float = 4.5_f64;
+        let int = 3_i32;
+        let flag = true;
+
+        let add = WrenCallRef::new(
+            ctx.get_var("test_handle", "Calc").unwrap(),
+            FnSymbolRef::compile(ctx, "add(_,_)").unwrap(),
+        );
+        let result = add.call::<_, f64>(ctx, (&float, &int)).unwrap();
+        assert_eq!(result, 7.5);
+
+        let not = WrenCallRef::new(
+            ctx.get_var("test_handle", "Calc").unwrap(),
+            FnSymbolRef::compile(ctx, "not(_)").unwrap(),
+        );
+        assert!(!not.call::<_, bool>(ctx, &flag).unwrap());
+    });
+}
+
+/// Preloaded calls should be available by name once their variable is declared.
+#[test]
+fn test_preloaded_calls() {
+    let mut vm = WrenBuilder::new()
+        .with_preloaded_calls(&[
+            ("test_handle", "Game", "update(_)"),
+            ("test_handle", "Game", "frames"),
+            ("test_handle", "Missing", "update(_)"),
+            // Same variable and signature in another module.
+            ("other", "Game", "update(_)"),
+        ])
+        .build();
+
+    assert!(vm.cached_call("test_handle", "Game.update(_)").is_none());
+
+    vm.interpret(
+        "test_handle",
+        r#"
+    class Game {
+        static frames { __frames }
+        static update(dt) {
+            __frames = (__frames || 0) + 1
+            return dt * 2
+        }
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    assert!(vm.cached_call("test_handle", "Game.update(_)").is_some());
+    assert!(vm.cached_call("test_handle", "Missing.update(_)").is_none());
+    assert!(vm.cached_call("other", "Game.update(_)").is_none());
+
+    vm.interpret(
+        "other",
+        r#"
+    class Game {
+        static update(dt) { dt * 10 }
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    assert!(vm.cached_call("other", "Game.update(_)").is_some());
+
+    vm.context(|ctx| {
+        let update = ctx.cached_call("test_handle", "Game.update(_)").unwrap();
+        for _ in 0..3 {
+            assert_eq!(update.call::<_, f64>(ctx, 0.25).unwrap(), 0.5);
+        }
+
+        let frames = ctx.cached_call("test_handle", "Game.frames").unwrap();
+        assert_eq!(frames.call::<_, f64>(ctx, ()).unwrap(), 3.0);
+
+        let other = ctx.cached_call("other", "Game.update(_)").unwrap();
+        assert_eq!(other.call::<_, f64>(ctx, 0.25).unwrap(), 2.5);
+    });
+}
@@ -1,4 +1,5 @@
 use rust_wren::{handle::WrenHandle, prelude::*, types::WrenType, WrenContext, WrenError};
+use std::mem::MaybeUninit;
 
 #[wren_class]
 struct Foo;
@@ -222,6 +223,64 @@ fn test_list_handle_push() {
     .unwrap();
 }
 
+/// Create a list from borrowed elements, without cloning.
+#[test]
+fn test_list_from_slice_ref() {
+    let mut vm = WrenBuilder::new().build();
+
+    let data = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+
+    vm.context_result(|ctx| {
+        let wren_list = WrenList::from_slice_ref(ctx, &data)?;
+
+        assert_eq!(wren_list.len(ctx), 3);
+        assert_eq!(wren_list.to_vec::<String>(ctx)?, data);
+
+        Ok(())
+    })
+    .unwrap();
+
+    // Original data is still owned by Rust.
+    assert_eq!(data.len(), 3);
+}
+
+/// Indexing with a flat result.
+#[test]
+fn test_list_try_get() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_list",
+        r#"
+        var x = [1, "two", null]
+        "#,
+    )
+    .expect("Interpret error");
+
+    vm.context_result(|ctx| {
+        let wren_list = ctx.get_list("test_list", "x")?;
+
+        assert_eq!(wren_list.try_get::<i32>(ctx, 0)?, 1);
+        assert_eq!(wren_list.try_get::<String>(ctx, 1)?, "two");
+        assert_eq!(wren_list.try_get::<Option<f64>>(ctx, 2)?, None);
+
+        assert!(matches!(
+            wren_list.try_get::<f64>(ctx, 1),
+            Err(WrenError::SlotType { .. })
+        ));
+        assert!(matches!(
+            wren_list.try_get::<f64>(ctx, 3),
+            Err(WrenError::IndexOutOfBounds { index: 3, len: 3 })
+        ));
+
+        // Existing `get` still reports out of bounds as `None`.
+        assert_eq!(wren_list.get::<f64>(ctx, 3)?, None);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
 #[test]
 fn test_list_to_vec() {
     let mut vm = WrenBuilder::new().build();
@@ -245,6 +304,39 @@ fn test_list_to_vec() {
     .expect("Context error");
 }
 
+#[test]
+fn test_list_get_many() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_list",
+        r#"
+        var x = [10, 20, 30, 40, 50]
+        var mixed = [1, "two"]
+        "#,
+    )
+    .expect("Interpret error");
+
+    vm.context_result(|ctx| {
+        let wren_list = ctx.get_list("test_list", "x")?;
+        let values = wren_list.get_many::<f64>(ctx, &[4, 0, 99, 2, 2])?;
+        assert_eq!(values, vec![Some(50.0), Some(10.0), None, Some(30.0), Some(30.0)]);
+
+        assert!(wren_list.get_many::<f64>(ctx, &[])?.is_empty());
+
+        // Elements that aren't read aren't type checked.
+        let mixed = ctx.get_list("test_list", "mixed")?;
+        assert_eq!(mixed.get_many::<f64>(ctx, &[0])?, vec![Some(1.0)]);
+        assert!(matches!(
+            mixed.get_many::<f64>(ctx, &[0, 1]),
+            Err(WrenError::SlotType { .. })
+        ));
+
+        Ok(())
+    })
+    .expect("Context error");
+}
+
 #[test]
 fn test_list_clone_to() {
     let mut vm = WrenBuilder::new().build();
@@ -281,3 +373,192 @@ fn test_list_clone_to() {
     })
     .expect("Context error");
 }
+
+#[test]
+fn test_list_fill_uninit() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_list",
+        r#"
+        var x = [1, 2, 3, 4]
+        var y = ["spruce", "maple", 3]
+        "#,
+    )
+    .expect("Interpret error");
+
+    vm.context_result(|ctx| {
+        // Buffer smaller than the list.
+        let wren_list = ctx.get_list("test_list", "x")?;
+        let mut buf = [MaybeUninit::<f64>::uninit(); 3];
+        let count = wren_list.fill_uninit::<f64>(ctx, &mut buf)?;
+        assert_eq!(count, 3);
+        let values = buf[..count]
+            .iter()
+            .map(|el| unsafe { el.assume_init() })
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![1.0, 2.0, 3.0]);
+
+        // Buffer larger than the list.
+        let mut buf = [MaybeUninit::<f64>::uninit(); 8];
+        assert_eq!(wren_list.fill_uninit::<f64>(ctx, &mut buf)?, 4);
+
+        // Conversion fails partway, after some strings were written.
+        let wren_list = ctx.get_list("test_list", "y")?;
+        let mut buf: Vec<MaybeUninit<String>> = (0..3).map(|_| MaybeUninit::uninit()).collect();
+        let result = wren_list.fill_uninit::<String>(ctx, &mut buf);
+        assert!(matches!(
+            result,
+            Err(WrenError::SlotType {
+                expected: WrenType::String,
+                actual: WrenType::Number
+            })
+        ));
+
+        Ok(())
+    })
+    .expect("Context error");
+}
+
+#[test]
+fn test_list_clear() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_list",
+        r#"
+        var x = [1, 2, 3]
+        var empty = []
+        "#,
+    )
+    .expect("Interpret error");
+
+    vm.context_result(|ctx| {
+        let mut wren_list = ctx.get_list("test_list", "x")?;
+        wren_list.clear(ctx)?;
+        assert_eq!(wren_list.len(ctx), 0);
+
+        // The module variable refers to the same list.
+        let x = ctx.get_list("test_list", "x")?;
+        assert!(x.is_empty(ctx));
+
+        // Still usable after clearing.
+        wren_list.push(ctx, 4.0);
+        assert_eq!(wren_list.to_vec::<f64>(ctx)?, vec![4.0]);
+
+        let mut empty = ctx.get_list("test_list", "empty")?;
+        empty.clear(ctx)?;
+        assert_eq!(empty.len(ctx), 0);
+
+        Ok(())
+    })
+    .expect("Context error");
+}
+
+/// Lists of mixed values should be converted one handle per element.
+#[test]
+fn test_list_to_handles() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_list", |m| {
+            m.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret(
+        "test_list",
+        r#"
+        foreign class Foo {
+            construct new() {}
+        }
+
+        var mixed = [1, "two", Foo.new()]
+        "#,
+    )
+    .expect("Interpret error");
+
+    vm.context_result(|ctx| {
+        let list = ctx.get_list("test_list", "mixed")?;
+        let handles = list.to_handles(ctx)?;
+        assert_eq!(handles.len(), 3);
+
+        let classes = [
+            ctx.get_var("test_list", "Num")?,
+            ctx.get_var("test_list", "String")?,
+            ctx.get_var("test_list", "Foo")?,
+        ];
+        for (index, handle) in handles.iter().enumerate() {
+            for (class_index, class) in classes.iter().enumerate() {
+                assert_eq!(ctx.is_instance_of(handle, class)?, index == class_index);
+            }
+        }
+
+        // Each handle can be converted on its own.
+        ctx.ensure_slots(1);
+        ToWren::put(&handles[1], ctx, 0);
+        assert_eq!(ctx.get_slot::<String>(0)?, "two");
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Numeric lists should be scanned in order, stopping at the first non-number.
+#[test]
+fn test_list_for_each_f64() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_list",
+        r#"
+        var numbers = [1, 2, 3, 4]
+        var mixed = [1, "two", 3]
+        "#,
+    )
+    .expect("Interpret error");
+
+    vm.context_result(|ctx| {
+        let numbers = ctx.get_list("test_list", "numbers")?;
+        let mut seen = vec![];
+        numbers.for_each_f64(ctx, |n| seen.push(n))?;
+        assert_eq!(seen, vec![1.0, 2.0, 3.0, 4.0]);
+
+        let mixed = ctx.get_list("test_list", "mixed")?;
+        let mut sum = 0.0;
+        let result = mixed.for_each_f64(ctx, |n| sum += n);
+        assert!(matches!(result, Err(WrenError::SlotType { .. })));
+        assert_eq!(sum, 1.0);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_list_eq_slice() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret(
+        "test_list",
+        r#"
+        var numbers = [1, 2, 3]
+        var names = ["a", "b", 3]
+        "#,
+    )
+    .expect("Interpret error");
+
+    vm.context_result(|ctx| {
+        let numbers = ctx.get_list("test_list", "numbers")?;
+        assert!(numbers.eq_slice(ctx, &[1.0, 2.0, 3.0])?);
+        assert!(!numbers.eq_slice(ctx, &[1.0, 5.0, 3.0])?);
+        assert!(!numbers.eq_slice(ctx, &[1.0, 2.0])?);
+
+        // Comparison stops before reaching the number.
+        let names = ctx.get_list("test_list", "names")?;
+        assert!(!names.eq_slice(ctx, &["a".to_string(), "c".to_string(), "d".to_string()])?);
+        let result = names.eq_slice(ctx, &["a".to_string(), "b".to_string(), "c".to_string()]);
+        assert!(matches!(result, Err(WrenError::SlotType { .. })));
+
+        Ok(())
+    })
+    .unwrap();
+}
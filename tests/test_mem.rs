@@ -1,5 +1,8 @@
 use rust_wren::prelude::*;
-use std::{ffi, mem};
+use std::{
+    ffi, mem,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 #[wren_class]
 #[derive(Debug)]
@@ -55,6 +58,54 @@ fn test_memory_safety() {
     drop(vm);
 }
 
+static FINALIZED: AtomicUsize = AtomicUsize::new(0);
+
+#[wren_class]
+struct Tracked;
+
+#[wren_methods]
+impl Tracked {
+    #[construct]
+    fn new() -> Self {
+        Tracked
+    }
+
+    #[finalize]
+    fn finalize(&mut self) {
+        FINALIZED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Test that the finalize hook is called once for every
+/// garbage collected instance.
+#[test]
+fn test_finalize_hook() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_finalize_hook", |m| {
+            m.register::<Tracked>();
+        })
+        .build();
+
+    vm.interpret("test_finalize_hook", &Tracked::wren_declaration())
+        .expect("Interpret error");
+    vm.interpret(
+        "test_finalize_hook",
+        r#"
+    Tracked.new()
+    Tracked.new()
+    var kept = Tracked.new()
+    "#,
+    )
+    .expect("Interpret error");
+
+    vm.context(|ctx| ctx.collect_garbage());
+    assert_eq!(FINALIZED.load(Ordering::SeqCst), 2);
+
+    // Remaining instances are finalized when the VM is freed.
+    drop(vm);
+    assert_eq!(FINALIZED.load(Ordering::SeqCst), 3);
+}
+
 /// There are cases where a `CString` is created in Rust, but
 /// ownership of the raw pointer is passed to Wren. The string
 /// is then deallocated via the `WrenReallocateFn` passed to
@@ -0,0 +1,51 @@
+use rust_wren::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// Each method of `Out` should send its message to its own channel.
+#[test]
+fn test_output_channels() {
+    let logs = Rc::new(RefCell::new(Vec::<String>::new()));
+    let warnings = Rc::new(RefCell::new(Vec::<String>::new()));
+    let errors = Rc::new(RefCell::new(Vec::<String>::new()));
+
+    let mut vm = {
+        let (logs, warnings, errors) = (logs.clone(), warnings.clone(), errors.clone());
+        WrenBuilder::new()
+            .with_log_fn(move |msg| logs.borrow_mut().push(msg.to_owned()))
+            .with_warn_fn(move |msg| warnings.borrow_mut().push(msg.to_owned()))
+            .with_error_out_fn(move |msg| errors.borrow_mut().push(msg.to_owned()))
+            .build()
+    };
+
+    vm.interpret(
+        "test_output",
+        r#"
+    import "rust_wren/out" for Out
+
+    Out.log("Loaded")
+    Out.warn(42)
+    Out.error("Failed: %(1 + 2)")
+    Out.log([1, 2])
+    "#,
+    )
+    .expect("Interpret failed");
+
+    assert_eq!(*logs.borrow(), vec!["Loaded", "[1, 2]"]);
+    assert_eq!(*warnings.borrow(), vec!["42"]);
+    assert_eq!(*errors.borrow(), vec!["Failed: 3"]);
+}
+
+/// The `Out` class should not exist when no channel is set.
+#[test]
+fn test_output_not_declared() {
+    let mut vm = WrenBuilder::new().build();
+
+    let result = vm.interpret(
+        "test_output",
+        r#"
+    import "rust_wren/out" for Out
+    "#,
+    );
+
+    assert!(result.is_err());
+}
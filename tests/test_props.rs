@@ -83,3 +83,301 @@ fn test_properties() {
     )
     .expect("Interpret failed");
 }
+
+#[wren_class]
+#[derive(Debug)]
+struct Pos(#[getset] f64, #[get] f64);
+
+#[wren_methods]
+impl Pos {
+    #[construct]
+    fn new(x: f64, y: f64) -> Self {
+        Self(x, y)
+    }
+}
+
+const POS: &str = r#"
+foreign class Pos {
+    foreign field0
+    foreign field0=(value)
+    foreign field1
+
+    construct new(x, y) {}
+}
+"#;
+
+#[test]
+fn test_tuple_properties() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_properties", |module| {
+            module.register::<Pos>();
+        })
+        .build();
+
+    vm.interpret("test_properties", POS).expect("Interpret failed");
+    vm.interpret("test", include_str!("test.wren"))
+        .expect("Interpret failed");
+
+    vm.interpret(
+        "test_properties",
+        r#"
+    import "test" for Test
+
+    var a = Pos.new(3, 7)
+
+    Test.assertEq(a.field0, 3, "Pos.field0")
+    Test.assertEq(a.field1, 7, "Pos.field1")
+    Test.assertEq(a.field0 = 11, 11, "Pos.field0=")
+    Test.assertEq(a.field0, 11, "Pos.field0")
+    Test.shouldFailWith("a.field1 assignment", "Pos does not implement 'field1=(_)'.") {
+        a.field1 = 13
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+#[wren_class]
+#[derive(Debug)]
+struct Sprite {
+    #[getset(name = xPos)]
+    x_pos: f64,
+    #[get(name = layerName)]
+    #[set(name = layerName)]
+    layer_name: String,
+}
+
+#[wren_methods]
+impl Sprite {
+    #[construct]
+    fn new() -> Self {
+        Self {
+            x_pos: 0.0,
+            layer_name: "background".to_owned(),
+        }
+    }
+}
+
+const SPRITE: &str = r#"
+foreign class Sprite {
+    foreign xPos
+    foreign xPos=(value)
+    foreign layerName
+    foreign layerName=(value)
+
+    construct new() {}
+}
+"#;
+
+#[test]
+fn test_renamed_properties() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_properties", |module| {
+            module.register::<Sprite>();
+        })
+        .build();
+
+    vm.interpret("test_properties", SPRITE).expect("Interpret failed");
+    vm.interpret("test", include_str!("test.wren"))
+        .expect("Interpret failed");
+
+    vm.interpret(
+        "test_properties",
+        r#"
+    import "test" for Test
+
+    var a = Sprite.new()
+
+    Test.assertEq(a.xPos, 0, "Sprite.xPos")
+    Test.assertEq(a.xPos = 5, 5, "Sprite.xPos=")
+    Test.assertEq(a.xPos, 5, "Sprite.xPos")
+
+    Test.assertEq(a.layerName, "background", "Sprite.layerName")
+    Test.assertEq(a.layerName = "foreground", "foreground", "Sprite.layerName=")
+    Test.assertEq(a.layerName, "foreground", "Sprite.layerName")
+
+    Test.shouldFailWith("a.x_pos get", "Sprite does not implement 'x_pos'.") {
+        var x = a.x_pos
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+#[wren_class]
+#[derive(Debug)]
+struct Bag {
+    #[iterable]
+    items: Vec<f64>,
+}
+
+#[wren_methods]
+impl Bag {
+    #[construct]
+    fn new(count: u32) -> Self {
+        Self {
+            items: (1..=count).map(f64::from).collect(),
+        }
+    }
+}
+
+#[test]
+fn test_iterable() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_properties", |module| {
+            module.register::<Bag>();
+        })
+        .build();
+
+    vm.interpret("test_properties", &Bag::wren_declaration())
+        .expect("Interpret failed");
+    vm.interpret("test", include_str!("test.wren"))
+        .expect("Interpret failed");
+
+    vm.interpret(
+        "test_properties",
+        r#"
+    import "test" for Test
+
+    var sum = 0
+    for (item in Bag.new(4)) {
+        sum = sum + item
+    }
+    Test.assertEq(sum, 10, "Bag sum")
+
+    var count = 0
+    for (item in Bag.new(0)) {
+        count = count + 1
+    }
+    Test.assertEq(count, 0, "Empty Bag count")
+
+    Test.assertEq(Bag.new(3).iterate(null), 0, "Bag.iterate(null)")
+    Test.assertEq(Bag.new(3).iterate(2), false, "Bag.iterate(2)")
+    Test.assertEq(Bag.new(3).iterate(1e300), false, "Bag.iterate(1e300)")
+    Test.assertEq(Bag.new(3).iteratorValue(1), 2, "Bag.iteratorValue(1)")
+    Test.shouldFail("Bag.iteratorValue out of bounds") {
+        Bag.new(3).iteratorValue(3)
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+#[wren_class]
+#[derive(Debug)]
+struct Document {
+    #[get(name = length, map = "|text| text.len() as f64")]
+    #[get(name = isBlank, map = "is_empty")]
+    #[get(name = lineCount, map = "|text| text.lines().count() as f64")]
+    #[get(name = text)]
+    text: String,
+    #[get(name = isEmpty, map = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+#[wren_methods]
+impl Document {
+    #[construct]
+    fn new(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            tags: vec![],
+        }
+    }
+}
+
+#[test]
+fn test_mapped_properties() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_properties", |module| {
+            module.register::<Document>();
+        })
+        .build();
+
+    vm.interpret("test_properties", &Document::wren_declaration())
+        .expect("Interpret failed");
+    vm.interpret("test", include_str!("test.wren"))
+        .expect("Interpret failed");
+
+    vm.interpret(
+        "test_properties",
+        r#"
+    import "test" for Test
+
+    var a = Document.new("one\ntwo\nthree")
+
+    Test.assertEq(a.length, 13, "Document.length")
+    Test.assertEq(a.lineCount, 3, "Document.lineCount")
+    Test.assertEq(a.isBlank, false, "Document.isBlank")
+    Test.assertEq(a.text, "one\ntwo\nthree", "Document.text")
+    Test.assertEq(a.isEmpty, true, "Document.isEmpty")
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+#[wren_class]
+#[derive(Debug)]
+struct Polyline {
+    #[getset_list]
+    points: Vec<f64>,
+    #[get_list(name = labels)]
+    names: Vec<String>,
+}
+
+#[wren_methods]
+impl Polyline {
+    #[construct]
+    fn new() -> Self {
+        Self {
+            points: vec![1.0, 2.0],
+            names: vec!["a".to_owned(), "b".to_owned()],
+        }
+    }
+
+    fn sum(&self) -> f64 {
+        self.points.iter().sum()
+    }
+}
+
+#[test]
+fn test_list_properties() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_properties", |module| {
+            module.register::<Polyline>();
+        })
+        .build();
+
+    vm.interpret("test_properties", &Polyline::wren_declaration())
+        .expect("Interpret failed");
+    vm.interpret("test", include_str!("test.wren"))
+        .expect("Interpret failed");
+
+    vm.interpret(
+        "test_properties",
+        r#"
+    import "test" for Test
+
+    var line = Polyline.new()
+    Test.assertEq(line.points.count, 2, "Polyline.points.count")
+    Test.assertEq(line.points[1], 2, "Polyline.points[1]")
+    Test.assertEq(line.labels.join(","), "a,b", "Polyline.labels")
+
+    // Lists are copies, so modifying one doesn't change the field.
+    line.points.add(3)
+    Test.assertEq(line.points.count, 2, "Polyline.points.count after add")
+
+    line.points = [4, 5, 6]
+    Test.assertEq(line.points.count, 3, "Polyline.points.count after set")
+    Test.assertEq(line.sum(), 15, "Polyline.sum()")
+
+    Test.shouldFail("Polyline.points = non-list") {
+        line.points = 1
+    }
+    Test.shouldFail("Polyline.points = list of strings") {
+        line.points = ["x"]
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+}
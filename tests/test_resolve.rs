@@ -1,7 +1,9 @@
 use rust_wren::{
-    module::{FileModuleLoader, UnitModuleResolver},
+    module::{FileModuleLoader, InMemoryModuleLoader, ModuleResolver, PathModuleResolver, UnitModuleResolver},
     prelude::*,
+    WrenError,
 };
+use std::{cell::Cell, rc::Rc};
 
 #[test]
 fn test_module_resolve() {
@@ -44,3 +46,224 @@ fn test_module_load() {
     )
     .expect("Interpret failed");
 }
+
+#[test]
+fn test_module_load_in_memory() {
+    let mut vm = WrenBuilder::new()
+        .with_module_resolver(UnitModuleResolver::default())
+        .with_module_loader(
+            InMemoryModuleLoader::new()
+                .with_module("math", "class Vec2 {}\nclass Vec3 {}")
+                .with_module("shapes", "import \"math\" for Vec2\nclass Rect {}"),
+        )
+        .build();
+
+    vm.interpret(
+        "module_2",
+        r#"
+    import "math" for Vec2, Vec3
+    import "shapes" for Rect
+    System.print("%(Vec2) %(Vec3) %(Rect)")
+    "#,
+    )
+    .expect("Interpret failed");
+
+    let result = vm.interpret(
+        "module_3",
+        r#"
+    import "missing" for Foo
+    "#,
+    );
+    assert!(result.is_err());
+}
+
+/// Loaded source containing a null byte should fail the import, instead of aborting the process.
+#[test]
+fn test_module_load_null_byte() {
+    let mut vm = WrenBuilder::new()
+        .with_module_resolver(UnitModuleResolver::default())
+        .with_module_loader(InMemoryModuleLoader::new().with_module("broken", "class Foo {}\0"))
+        .build();
+
+    let result = vm.interpret(
+        "module_1",
+        r#"
+    import "broken" for Foo
+    "#,
+    );
+    assert!(matches!(result, Err(WrenError::RuntimeError { .. })));
+}
+
+#[test]
+fn test_path_resolve() {
+    let mut resolver = PathModuleResolver::new();
+
+    // Same directory
+    assert_eq!(
+        resolver.resolve("game/player", "./input"),
+        Some("game/input".to_string())
+    );
+    assert_eq!(
+        resolver.resolve("game/player", "./sub/mod"),
+        Some("game/sub/mod".to_string())
+    );
+    assert_eq!(resolver.resolve("main", "./input"), Some("input".to_string()));
+
+    // Parent directory
+    assert_eq!(resolver.resolve("game/player", "../util"), Some("util".to_string()));
+    assert_eq!(
+        resolver.resolve("game/ai/path", "../../util/./math"),
+        Some("util/math".to_string())
+    );
+    assert_eq!(resolver.resolve("main", "../util"), Some("../util".to_string()));
+
+    // Absolute
+    assert_eq!(resolver.resolve("game/player", "math"), Some("math".to_string()));
+    assert_eq!(
+        resolver.resolve("game/player", "lib/math"),
+        Some("lib/math".to_string())
+    );
+}
+
+#[test]
+fn test_path_resolve_import() {
+    let mut vm = WrenBuilder::new()
+        .with_module_resolver(PathModuleResolver::new())
+        .with_module_loader(
+            InMemoryModuleLoader::new()
+                .with_module("game/player", "import \"./input\" for Input\nclass Player {}")
+                .with_module("game/input", "import \"../util\" for Util\nclass Input {}")
+                .with_module("util", "class Util {}"),
+        )
+        .build();
+
+    vm.interpret(
+        "main",
+        r#"
+    import "game/player" for Player
+    import "game/input" for Input
+    import "util" for Util
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+/// Resolver that counts how often it is called.
+struct CountingResolver(Rc<Cell<usize>>);
+
+impl ModuleResolver for CountingResolver {
+    fn resolve(&mut self, _importer: &str, module: &str) -> Option<String> {
+        self.0.set(self.0.get() + 1);
+        Some(module.to_string())
+    }
+}
+
+#[test]
+fn test_resolve_cache() {
+    let count = Rc::new(Cell::new(0));
+    let mut vm = WrenBuilder::new()
+        .with_module_resolver(CountingResolver(count.clone()))
+        .with_resolve_cache(true)
+        .build();
+
+    vm.interpret("module_1", "class Foo {}").expect("Interpret failed");
+
+    // Each block scoped import runs again in every iteration.
+    let source = r#"
+    for (i in 0...3) {
+      import "module_1" for Foo
+    }
+    "#;
+    vm.interpret("module_2", source).expect("Interpret failed");
+    assert_eq!(count.get(), 1);
+
+    // A different importer is resolved separately.
+    vm.interpret("module_3", source).expect("Interpret failed");
+    assert_eq!(count.get(), 2);
+
+    vm.clear_resolve_cache();
+    vm.interpret("module_2", source).expect("Interpret failed");
+    assert_eq!(count.get(), 3);
+}
+
+/// Modules provided by the loader should be told apart from interpreted ones.
+#[test]
+fn test_module_loaded_via_loader() {
+    let mut vm = WrenBuilder::new()
+        .with_module_resolver(UnitModuleResolver::default())
+        .with_module_loader(InMemoryModuleLoader::new().with_module("math", "class Vec2 {}"))
+        .build();
+
+    vm.interpret("scripts", "class Player {}").expect("Interpret failed");
+    vm.interpret(
+        "main",
+        r#"
+    import "math" for Vec2
+    import "scripts" for Player
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context(|ctx| {
+        assert!(ctx.is_module_loaded_via_loader("math"));
+        assert!(!ctx.is_module_loaded_via_loader("scripts"));
+        assert!(!ctx.is_module_loaded_via_loader("main"));
+        assert!(!ctx.is_module_loaded_via_loader("missing"));
+    });
+}
+
+/// Resolver that rejects imports of forbidden modules.
+struct SandboxResolver;
+
+impl ModuleResolver for SandboxResolver {
+    fn resolve(&mut self, _importer: &str, module: &str) -> Option<String> {
+        Some(module.to_string())
+    }
+
+    fn try_resolve(&mut self, _importer: &str, module: &str) -> Result<Option<String>, String> {
+        match module {
+            "os" => Err(format!("import of '{}' is not allowed in sandboxed mode", module)),
+            "missing" => Ok(None),
+            _ => Ok(Some(module.to_string())),
+        }
+    }
+}
+
+#[test]
+fn test_module_resolve_rejected() {
+    let mut vm = WrenBuilder::new().with_module_resolver(SandboxResolver).build();
+
+    let result = vm.interpret(
+        "sandbox",
+        r#"
+    import "os" for Process
+    "#,
+    );
+
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => match foreign.downcast_ref::<WrenError>() {
+            Some(WrenError::ImportRejected {
+                importer,
+                module,
+                reason,
+            }) => {
+                assert_eq!(importer, "sandbox");
+                assert_eq!(module, "os");
+                assert_eq!(reason, "import of 'os' is not allowed in sandboxed mode");
+            }
+            err => panic!("Unexpected foreign error {:?}", err),
+        },
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    // Modules that can't be resolved are not found, rather than rejected.
+    let result = vm.interpret(
+        "sandbox",
+        r#"
+    import "missing" for Value
+    "#,
+    );
+    assert!(matches!(result, Err(WrenError::RuntimeError { foreign: None, .. })));
+}
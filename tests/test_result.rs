@@ -78,7 +78,10 @@ fn test_compile_error() {
 
     match result {
         Err(WrenError::CompileError(errors)) => {
-            for WrenCompileError { module, message, line } in errors {
+            for WrenCompileError {
+                module, message, line, ..
+            } in errors
+            {
                 eprintln!("[Rust Result] Compile Error [{} line {}] {}", module, line, message);
             }
         }
@@ -86,6 +89,7 @@ fn test_compile_error() {
             message,
             foreign,
             stack,
+            ..
         }) => {
             let mut msg = String::new();
 
@@ -119,3 +123,41 @@ fn test_compile_error() {
         Err(err) => eprintln!("{}", err),
     }
 }
+
+/// Errors created with `foreign_error!` should show up as a foreign frame in the stack trace.
+#[test]
+fn test_annotated_foreign_error() {
+    use foo::*;
+    let mut vm = WrenBuilder::new()
+        .with_module("depend", |module| module.register::<Foo>())
+        .build();
+
+    vm.interpret(
+        "depend",
+        r#"
+    foreign class Foo {
+        construct new() {}
+        foreign static eatme()
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    match vm.interpret("depend", "Foo.eatme()") {
+        Err(WrenError::RuntimeError { foreign, stack, .. }) => {
+            assert!(foreign.unwrap().is::<SomeError>());
+
+            let frame = &stack[0];
+            assert!(frame.is_foreign);
+            assert_eq!(frame.module, "tests/test_result.rs");
+            assert_eq!(frame.function, "(foreign)");
+            assert!(frame.line > 0);
+
+            // Wren's own frame for the script follows.
+            assert!(stack[1..]
+                .iter()
+                .any(|frame| !frame.is_foreign && frame.module == "depend"));
+        }
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "send")]
+use rust_wren::{prelude::*, SendWrenVm};
+use std::{sync::mpsc::channel, thread};
+
+/// Should move a VM to a worker thread and back again.
+#[test]
+fn test_send_vm() {
+    #[wren_class]
+    struct Counter(f64);
+
+    #[wren_methods]
+    impl Counter {
+        #[construct]
+        fn new() -> Self {
+            Counter(0.0)
+        }
+
+        fn add(&mut self, value: f64) -> f64 {
+            self.0 += value;
+            self.0
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_send", |m| {
+            m.register::<Counter>();
+        })
+        .build();
+
+    vm.interpret("test_send", &Counter::wren_declaration())
+        .expect("Interpret failed");
+    vm.interpret("test_send", "var counter = Counter.new()")
+        .expect("Interpret failed");
+
+    let (vm_tx, vm_rx) = channel::<SendWrenVm>();
+    let (back_tx, back_rx) = channel::<SendWrenVm>();
+
+    let worker = thread::spawn(move || {
+        let mut vm = vm_rx.recv().unwrap();
+        vm.interpret("test_send", "counter.add(3)").expect("Interpret failed");
+        back_tx.send(vm).unwrap();
+    });
+
+    vm_tx.send(unsafe { SendWrenVm::new(vm) }).unwrap();
+    let mut vm = back_rx.recv().unwrap().into_inner();
+    worker.join().unwrap();
+
+    vm.interpret(
+        "test_send",
+        r#"
+    if (counter.add(4) != 7) {
+        Fiber.abort("Unexpected counter value")
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+}
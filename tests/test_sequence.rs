@@ -0,0 +1,56 @@
+use rust_wren::{prelude::*, sequence::WrenSequence};
+use std::{cell::Cell, rc::Rc};
+
+const API: &str = r#"
+class Api {
+    static sumUntil(seq, limit) {
+        var sum = 0
+        for (value in seq) {
+            if (value > limit) break
+            sum = sum + value
+        }
+        return sum
+    }
+
+    static names(seq) { seq.map {|name| name.count }.toList }
+}
+"#;
+
+/// Wren should only pull as many items from a lazy range as it iterates.
+#[test]
+fn test_lazy_range() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret("test_sequence", API).expect("Interpret failed");
+
+    let pulled = Rc::new(Cell::new(0));
+
+    vm.context(|ctx| {
+        let pulled_ref = pulled.clone();
+        let range = WrenSequence::new((1..).map(move |n| {
+            pulled_ref.set(pulled_ref.get() + 1);
+            n as f64
+        }));
+
+        let sum_until = ctx.make_call_ref("test_sequence", "Api", "sumUntil(_,_)").unwrap();
+        let result = sum_until.call::<_, f64>(ctx, (range, 4.0)).unwrap();
+        assert_eq!(result, 10.0);
+    });
+
+    // The loop stops on the first value over the limit.
+    assert_eq!(pulled.get(), 5);
+}
+
+/// Lazy sequences should support the methods of Wren's `Sequence`.
+#[test]
+fn test_sequence_methods() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret("test_sequence", API).expect("Interpret failed");
+
+    vm.context(|ctx| {
+        let names = WrenSequence::new(vec!["a", "bb", "ccc"]);
+
+        let counts = ctx.make_call_ref("test_sequence", "Api", "names(_)").unwrap();
+        let list = counts.call::<_, WrenList>(ctx, names).unwrap();
+        assert_eq!(list.to_vec::<f64>(ctx).unwrap(), vec![1.0, 2.0, 3.0]);
+    });
+}
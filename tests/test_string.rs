@@ -0,0 +1,39 @@
+use rust_wren::{prelude::*, string::WrenString, WrenError};
+
+/// String handles should keep their contents alive across garbage collection.
+#[test]
+fn test_wren_string() {
+    let mut vm = WrenBuilder::new().build();
+    vm.interpret(
+        "test_string",
+        r#"
+    var text = "lorem ipsum " * 1000
+    var count = 0
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        let text = ctx.get_var_as::<WrenString>("test_string", "text")?;
+
+        // The module variable no longer refers to the string, only the handle does.
+        ctx.set_var("test_string", "text", ())?;
+        ctx.collect_garbage();
+
+        let contents = text.as_str(ctx)?;
+        assert_eq!(contents.len(), 12 * 1000);
+        assert!(contents.starts_with("lorem ipsum lorem"));
+
+        // Handle can be passed back to Wren without copying.
+        ctx.set_var("test_string", "text", &text)?;
+        assert_eq!(ctx.get_var_as::<String>("test_string", "text")?.len(), 12 * 1000);
+
+        match ctx.get_var_as::<WrenString>("test_string", "count") {
+            Err(WrenError::SlotType { .. }) => {}
+            result => panic!("Unexpected result {:?}", result),
+        }
+
+        Ok(())
+    })
+    .unwrap();
+}
@@ -1,9 +1,22 @@
-use rust_wren::prelude::*;
+use rust_wren::{
+    handle::WrenRef,
+    prelude::*,
+    value::{Either, Truthy, Typed, WrenNull},
+    WrenContext, WrenError,
+};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
 
 #[wren_class]
 #[derive(Debug)]
 struct Foo(f64);
 
+#[derive(Debug, Clone, Copy, PartialEq, WrenNumber)]
+struct EntityId(u32);
+
 #[wren_methods]
 impl Foo {
     #[construct]
@@ -53,6 +66,38 @@ impl Foo {
         }
     }
 
+    fn cow(&self, shout: bool, s: &str) -> Cow<'static, str> {
+        if shout {
+            Cow::Owned(s.to_uppercase())
+        } else {
+            Cow::Borrowed("quiet")
+        }
+    }
+
+    fn boxed(&self, s: Box<str>) -> Box<str> {
+        format!("{} boxed", s).into_boxed_str()
+    }
+
+    fn narrow(&self, val: f32) -> f32 {
+        val
+    }
+
+    fn next_entity(&self, id: EntityId) -> EntityId {
+        EntityId(id.0 + 1)
+    }
+
+    fn double_duration(&self, duration: Duration) -> Duration {
+        duration * 2
+    }
+
+    fn truthy(&self, val: Truthy) -> bool {
+        val.into()
+    }
+
+    fn strict(&self, val: bool) -> bool {
+        val
+    }
+
     fn multi_borrow(&self, foo: &WrenCell<Foo>) {
         // Should fail when both self and foo are the same foreign value
         let _eat_me = foo.borrow_mut();
@@ -68,6 +113,13 @@ foreign class Foo {
     foreign string(s)
     foreign str(s)
     foreign optional(val)
+    foreign cow(shout, s)
+    foreign boxed(s)
+    foreign narrow(val)
+    foreign next_entity(id)
+    foreign double_duration(duration)
+    foreign truthy(val)
+    foreign strict(val)
     foreign multi_borrow(foo)
 }
 "#;
@@ -138,6 +190,44 @@ fn test_wren_cell_incorrect_type() {
     .expect("Interpret failed");
 }
 
+#[test]
+fn test_f32_overflow() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_value", |m| {
+            m.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret("test_value", FOO).expect("Interpret failed");
+    vm.interpret(
+        "test_value",
+        r#"
+    var narrowed = Foo.new(1).narrow(0.1)
+    if (narrowed == 0.1 || (narrowed - 0.1).abs > 0.0001) {
+        Fiber.abort("Unexpected narrowed number %(narrowed)")
+    }
+    "#,
+    )
+    .expect("Ordinary precision loss must be permitted");
+
+    let result = vm.interpret("test_value", &format!("Foo.new(1).narrow({:e})", f64::MAX));
+
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => match foreign.downcast_ref::<WrenError>() {
+            Some(WrenError::ForeignCall { cause, .. }) => match &**cause {
+                WrenError::GetArg { cause, .. } => {
+                    assert!(matches!(**cause, WrenError::NumberRange { target: "f32", .. }))
+                }
+                err => panic!("Unexpected cause {:?}", err),
+            },
+            err => panic!("Unexpected foreign error {:?}", err),
+        },
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
 #[test]
 fn test_string() {
     let mut vm = WrenBuilder::new()
@@ -164,6 +254,104 @@ fn test_string() {
     .expect("Interpret failed");
 }
 
+#[test]
+fn test_cow_and_boxed_str() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_value", |m| {
+            m.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret("test_value", FOO).expect("Interpret failed");
+    vm.interpret(
+        "test_value",
+        r#"
+    var foo = Foo.new(1)
+
+    var owned = foo.cow(true, "loud")
+    if (owned != "LOUD") {
+        Fiber.abort("Unexpected owned Cow string \"%(owned)\"")
+    }
+
+    var borrowed = foo.cow(false, "loud")
+    if (borrowed != "quiet") {
+        Fiber.abort("Unexpected borrowed Cow string \"%(borrowed)\"")
+    }
+
+    var boxed = foo.boxed("string")
+    if (boxed != "string boxed") {
+        Fiber.abort("Unexpected boxed string \"%(boxed)\"")
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+#[test]
+fn test_number_newtype() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_value", |m| {
+            m.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret("test_value", FOO).expect("Interpret failed");
+    vm.interpret(
+        "test_value",
+        r#"
+    var foo = Foo.new(1)
+    var id = foo.next_entity(41)
+    if (id != 42) {
+        Fiber.abort("Unexpected entity id %(id)")
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    let result = vm.interpret("test_value", r#"foo.next_entity("41")"#);
+    assert!(result.is_err());
+
+    vm.context_result(|ctx| {
+        ctx.get_var("test_value", "id")?;
+        assert_eq!(ctx.get_slot::<EntityId>(0)?, EntityId(42));
+
+        ToWren::put(EntityId(7), ctx, 0);
+        assert_eq!(ctx.get_slot::<u32>(0)?, 7);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Truthy arguments should follow Wren's rules, while plain bools stay strict.
+#[test]
+fn test_truthy() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_value", |m| {
+            m.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret("test_value", FOO).expect("Interpret failed");
+    vm.interpret(
+        "test_value",
+        r#"
+    var foo = Foo.new(1)
+    var cases = [[0, true], [null, false], ["", true], ["text", true], [false, false], [true, true]]
+    for (case in cases) {
+        var result = foo.truthy(case[0])
+        if (result != case[1]) {
+            Fiber.abort("Unexpected truthiness %(result) for %(case[0])")
+        }
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    let result = vm.interpret("test_value", "foo.strict(0)");
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_unicode() {
     let mut vm = WrenBuilder::new()
@@ -235,3 +423,331 @@ fn test_multiple_borrow() {
     )
     .expect("Interpret failed");
 }
+
+#[test]
+fn test_duration() {
+    let mut vm = WrenBuilder::new()
+        .with_module("test_value", |m| {
+            m.register::<Foo>();
+        })
+        .build();
+
+    vm.interpret("test_value", FOO).expect("Interpret failed");
+    vm.interpret(
+        "test_value",
+        r#"
+    var doubled = Foo.new(1).double_duration(1.25)
+    if (doubled != 2.5) {
+        Fiber.abort("Unexpected duration %(doubled)")
+    }
+
+    var seconds = null
+    "#,
+    )
+    .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        ctx.set_var("test_value", "seconds", Duration::from_millis(1500))?;
+        assert_eq!(ctx.get_var_as::<f64>("test_value", "seconds")?, 1.5);
+        assert_eq!(
+            ctx.get_var_as::<Duration>("test_value", "seconds")?,
+            Duration::from_secs_f64(1.5)
+        );
+        Ok(())
+    })
+    .unwrap();
+
+    let result = vm.interpret("test_value", "Foo.new(1).double_duration(-1)");
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => match foreign.downcast_ref::<WrenError>() {
+            Some(WrenError::ForeignCall { cause, .. }) => match &**cause {
+                WrenError::GetArg { cause, .. } => {
+                    assert!(matches!(**cause, WrenError::NumberRange { target: "Duration", .. }))
+                }
+                err => panic!("Unexpected cause {:?}", err),
+            },
+            err => panic!("Unexpected foreign error {:?}", err),
+        },
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+/// Either should accept a foreign object or a plain value in the same argument.
+#[test]
+fn test_either() {
+    #[wren_class]
+    struct Vec2 {
+        x: f64,
+        y: f64,
+    }
+
+    #[wren_methods]
+    impl Vec2 {
+        #[construct]
+        fn new(x: f64, y: f64) -> Self {
+            Vec2 { x, y }
+        }
+
+        fn sum(#[ctx] ctx: &mut WrenContext, value: Either<&WrenCell<Vec2>, WrenList>) -> rust_wren::Result<f64> {
+            match value {
+                Either::Left(cell) => {
+                    let vec2 = cell.borrow();
+                    Ok(vec2.x + vec2.y)
+                }
+                Either::Right(list) => {
+                    let items = list.to_vec::<f64>(ctx).map_err(|err| foreign_error!(err))?;
+                    Ok(items.iter().sum())
+                }
+            }
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_value", |m| {
+            m.register::<Vec2>();
+        })
+        .build();
+
+    vm.interpret(
+        "test_value",
+        r#"
+    foreign class Vec2 {
+        construct new(x, y) {}
+        foreign static sum(value)
+    }
+
+    if (Vec2.sum(Vec2.new(1, 2)) != 3 || Vec2.sum([3, 4]) != 7) {
+        Fiber.abort("Unexpected sums")
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    // Neither type matches, so the error of the right type is reported.
+    let result = vm.interpret("test_value", "Vec2.sum(true)");
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => match foreign.downcast_ref::<WrenError>() {
+            Some(WrenError::ForeignCall { cause, .. }) => match &**cause {
+                WrenError::GetArg { cause, .. } => {
+                    assert!(matches!(
+                        **cause,
+                        WrenError::SlotType {
+                            expected: rust_wren::WrenType::List,
+                            ..
+                        }
+                    ))
+                }
+                err => panic!("Unexpected cause {:?}", err),
+            },
+            err => panic!("Unexpected foreign error {:?}", err),
+        },
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+/// Wren strings can contain null bytes, which must not truncate the string.
+#[test]
+fn test_embedded_null() {
+    let mut vm = WrenBuilder::new().build();
+
+    vm.interpret("test_value", r#"var text = "ab\0cd""#)
+        .expect("Interpret failed");
+
+    vm.context_result(|ctx| {
+        assert_eq!(ctx.get_var_as::<&str>("test_value", "text")?, "ab\0cd");
+        assert_eq!(ctx.slot_string_len(0)?, 5);
+
+        assert_eq!(ctx.get_var_as::<String>("test_value", "text")?, "ab\0cd");
+
+        ToWren::put("ab\0cd", ctx, 0);
+        assert_eq!(ctx.get_slot::<String>(0)?, "ab\0cd");
+        assert_eq!(ctx.slot_string_len(0)?, 5);
+
+        ToWren::put(1.0, ctx, 0);
+        assert!(matches!(ctx.slot_string_len(0), Err(WrenError::SlotType { .. })));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Rust maps should be created as Wren maps.
+#[test]
+fn test_map_to_wren() {
+    #[wren_class]
+    #[derive(Default)]
+    struct Maps;
+
+    #[wren_methods]
+    impl Maps {
+        fn scores() -> HashMap<String, f64> {
+            let mut scores = HashMap::new();
+            scores.insert("alice".to_owned(), 3.0);
+            scores.insert("bob".to_owned(), 5.0);
+            scores
+        }
+
+        fn names() -> BTreeMap<i32, &'static str> {
+            vec![(1, "one"), (2, "two")].into_iter().collect()
+        }
+
+        fn nested() -> HashMap<&'static str, HashMap<&'static str, bool>> {
+            let mut inner = HashMap::new();
+            inner.insert("enabled", true);
+
+            let mut outer = HashMap::new();
+            outer.insert("options", inner);
+            outer
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_value", |m| {
+            m.register::<Maps>();
+        })
+        .with_bootstrap("test_value", Maps::wren_declaration())
+        .build();
+
+    vm.interpret(
+        "test_value",
+        r#"
+    var scores = Maps.scores()
+    if (!(scores is Map) || scores.count != 2) {
+        Fiber.abort("Expected a map of two scores")
+    }
+    if (scores["alice"] != 3 || scores["bob"] != 5) {
+        Fiber.abort("Unexpected scores %(scores)")
+    }
+
+    var names = Maps.names()
+    if (names[1] != "one" || names[2] != "two") {
+        Fiber.abort("Unexpected names %(names)")
+    }
+
+    if (Maps.nested()["options"]["enabled"] != true) {
+        Fiber.abort("Unexpected nested map")
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+/// Typed should report the dynamic type of any argument.
+#[test]
+fn test_typed() {
+    #[wren_class]
+    struct Inspect;
+
+    #[wren_methods]
+    impl Inspect {
+        #[construct]
+        fn new() -> Self {
+            Inspect
+        }
+
+        fn kind(arg: Typed<WrenRef<'_>>) -> String {
+            format!("{:?}", arg.ty)
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_value", |m| {
+            m.register::<Inspect>();
+        })
+        .build();
+
+    vm.interpret(
+        "test_value",
+        r#"
+    foreign class Inspect {
+        construct new() {}
+        foreign static kind(arg)
+    }
+
+    var expected = [
+        [true, "Bool"],
+        [1.5, "Number"],
+        [Inspect.new(), "Foreign"],
+        [[1, 2], "List"],
+        [{"a": 1}, "Map"],
+        [null, "Null"],
+        ["str", "String"],
+        [Fn.new {}, "Unknown"],
+    ]
+
+    for (pair in expected) {
+        var kind = Inspect.kind(pair[0])
+        if (kind != pair[1]) {
+            Fiber.abort("Expected %(pair[1]) for %(pair[0]), got %(kind)")
+        }
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+}
+
+/// WrenNull should only accept an explicit null.
+#[test]
+fn test_wren_null() {
+    #[wren_class]
+    struct Strict;
+
+    #[wren_methods]
+    impl Strict {
+        #[construct]
+        fn new() -> Self {
+            Strict
+        }
+
+        fn clear(_value: WrenNull) -> WrenNull {
+            WrenNull
+        }
+    }
+
+    let mut vm = WrenBuilder::new()
+        .with_module("test_value", |m| {
+            m.register::<Strict>();
+        })
+        .build();
+
+    vm.interpret(
+        "test_value",
+        r#"
+    foreign class Strict {
+        construct new() {}
+        foreign static clear(value)
+    }
+
+    if (Strict.clear(null) != null) {
+        Fiber.abort("Unexpected result")
+    }
+    "#,
+    )
+    .expect("Interpret failed");
+
+    let result = vm.interpret("test_value", "Strict.clear(1)");
+    match result {
+        Err(WrenError::RuntimeError {
+            foreign: Some(foreign), ..
+        }) => match foreign.downcast_ref::<WrenError>() {
+            Some(WrenError::ForeignCall { cause, .. }) => match &**cause {
+                WrenError::GetArg { cause, .. } => {
+                    assert!(matches!(
+                        **cause,
+                        WrenError::SlotType {
+                            expected: rust_wren::WrenType::Null,
+                            actual: rust_wren::WrenType::Number,
+                        }
+                    ));
+                }
+                err => panic!("Unexpected cause {:?}", err),
+            },
+            err => panic!("Unexpected foreign error {:?}", err),
+        },
+        result => panic!("Unexpected result {:?}", result),
+    }
+}